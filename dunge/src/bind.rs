@@ -2,10 +2,24 @@
 
 use {
     crate::{
-        group::BoundTexture, shader::Shader, state::State, texture::Sampler, uniform::Uniform,
+        context::Context,
+        group::BoundTexture,
+        shader::Shader,
+        state::State,
+        texture::Sampler,
+        uniform::{DynamicUniform, Uniform},
         Group,
     },
-    std::{any::TypeId, error, fmt, marker::PhantomData, sync::Arc},
+    std::{
+        any::TypeId,
+        error, fmt, iter,
+        marker::PhantomData,
+        ops::Deref,
+        sync::{
+            atomic::{self, AtomicU32},
+            Arc,
+        },
+    },
     wgpu::{
         BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindingResource, Device,
     },
@@ -16,12 +30,20 @@ pub trait Visit: Group {
     fn visit<'a>(&'a self, visitor: &mut Visitor<'a>);
 }
 
-pub struct Visitor<'a>(Vec<BindGroupEntry<'a>>);
+pub struct Visitor<'a> {
+    entries: Vec<BindGroupEntry<'a>>,
+    offsets: Vec<Arc<AtomicU32>>,
+}
 
 impl<'a> Visitor<'a> {
     fn push(&mut self, resource: BindingResource<'a>) {
-        let binding = self.0.len() as u32;
-        self.0.push(BindGroupEntry { binding, resource });
+        let binding = self.entries.len() as u32;
+        self.entries.push(BindGroupEntry { binding, resource });
+    }
+
+    fn push_dynamic(&mut self, resource: BindingResource<'a>, offset: Arc<AtomicU32>) {
+        self.push(resource);
+        self.offsets.push(offset);
     }
 }
 
@@ -29,6 +51,16 @@ pub trait VisitMember<'a> {
     fn visit_member(self, visitor: &mut Visitor<'a>);
 }
 
+impl<'a, M> VisitMember<'a> for &'a &M
+where
+    &'a M: VisitMember<'a>,
+{
+    fn visit_member(self, visitor: &mut Visitor<'a>) {
+        let member: &'a M = self;
+        member.visit_member(visitor);
+    }
+}
+
 impl<'a, V> VisitMember<'a> for &'a Uniform<V> {
     fn visit_member(self, visitor: &mut Visitor<'a>) {
         let binding = self.buffer().as_entire_buffer_binding();
@@ -36,7 +68,13 @@ impl<'a, V> VisitMember<'a> for &'a Uniform<V> {
     }
 }
 
-impl<'a> VisitMember<'a> for BoundTexture<'a> {
+impl<'a, V> VisitMember<'a> for &'a DynamicUniform<V> {
+    fn visit_member(self, visitor: &mut Visitor<'a>) {
+        visitor.push_dynamic(BindingResource::Buffer(self.binding()), self.offset());
+    }
+}
+
+impl<'a> VisitMember<'a> for &'a BoundTexture<'_> {
     fn visit_member(self, visitor: &mut Visitor<'a>) {
         visitor.push(BindingResource::TextureView(self.0.view()));
     }
@@ -48,15 +86,22 @@ impl<'a> VisitMember<'a> for &'a Sampler {
     }
 }
 
-fn visit<G>(group: &G) -> Vec<BindGroupEntry>
+fn visit<G>(group: &G) -> Visitor<'_>
 where
     G: Visit,
 {
-    let mut visitor = Visitor(Vec::with_capacity(G::N_MEMBERS));
+    let mut visitor = Visitor {
+        entries: Vec::with_capacity(G::N_MEMBERS),
+        offsets: vec![],
+    };
+
     group.visit(&mut visitor);
-    visitor.0
+    visitor
 }
 
+/// The group handler.
+///
+/// It's used to [rebind](UniqueBinding::rebind) a group in the binding.
 pub struct GroupHandler<P> {
     shader_id: usize,
     id: usize,
@@ -76,31 +121,98 @@ impl fmt::Display for ForeignShader {
 impl error::Error for ForeignShader {}
 
 pub trait Binding {
-    fn binding(&self) -> Bind;
+    fn binding(&self) -> Bind<'_>;
 }
 
 pub struct Bind<'a> {
     pub(crate) shader_id: usize,
-    pub(crate) groups: &'a [BindGroup],
+    pub(crate) groups: &'a [Bound],
+}
+
+/// A bind group with the dynamic offsets of its members.
+pub(crate) struct Bound {
+    group: BindGroup,
+    offsets: Box<[Arc<AtomicU32>]>,
+}
+
+impl Bound {
+    fn new(group: BindGroup, offsets: Vec<Arc<AtomicU32>>) -> Self {
+        assert!(
+            offsets.len() <= Offsets::MAX,
+            "too many dynamic uniforms in the group, the maximum is {}",
+            Offsets::MAX,
+        );
+
+        Self {
+            group,
+            offsets: Box::from(offsets),
+        }
+    }
+
+    pub fn group(&self) -> &BindGroup {
+        &self.group
+    }
+
+    /// Checks if the group has dynamic offsets.
+    pub fn is_dynamic(&self) -> bool {
+        !self.offsets.is_empty()
+    }
+
+    pub fn offsets(&self) -> Offsets {
+        let mut vals = [0; Offsets::MAX];
+        for (val, offset) in iter::zip(&mut vals, &self.offsets) {
+            *val = offset.load(atomic::Ordering::Relaxed);
+        }
+
+        Offsets {
+            vals,
+            len: self.offsets.len(),
+        }
+    }
+}
+
+/// The current dynamic offsets of a bind group.
+///
+/// They're read on each bind, so the offsets are stored inline to not allocate.
+pub(crate) struct Offsets {
+    vals: [u32; Self::MAX],
+    len: usize,
+}
+
+impl Offsets {
+    /// The number of dynamic uniforms per pipeline supported by all devices.
+    const MAX: usize = 8;
+}
+
+impl Deref for Offsets {
+    type Target = [u32];
+
+    fn deref(&self) -> &Self::Target {
+        &self.vals[..self.len]
+    }
 }
 
 #[derive(Clone)]
 pub struct SharedBinding {
     shader_id: usize,
-    groups: Arc<[BindGroup]>,
+    groups: Arc<[Bound]>,
 }
 
 impl SharedBinding {
-    fn new(shader_id: usize, groups: Vec<BindGroup>) -> Self {
+    fn new(shader_id: usize, groups: Vec<Bound>) -> Self {
         Self {
             shader_id,
             groups: Arc::from(groups),
         }
     }
+
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.groups, &other.groups)
+    }
 }
 
 impl Binding for SharedBinding {
-    fn binding(&self) -> Bind {
+    fn binding(&self) -> Bind<'_> {
         Bind {
             shader_id: self.shader_id,
             groups: &self.groups,
@@ -121,19 +233,24 @@ where
         return Err(ForeignShader);
     }
 
-    let entries = visit(group);
+    let visitor = visit(group);
     let desc = BindGroupDescriptor {
         label: None,
         layout: &handler.layout,
-        entries: &entries,
+        entries: &visitor.entries,
     };
 
     let new = state.device().create_bind_group(&desc);
     let groups = uni.groups();
-    groups[handler.id] = new;
+    groups[handler.id] = Bound::new(new, visitor.offsets);
     Ok(())
 }
 
+/// The binding which can be updated.
+///
+/// Can be created using the binder's [`into_binding`](Binder::into_binding) function.
+/// Call the [`shared`](UniqueBinding::shared) function to get a binding
+/// which can be cloned, but can't be updated anymore.
 pub struct UniqueBinding(SharedBinding);
 
 impl UniqueBinding {
@@ -141,13 +258,39 @@ impl UniqueBinding {
         self.0
     }
 
-    fn groups(&mut self) -> &mut [BindGroup] {
+    /// Replaces a group in the binding with a new one.
+    ///
+    /// The `handler` is returned by the binder's [`add`](Binder::add) function
+    /// when the group is added, and it identifies the group to replace.
+    /// Only this group is recreated, other groups of the binding are kept.
+    ///
+    /// Any member can be swapped with another object of the same type,
+    /// for example, a texture recreated after a window resize.
+    /// To change data of a [uniform](crate::uniform::Uniform) there is no need
+    /// to rebind it, use its `update` function instead. If the group type
+    /// itself changes, the binding must be rebuilt with a new [binder](Binder).
+    ///
+    /// # Errors
+    /// Returns the [`ForeignShader`] error if the handler belongs to another shader.
+    pub fn rebind<G>(
+        &mut self,
+        cx: &Context,
+        handler: &GroupHandler<G::Projection>,
+        group: &G,
+    ) -> Result<(), ForeignShader>
+    where
+        G: Visit,
+    {
+        update(cx.state(), self, handler, group)
+    }
+
+    fn groups(&mut self) -> &mut [Bound] {
         Arc::get_mut(&mut self.0.groups).expect("uniqueness is guaranteed by the type")
     }
 }
 
 impl Binding for UniqueBinding {
-    fn binding(&self) -> Bind {
+    fn binding(&self) -> Bind<'_> {
         self.0.binding()
     }
 }
@@ -177,7 +320,7 @@ pub struct Binder<'a> {
     shader_id: usize,
     device: &'a Device,
     layout: &'a [TypedGroup],
-    groups: Vec<BindGroup>,
+    groups: Vec<Bound>,
 }
 
 impl<'a> Binder<'a> {
@@ -216,15 +359,15 @@ impl<'a> Binder<'a> {
         );
 
         let layout = Arc::clone(&layout.bind);
-        let entries = visit(group);
+        let visitor = visit(group);
         let desc = BindGroupDescriptor {
             label: None,
             layout: &layout,
-            entries: &entries,
+            entries: &visitor.entries,
         };
 
         let bind = self.device.create_bind_group(&desc);
-        self.groups.push(bind);
+        self.groups.push(Bound::new(bind, visitor.offsets));
 
         GroupHandler {
             shader_id: self.shader_id,
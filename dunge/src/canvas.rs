@@ -0,0 +1,347 @@
+//! The 2D canvas.
+
+use {
+    crate::{
+        bind::SharedBinding,
+        color::Rgba,
+        context::Context,
+        group::BoundTexture,
+        instance::Row,
+        layer::{Blend, Config, Layer, Topology},
+        mesh,
+        shader::Shader,
+        sl::{self, Eval, Fs, Groups, InInstance, Index, Out, Vs},
+        state::{Frame, Options},
+        texture::{BindTexture, Filter, Sampler, TextureData},
+        types,
+        uniform::Uniform,
+        Format, Group, Instance,
+    },
+    std::cell::{Cell, RefCell},
+};
+
+#[derive(Instance)]
+struct Quads {
+    ab: Row<[f32; 4]>,
+    cd: Row<[f32; 4]>,
+    uv: Row<[f32; 4]>,
+    col: Row<[f32; 4]>,
+}
+
+#[derive(Group)]
+struct Map<'a> {
+    tex: BoundTexture<'a>,
+    sam: &'a Sampler,
+    siz: &'a Uniform<[f32; 2]>,
+}
+
+/// The texture bound to a [canvas](Canvas).
+///
+/// Can be created using the canvas's [`bind`](Canvas::bind) function.
+#[derive(Clone)]
+pub struct CanvasTexture(SharedBinding);
+
+/// The quad corners in the order of the triangle strip.
+struct Quad {
+    corners: [[f32; 2]; 4],
+    uv: [f32; 4],
+    col: [f32; 4],
+}
+
+struct Run {
+    tex: CanvasTexture,
+    quads: Vec<Quad>,
+}
+
+/// The 2D drawing in pixel coordinates.
+///
+/// Can be created using the context's [`make_2d_layer`](crate::Context::make_2d_layer) function.
+///
+/// The canvas maps the `(0, 0)`-`(width, height)` region of the target size
+/// to the whole target with the origin at the top left corner, has no depth
+/// and draws shapes in order with alpha blending:
+/// ```rust,ignore
+/// let mut canvas = cx.make_2d_layer(view.size(), view.format());
+/// let image = canvas.bind(&cx, &texture);
+///
+/// // In the update stage
+/// canvas.clear();
+/// canvas.rect([10., 10., 100., 50.], Rgba::from_standard([1., 0., 0., 1.]));
+/// canvas.line([0., 0.], [200., 100.], 2., Rgba::from_standard([1.; 4]));
+/// canvas.texture(&image, [50., 50., 64., 64.], [0., 0., 1., 1.], Rgba::from_standard([1.; 4]));
+///
+/// // Then in the draw stage
+/// canvas.draw(&cx, &mut frame);
+/// ```
+/// Consecutive shapes with the same texture are drawn with a single draw call.
+pub struct Canvas {
+    shader: Shader<(), Quads>,
+    layer: Layer<(), Quads>,
+    sampler: Sampler,
+    size: Uniform<[f32; 2]>,
+    plain: CanvasTexture,
+    runs: Vec<Run>,
+    rows: RefCell<Vec<Quads>>,
+    dirty: Cell<bool>,
+}
+
+impl Canvas {
+    pub(crate) fn new(cx: &Context, size: (u32, u32), format: Format) -> Self {
+        let shader = cx.make_shader(canvas_shader);
+        let conf = Config {
+            format,
+            blend: Blend::Alpha,
+            topology: Topology::TriangleStrip,
+            ..Default::default()
+        };
+
+        let layer = cx.make_layer(&shader, conf);
+        let sampler = cx.make_sampler(Filter::Linear);
+        let (width, height) = size;
+        let size = cx.make_uniform([width as f32, height as f32]);
+        let plain = {
+            let data = TextureData::new(&[u8::MAX; 4], (1, 1), Format::RgbAlpha)
+                .expect("valid texture data")
+                .with_bind();
+
+            let texture = cx.make_texture(data);
+            bind(cx, &shader, &texture, &sampler, &size)
+        };
+
+        Self {
+            shader,
+            layer,
+            sampler,
+            size,
+            plain,
+            runs: vec![],
+            rows: RefCell::default(),
+            dirty: Cell::new(false),
+        }
+    }
+
+    /// Creates a binding of the texture to draw textured quads.
+    ///
+    /// The binding must be created again if the texture is recreated.
+    pub fn bind<T>(&self, cx: &Context, texture: &T) -> CanvasTexture
+    where
+        T: BindTexture,
+    {
+        bind(cx, &self.shader, texture, &self.sampler, &self.size)
+    }
+
+    /// Sets the size of the target in pixels.
+    ///
+    /// Call it when the target is resized, for example in the window's resize handler.
+    pub fn set_size(&self, cx: &Context, size: (u32, u32)) {
+        let (width, height) = size;
+        self.size.update(cx, [width as f32, height as f32]);
+    }
+
+    /// Adds a filled rectangle `[x, y, width, height]`.
+    pub fn rect(&mut self, rect: [f32; 4], col: Rgba) {
+        let tex = self.plain.clone();
+        self.push(&tex, rect_corners(rect), [0., 0., 1., 1.], col);
+    }
+
+    /// Adds a line segment of the given width.
+    ///
+    /// Zero length segments are skipped.
+    pub fn line(&mut self, from: [f32; 2], to: [f32; 2], width: f32, col: Rgba) {
+        if let Some(corners) = line_corners(from, to, width) {
+            let tex = self.plain.clone();
+            self.push(&tex, corners, [0., 0., 1., 1.], col);
+        }
+    }
+
+    /// Adds a textured rectangle `[x, y, width, height]`.
+    ///
+    /// The `uv` is the region of the texture in the `[0, 1]` range
+    /// and the texture color is multiplied by the given one.
+    pub fn texture(&mut self, tex: &CanvasTexture, rect: [f32; 4], uv: [f32; 4], col: Rgba) {
+        self.push(tex, rect_corners(rect), uv, col);
+    }
+
+    /// Removes all shapes from the canvas.
+    pub fn clear(&mut self) {
+        self.runs.clear();
+        self.dirty.set(true);
+    }
+
+    /// Checks if the canvas has no shapes.
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    fn push(&mut self, tex: &CanvasTexture, corners: [[f32; 2]; 4], uv: [f32; 4], col: Rgba) {
+        let quad = Quad {
+            corners,
+            uv,
+            col: col.0,
+        };
+
+        match self.runs.last_mut() {
+            Some(run) if run.tex.0.ptr_eq(&tex.0) => run.quads.push(quad),
+            _ => self.runs.push(Run {
+                tex: tex.clone(),
+                quads: vec![quad],
+            }),
+        }
+
+        self.dirty.set(true);
+    }
+
+    /// Draws the shapes to the frame keeping its previous content.
+    ///
+    /// If the canvas was changed since the last draw, the shapes are
+    /// uploaded to the instance buffers first. The buffers grow as needed
+    /// and are reused for the following frames.
+    pub fn draw(&self, cx: &Context, frame: &mut Frame) {
+        if self.dirty.replace(false) {
+            let state = cx.state();
+            let mut rows = self.rows.borrow_mut();
+            for (n, run) in self.runs.iter().enumerate() {
+                if rows.len() == n {
+                    rows.push(Quads {
+                        ab: cx.make_row(&[]),
+                        cd: cx.make_row(&[]),
+                        uv: cx.make_row(&[]),
+                        col: cx.make_row(&[]),
+                    });
+                }
+
+                let pair = |i: usize| -> Vec<_> {
+                    let pair = |q: &Quad| {
+                        let [x0, y0] = q.corners[i];
+                        let [x1, y1] = q.corners[i + 1];
+                        [x0, y0, x1, y1]
+                    };
+
+                    run.quads.iter().map(pair).collect()
+                };
+
+                let quads = &mut rows[n];
+                quads.ab.write(state, &pair(0));
+                quads.cd.write(state, &pair(2));
+                let uv: Vec<_> = run.quads.iter().map(|q| q.uv).collect();
+                quads.uv.write(state, &uv);
+                let col: Vec<_> = run.quads.iter().map(|q| q.col).collect();
+                quads.col.write(state, &col);
+            }
+        }
+
+        let rows = self.rows.borrow();
+        for (run, quads) in self.runs.iter().zip(rows.iter()) {
+            frame
+                .layer(&self.layer, Options::default())
+                .bind(&run.tex.0)
+                .instance(quads)
+                .draw_points(4);
+        }
+    }
+}
+
+fn rect_corners([x, y, w, h]: [f32; 4]) -> [[f32; 2]; 4] {
+    [[x, y], [x, y + h], [x + w, y], [x + w, y + h]]
+}
+
+fn line_corners(from: [f32; 2], to: [f32; 2], width: f32) -> Option<[[f32; 2]; 4]> {
+    let quads = mesh::thick_lines(&[[from, to]], width, |pos, _| pos);
+    quads.first().map(|&[a, c, d, b]| [a, b, c, d])
+}
+
+fn bind<T>(
+    cx: &Context,
+    shader: &Shader<(), Quads>,
+    texture: &T,
+    sampler: &Sampler,
+    size: &Uniform<[f32; 2]>,
+) -> CanvasTexture
+where
+    T: BindTexture,
+{
+    let map = Map {
+        tex: BoundTexture::new(texture),
+        sam: sampler,
+        siz: size,
+    };
+
+    let mut binder = cx.make_binder(shader);
+    binder.add(&map);
+    CanvasTexture(binder.into_binding().shared())
+}
+
+fn canvas_shader(
+    q: InInstance<Quads>,
+    Index(idx): Index,
+    Groups(map): Groups<Map>,
+) -> Out<impl Eval<Vs, Out = types::Vec4<f32>>, impl Eval<Fs, Out = types::Vec4<f32>>> {
+    // Quad corners as a triangle strip in counter-clockwise order,
+    // each corner is picked by its bilinear weight
+    let u = sl::thunk(sl::f32(idx / 2));
+    let v = sl::thunk(sl::f32(idx % 2));
+    let iu = sl::thunk(1. - u.clone());
+    let iv = sl::thunk(1. - v.clone());
+    let wa = sl::thunk(iu.clone() * iv.clone());
+    let wb = sl::thunk(iu * v.clone());
+    let wc = sl::thunk(u.clone() * iv);
+    let wd = sl::thunk(u.clone() * v.clone());
+    let ab = sl::thunk(q.ab);
+    let cd = sl::thunk(q.cd);
+    let x = ab.clone().x() * wa.clone()
+        + ab.clone().z() * wb.clone()
+        + cd.clone().x() * wc.clone()
+        + cd.clone().z() * wd.clone();
+
+    let y = ab.clone().y() * wa + ab.w() * wb + cd.clone().y() * wc + cd.w() * wd;
+    let siz = sl::thunk(map.siz);
+    let uv = sl::thunk(q.uv);
+    let tu = uv.clone().x() + u * uv.clone().z();
+    let tv = uv.clone().y() + v * uv.w();
+    Out {
+        place: sl::vec4(x / siz.clone().x() * 2. - 1., 1. - y / siz.y() * 2., 0., 1.),
+        color: {
+            let tex = sl::thunk(sl::texture_sample(
+                map.tex,
+                map.sam,
+                sl::fragment(sl::vec2(tu, tv)),
+            ));
+
+            let col = sl::thunk(sl::fragment(q.col));
+            let r = tex.clone().x() * col.clone().x();
+            let g = tex.clone().y() * col.clone().y();
+            let b = tex.clone().z() * col.clone().z();
+            let a = tex.w() * col.w();
+            sl::vec4(r, g, b, a)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::sl::IntoModule};
+
+    #[test]
+    fn shader() {
+        let module = canvas_shader.into_module();
+        helpers::eq_lines(&module.wgsl, include_str!("../tests/canvas.wgsl"));
+    }
+
+    #[test]
+    fn corners() {
+        // The corners follow the strip order of the shader: the first two
+        // have `u = 0` and the odd ones have `v = 1`, so they map to pixels
+        // with `x` growing to the right and `y` growing down
+        let rect = rect_corners([1., 2., 3., 4.]);
+        assert_eq!(rect, [[1., 2.], [1., 6.], [4., 2.], [4., 6.]]);
+
+        let line = line_corners([0., 5.], [10., 5.], 2.).expect("non-zero length");
+        let [a, b, c, d] = line.map(|[x, y]| [x.round(), y.round()]);
+        assert_eq!([a[0], b[0], c[0], d[0]], [0., 0., 10., 10.]);
+        assert_eq!(a[1], c[1]);
+        assert_eq!(b[1], d[1]);
+        assert_eq!((a[1] - b[1]).abs(), 2.);
+
+        assert!(line_corners([1., 1.], [1., 1.], 2.).is_none());
+    }
+}
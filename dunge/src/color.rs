@@ -1,4 +1,8 @@
 //! Color types.
+//!
+//! Colors are linear, the same as in shaders. The [`from_standard`](Color::from_standard)
+//! and [`to_standard`](Color::to_standard) functions convert them from and to sRGB
+//! on the CPU, while [sRGB formats](crate::Format::is_srgb) are converted by the hardware.
 
 /// A linear RGB color.
 pub type Rgb = Color<3>;
@@ -13,14 +17,6 @@ pub struct Color<const N: usize>(pub [f32; N]);
 impl<const N: usize> Color<N> {
     /// Creates a linear `Color` from sRGB(A) color.
     pub fn from_standard(col: [f32; N]) -> Self {
-        fn to_linear(c: f32) -> f32 {
-            if c > 0.04045 {
-                ((c + 0.055) / 1.055).powf(2.4)
-            } else {
-                c / 12.92
-            }
-        }
-
         Self(col.map(to_linear))
     }
 
@@ -33,15 +29,140 @@ impl<const N: usize> Color<N> {
     pub fn from_standard_bytes(col: [u8; N]) -> Self {
         Self::from_standard(col.map(to_f32_color))
     }
+
+    /// Converts the `Color` to sRGB(A) color.
+    ///
+    /// This is the inverse of the [`from_standard`](Self::from_standard) function.
+    pub fn to_standard(self) -> [f32; N] {
+        self.0.map(to_standard)
+    }
+
+    /// Converts the `Color` to sRGB(A) bytes.
+    pub fn to_standard_bytes(self) -> [u8; N] {
+        self.to_standard().map(to_u8_color)
+    }
+}
+
+impl Color<3> {
+    /// Creates a linear `Color` from HSV color.
+    ///
+    /// The hue is specified in degrees, the saturation and
+    /// the value are in range from 0 to 1. The HSV model describes
+    /// sRGB colors, so the result is converted to linear space.
+    pub fn from_hsv(hue: f32, sat: f32, val: f32) -> Self {
+        let h = hue.rem_euclid(360.) / 60.;
+        let c = val * sat;
+        let x = c * (1. - (h % 2. - 1.).abs());
+        let m = val - c;
+        let [r, g, b] = match h as u32 {
+            0 => [c, x, 0.],
+            1 => [x, c, 0.],
+            2 => [0., c, x],
+            3 => [0., x, c],
+            4 => [x, 0., c],
+            _ => [c, 0., x],
+        };
+
+        Self::from_standard([r + m, g + m, b + m])
+    }
 }
 
 impl Color<4> {
+    /// Returns the color with RGB channels multiplied by alpha.
+    ///
+    /// Use it with the [premultiplied](crate::layer::Blend::Premultiplied) blend mode.
+    pub fn premultiplied(self) -> Self {
+        let [r, g, b, a] = self.0;
+        Self([r * a, g * a, b * a, a])
+    }
+
     pub(crate) fn wgpu(self) -> wgpu::Color {
         let [r, g, b, a] = self.0.map(f64::from);
         wgpu::Color { r, g, b, a }
     }
 }
 
+fn to_linear(c: f32) -> f32 {
+    if c > 0.04045 {
+        ((c + 0.055) / 1.055).powf(2.4)
+    } else {
+        c / 12.92
+    }
+}
+
+fn to_standard(c: f32) -> f32 {
+    if c > 0.003_130_8 {
+        1.055 * c.powf(1. / 2.4) - 0.055
+    } else {
+        c * 12.92
+    }
+}
+
 fn to_f32_color(c: u8) -> f32 {
     f32::from(c) / f32::from(u8::MAX)
 }
+
+fn to_u8_color(c: f32) -> u8 {
+    (c.clamp(0., 1.) * f32::from(u8::MAX)).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx<const N: usize>(a: [f32; N], b: [f32; N]) -> bool {
+        std::iter::zip(a, b).all(|(a, b)| (a - b).abs() < 1e-4)
+    }
+
+    #[test]
+    fn standard_to_linear() {
+        let col = Rgb::from_standard([0., 0.5, 1.]);
+        assert!(
+            approx(col.0, [0., 0.214_041, 1.]),
+            "unexpected linear color {:?}",
+            col.0,
+        );
+    }
+
+    #[test]
+    fn standard_roundtrip() {
+        let bytes = [0, 1, 10, 128, 200, 255];
+        for b in bytes {
+            let col = Rgb::from_standard_bytes([b; 3]);
+            assert_eq!(col.to_standard_bytes(), [b; 3], "roundtrip of {b}");
+        }
+    }
+
+    #[test]
+    fn premultiplied() {
+        let col = Rgba::from_bytes([255, 128, 0, 0]).premultiplied();
+        assert_eq!(col.0, [0.; 4], "transparent color must be black");
+
+        let col = Color([1., 0.5, 0.25, 0.5]).premultiplied();
+        assert_eq!(
+            col.0,
+            [0.5, 0.25, 0.125, 0.5],
+            "unexpected premultiplied color"
+        );
+    }
+
+    #[test]
+    fn from_hsv() {
+        let cases = [
+            ((0., 1., 1.), [1., 0., 0.]),
+            ((120., 1., 1.), [0., 1., 0.]),
+            ((240., 1., 1.), [0., 0., 1.]),
+            ((60., 1., 1.), [1., 1., 0.]),
+            ((360., 1., 1.), [1., 0., 0.]),
+            ((0., 0., 0.5), [0.5; 3]),
+        ];
+
+        for ((h, s, v), expected) in cases {
+            let col = Rgb::from_hsv(h, s, v).to_standard();
+            assert!(
+                approx(col, expected),
+                "hsv({h}, {s}, {v}) gives {col:?}, expected {expected:?}",
+            );
+        }
+    }
+}
@@ -1,41 +1,133 @@
 use {
     crate::{
         bind::{self, Binder, ForeignShader, GroupHandler, UniqueBinding, Visit},
+        canvas::Canvas,
+        color::Rgba,
         draw::Draw,
+        format::Format,
+        indirect::{DrawIndirectArgs, IndirectBuffer},
         instance::Row,
-        layer::{Config, Layer},
-        mesh::{self, Mesh},
+        layer::{Bundle, Config, Layer, PipelineCacheStats, Recorder},
+        light::{Lights, PointLight},
+        mesh::{self, Mesh, MeshArena},
+        post::{Blit, Bloom, BloomParams, Blur, Post, PostEffect},
+        query::OcclusionQuery,
         shader::Shader,
         sl::IntoModule,
+        sprite::SpriteBatch,
         state::{AsTarget, State},
-        texture::{self, CopyBuffer, CopyBufferView, Filter, Make, MapResult, Mapped, Sampler},
-        uniform::{IntoValue, Uniform, Value},
+        texture::{
+            self, CopyBuffer, CopyBufferView, DrawTexture, Filter, Make, MapResult, Mapped, Sampler,
+        },
+        uniform::{DynamicUniform, IntoValue, Uniform, Updates, Value},
         Vertex,
     },
-    std::{error, fmt, future::IntoFuture, sync::Arc},
+    std::{error, fmt, future::IntoFuture, path::PathBuf, sync::Arc},
+    wgpu::{AdapterInfo, Backend, Backends, Features, PowerPreference, TextureFormatFeatureFlags},
 };
 
 /// Creates the context instance.
 ///
+/// The context doesn't depend on a window and never creates a surface,
+/// so it can be used headless: draw into a [texture](crate::texture::Texture2d),
+/// copy it with a [copy buffer](crate::texture::CopyBuffer) and read the data back
+/// using the [`map_view`](Context::map_view) function.
+/// The `winit` feature is not required for that.
+///
 /// # Errors
 /// Returns an error when the context could not be created.
 /// See [`FailedMakeContext`] for details.
 pub async fn context() -> Result<Context, FailedMakeContext> {
-    Context::new().await
+    Context::new(ContextOptions::default()).await
 }
 
-/// The main dunge context.
+/// Creates the context instance with given [options](ContextOptions).
 ///
-/// It can be created via the [`context`](fn@crate::context) function
-/// or the [`window`](fn@crate::window) function if you need a window
-/// and the `winit` feature is enabled.
-#[derive(Clone)]
-pub struct Context(Arc<State>);
+/// # Errors
+/// Returns an error when the context could not be created.
+/// See [`FailedMakeContext`] for details.
+pub async fn context_with(opts: ContextOptions) -> Result<Context, FailedMakeContext> {
+    Context::new(opts).await
+}
 
-impl Context {
-    pub(crate) async fn new() -> Result<Self, FailedMakeContext> {
-        use wgpu::{Backends, Instance, InstanceDescriptor, InstanceFlags};
+/// Creates the context instance sharing the existing wgpu objects.
+///
+/// It lets dunge draw with the device of a larger application
+/// instead of creating its own instance and device. The objects
+/// stay available through the context's [`device`](Context::device)
+/// and [`queue`](Context::queue) functions.
+/// Features of the device should be enabled by the application.
+pub fn context_from_wgpu(
+    instance: wgpu::Instance,
+    adapter: wgpu::Adapter,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+) -> Context {
+    let state = State::from_parts(instance, adapter, device, queue);
+    Context(Arc::new(state))
+}
+
+type AdapterFilter = Box<dyn Fn(&AdapterInfo) -> bool>;
 
+/// The context creation options.
+pub struct ContextOptions {
+    pub(crate) power_preference: PowerPreference,
+    pub(crate) backends: Backends,
+    pub(crate) adapter_filter: Option<AdapterFilter>,
+    pub(crate) features: Features,
+    pub(crate) trace_path: Option<PathBuf>,
+}
+
+impl ContextOptions {
+    /// Sets the power preference of the adapter.
+    pub fn power_preference(mut self, power_preference: PowerPreference) -> Self {
+        self.power_preference = power_preference;
+        self
+    }
+
+    /// Sets backends allowed to use.
+    pub fn backends(mut self, backends: Backends) -> Self {
+        self.backends = backends;
+        self
+    }
+
+    /// Sets the directory to record the API trace of the device to.
+    ///
+    /// The trace can be attached to a bug report to reproduce a driver issue.
+    /// It's recorded only if the `trace` feature of wgpu is enabled,
+    /// otherwise the path is ignored.
+    pub fn trace_path<P>(mut self, path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.trace_path = Some(path.into());
+        self
+    }
+
+    /// Sets the filter to select an adapter.
+    ///
+    /// Only adapters for which the filter returns `true` can be selected.
+    pub fn adapter_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&AdapterInfo) -> bool + 'static,
+    {
+        self.adapter_filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Sets features required from the device.
+    ///
+    /// The context creation fails with the
+    /// [`MissingFeatures`](FailedMakeContext::MissingFeatures) error
+    /// if the selected adapter doesn't support some of them.
+    pub fn features(mut self, features: Features) -> Self {
+        self.features = features;
+        self
+    }
+}
+
+impl Default for ContextOptions {
+    fn default() -> Self {
         let backends;
 
         #[cfg(any(target_family = "unix", target_family = "windows"))]
@@ -48,9 +140,31 @@ impl Context {
             backends = Backends::BROWSER_WEBGPU;
         }
 
+        Self {
+            power_preference: PowerPreference::HighPerformance,
+            backends,
+            adapter_filter: None,
+            features: Features::empty(),
+            trace_path: None,
+        }
+    }
+}
+
+/// The main dunge context.
+///
+/// It can be created via the [`context`](fn@crate::context) function
+/// or the [`window`](fn@crate::window) function if you need a window
+/// and the `winit` feature is enabled.
+#[derive(Clone)]
+pub struct Context(Arc<State>);
+
+impl Context {
+    pub(crate) async fn new(opts: ContextOptions) -> Result<Self, FailedMakeContext> {
+        use wgpu::{Instance, InstanceDescriptor, InstanceFlags};
+
         let instance = {
             let desc = InstanceDescriptor {
-                backends,
+                backends: opts.backends,
                 flags: InstanceFlags::ALLOW_UNDERLYING_NONCOMPLIANT_ADAPTER,
                 ..Default::default()
             };
@@ -58,7 +172,7 @@ impl Context {
             Instance::new(desc)
         };
 
-        let state = State::new(instance).await?;
+        let state = State::new(instance, &opts).await?;
         Ok(Self(Arc::new(state)))
     }
 
@@ -66,11 +180,85 @@ impl Context {
         &self.0
     }
 
+    /// Returns the limits of the device.
+    pub fn limits(&self) -> Limits {
+        Limits::from_wgpu(&self.0.device().limits())
+    }
+
+    /// Returns the graphics backend used by the context.
+    pub fn backend(&self) -> Backend {
+        self.0.info().backend
+    }
+
+    /// Returns the name of the adapter (GPU) used by the context.
+    pub fn adapter_name(&self) -> &str {
+        &self.0.info().name
+    }
+
+    /// Returns sample counts supported for the texture format in ascending order.
+    ///
+    /// A count beyond 1 and 4 is available only if the
+    /// [`TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`](Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+    /// feature is [enabled](ContextOptions::features).
+    pub fn supported_sample_counts(&self, format: Format) -> Vec<u32> {
+        self.0.sample_counts(format)
+    }
+
+    /// Checks if a layer can blend into a target of the format on the current adapter.
+    ///
+    /// Beyond the [guaranteed](Format::is_blendable) formats it requires the
+    /// [`TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`](Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+    /// feature to be [enabled](ContextOptions::features).
+    pub fn supports_blending(&self, format: Format) -> bool {
+        let flags = self.0.format_flags(format);
+        flags.contains(TextureFormatFeatureFlags::BLENDABLE)
+    }
+
+    /// Checks if a texture of the format can be sampled with
+    /// the [linear](Filter::Linear) filter on the current adapter.
+    ///
+    /// Beyond the [guaranteed](Format::is_filterable) formats it requires the
+    /// [`TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`](Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+    /// feature to be [enabled](ContextOptions::features).
+    pub fn supports_filtering(&self, format: Format) -> bool {
+        let flags = self.0.format_flags(format);
+        flags.contains(TextureFormatFeatureFlags::FILTERABLE)
+    }
+
+    /// Returns the wgpu device used by the context.
+    pub fn device(&self) -> &wgpu::Device {
+        self.0.device()
+    }
+
+    /// Returns the wgpu queue used by the context.
+    pub fn queue(&self) -> &wgpu::Queue {
+        self.0.queue()
+    }
+
+    /// Returns the features enabled on the device.
+    pub fn features(&self) -> Features {
+        self.0.device().features()
+    }
+
     pub fn make_shader<M, A>(&self, module: M) -> Shader<M::Vertex, M::Instance>
     where
         M: IntoModule<A>,
     {
-        Shader::new(&self.0, module)
+        Shader::new(&self.0, module, None)
+    }
+
+    /// Creates a [shader](Shader) with the debug label.
+    ///
+    /// The label is shown in graphics debuggers and validation errors.
+    pub fn make_shader_labeled<M, A>(
+        &self,
+        label: &str,
+        module: M,
+    ) -> Shader<M::Vertex, M::Instance>
+    where
+        M: IntoModule<A>,
+    {
+        Shader::new(&self.0, module, Some(label))
     }
 
     pub fn make_binder<'a, V, I>(&'a self, shader: &'a Shader<V, I>) -> Binder<'a> {
@@ -82,22 +270,154 @@ impl Context {
         U: IntoValue,
     {
         let val = val.into_value();
-        Uniform::new(&self.0, val.value().as_ref())
+        Uniform::new(&self.0, val.value().as_ref(), None)
+    }
+
+    /// Creates a [uniform](Uniform) with the debug label.
+    pub fn make_uniform_labeled<U>(&self, label: &str, val: U) -> Uniform<U::Value>
+    where
+        U: IntoValue,
+    {
+        let val = val.into_value();
+        Uniform::new(&self.0, val.value().as_ref(), Some(label))
+    }
+
+    /// Creates a [dynamic uniform](DynamicUniform) with `len` slots.
+    ///
+    /// See the uniform's docs on how to choose the number of slots.
+    ///
+    /// # Panic
+    /// It will panic if the `len` is zero.
+    pub fn make_dynamic_uniform<U>(&self, val: U, len: u32) -> DynamicUniform<U::Value>
+    where
+        U: IntoValue,
+    {
+        let val = val.into_value();
+        DynamicUniform::new(&self.0, val.value().as_ref(), len)
+    }
+
+    /// Creates a [lights](Lights) group.
+    ///
+    /// # Panic
+    /// It will panic if there are more than [`MAX_POINT_LIGHTS`](crate::light::MAX_POINT_LIGHTS) lights
+    /// or a light [radius](PointLight::radius) isn't positive.
+    pub fn make_lights(&self, lights: &[PointLight]) -> Lights {
+        Lights::new(&self.0, lights)
     }
 
+    pub fn begin_updates(&self) -> Updates<'_> {
+        Updates::new(&self.0)
+    }
+
+    /// Creates a [layer](Layer) for the shader.
+    ///
+    /// Layers made from the same shader with the same config share
+    /// a single compiled pipeline, see [`pipeline_cache_stats`](Self::pipeline_cache_stats).
     pub fn make_layer<V, I, O>(&self, shader: &Shader<V, I>, opts: O) -> Layer<V, I>
     where
         O: Into<Config>,
     {
         let opts = opts.into();
-        Layer::new(&self.0, shader, &opts)
+        Layer::new(&self.0, shader, &opts, None)
+    }
+
+    /// Creates a [layer](Layer) with the debug label.
+    pub fn make_layer_labeled<V, I, O>(
+        &self,
+        label: &str,
+        shader: &Shader<V, I>,
+        opts: O,
+    ) -> Layer<V, I>
+    where
+        O: Into<Config>,
+    {
+        let opts = opts.into();
+        Layer::new(&self.0, shader, &opts, Some(label))
+    }
+
+    /// Creates many [layers](Layer) at once.
+    ///
+    /// Pipelines are compiled concurrently on several threads, which reduces
+    /// the startup time when an application has a lot of layers. Layers are
+    /// returned in the same order as the given pairs. The vertex and instance
+    /// types must be [`Send`] and [`Sync`] since shaders are shared between threads.
+    /// Equal pairs share one pipeline, which is compiled only once. Pipelines are
+    /// labeled with the index of their pair for graphics debuggers.
+    ///
+    /// On wasm the pipelines are compiled serially.
+    pub fn make_layers<V, I>(&self, layers: &[(&Shader<V, I>, Config)]) -> Vec<Layer<V, I>>
+    where
+        V: Send + Sync,
+        I: Send + Sync,
+    {
+        Layer::new_many(&self.0, layers)
+    }
+
+    /// Records a [bundle](Bundle) of draw commands for the layer.
+    ///
+    /// The recorded commands can be replayed every frame with the frame's
+    /// [`execute_bundle`](crate::Frame::execute_bundle) function:
+    /// ```rust,ignore
+    /// let bundle = cx.make_bundle(&layer, |rec| {
+    ///     for mesh in &meshes {
+    ///         rec.bind(&bind).draw(mesh);
+    ///     }
+    /// });
+    ///
+    /// frame.execute_bundle(&bundle, opts);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if a binding with a [dynamic uniform](crate::uniform::DynamicUniform)
+    /// is recorded, since the bundle would keep reading its old slot.
+    pub fn make_bundle<'p, V, I, F>(&'p self, layer: &'p Layer<V, I>, record: F) -> Bundle
+    where
+        F: FnOnce(&mut Recorder<'p, V, I>),
+    {
+        layer.bundle(&self.0, record)
+    }
+
+    /// Returns statistics of the pipeline cache.
+    ///
+    /// When a layer is created for a shader and config that already
+    /// have a live pipeline, the pipeline is reused instead of compiling a new one.
+    /// A reused pipeline keeps the debug label of the layer that created it.
+    pub fn pipeline_cache_stats(&self) -> PipelineCacheStats {
+        self.0.pipelines().stats()
+    }
+
+    /// Creates a layer to draw a full-screen triangle.
+    ///
+    /// The shader has no vertex input and should compute the vertex position
+    /// from its [index](crate::sl::Index) with the [`fullscreen_place`](crate::sl::fullscreen_place)
+    /// and [`fullscreen_uv`](crate::sl::fullscreen_uv) functions. Draw the layer with `draw_points(3)`.
+    pub fn make_fullscreen_layer(&self, shader: &Shader<(), ()>, format: Format) -> Layer<(), ()> {
+        let conf = Config {
+            format,
+            ..Default::default()
+        };
+
+        Layer::new(&self.0, shader, &conf, None)
     }
 
     pub fn make_mesh<V>(&self, data: &mesh::MeshData<V>) -> Mesh<V>
     where
         V: Vertex,
     {
-        Mesh::new(&self.0, data)
+        Mesh::new(&self.0, data, None)
+    }
+
+    /// Creates a [mesh](Mesh) with the debug label.
+    pub fn make_mesh_labeled<V>(&self, label: &str, data: &mesh::MeshData<V>) -> Mesh<V>
+    where
+        V: Vertex,
+    {
+        Mesh::new(&self.0, data, Some(label))
+    }
+
+    /// Creates an empty [mesh arena](MeshArena) for transient meshes.
+    pub fn make_mesh_arena(&self) -> MeshArena {
+        MeshArena::new()
     }
 
     pub fn make_row<U>(&self, data: &[U]) -> Row<U>
@@ -107,21 +427,155 @@ impl Context {
         Row::new(&self.0, data)
     }
 
+    /// Creates an instance [row](Row) of colors.
+    ///
+    /// The row can be a member of an [instance](crate::Instance) type,
+    /// so each instance gets its own color in the shader as a `vec4<f32>`:
+    /// ```rust,ignore
+    /// #[derive(Instance)]
+    /// struct Transform(Row<[f32; 2]>, Row<[f32; 4]>);
+    ///
+    /// let colors = cx.make_instance_colors(&[Rgba::from_standard([1., 0., 0., 1.])]);
+    /// ```
+    pub fn make_instance_colors(&self, colors: &[Rgba]) -> Row<[f32; 4]> {
+        let data: Vec<_> = colors.iter().map(|col| col.0).collect();
+        Row::new(&self.0, &data)
+    }
+
     pub fn make_texture<M>(&self, data: M) -> M::Out
     where
         M: Make,
     {
-        texture::make(&self.0, data)
+        texture::make(&self.0, data, None)
+    }
+
+    /// Creates a texture uploading its data through a staging buffer.
+    ///
+    /// Unlike [`make_texture`](Self::make_texture), the data isn't written
+    /// to the queue synchronously. A staging buffer is mapped first, like in
+    /// [`map_view`](Self::map_view), so awaiting the `rx` future yields until the
+    /// buffer is ready when the event loop runs on another thread. Then the data
+    /// is copied into the buffer and the copy to the texture is submitted,
+    /// the function returns after the submission:
+    /// ```rust,ignore
+    /// let (tx, rx) = oneshot();
+    /// let texture = cx.make_texture_async(data, tx, rx).await;
+    /// ```
+    pub async fn make_texture_async<M, S, R>(&self, data: M, tx: S, rx: R) -> M::Out
+    where
+        M: Make,
+        S: FnOnce(MapResult) + wgpu::WasmNotSend + 'static,
+        R: IntoFuture<Output = MapResult>,
+    {
+        texture::make_staged(&self.0, data, tx, rx).await
+    }
+
+    /// Creates a texture with the debug label.
+    pub fn make_texture_labeled<M>(&self, label: &str, data: M) -> M::Out
+    where
+        M: Make,
+    {
+        texture::make(&self.0, data, Some(label))
     }
 
     pub fn make_sampler(&self, filter: Filter) -> Sampler {
-        Sampler::new(&self.0, filter)
+        Sampler::new(&self.0, filter, (0., 32.))
+    }
+
+    /// Creates a [sampler](Sampler) with the level of detail clamped to the given range.
+    ///
+    /// Sampling is restricted to mip levels from `min` to `max`, for example,
+    /// raising `min` forces a blurry look. Textures have a single mip level
+    /// for now, so the clamp only matters for textures with mipmaps.
+    ///
+    /// # Panics
+    /// Panics if `min` is negative, greater than `max` or any of them is NaN.
+    pub fn make_sampler_with_lod(&self, filter: Filter, min: f32, max: f32) -> Sampler {
+        Sampler::new(&self.0, filter, (min, max))
+    }
+
+    /// Creates a full-screen [post effect](Post) drawing to the given format.
+    pub fn make_post(&self, effect: PostEffect, format: Format) -> Post {
+        Post::new(self, effect, format)
+    }
+
+    /// Creates a [bloom](Bloom) effect drawing to the given format.
+    pub fn make_bloom(&self, params: BloomParams, format: Format) -> Bloom {
+        Bloom::new(self, params, format)
+    }
+
+    /// Creates a Gaussian [blur](Blur) with the given radius in texels.
+    pub fn make_blur(&self, radius: f32) -> Blur {
+        Blur::new(self, radius)
+    }
+
+    /// Creates a [blit](Blit) drawing textures to the given format.
+    ///
+    /// # Panics
+    /// Panics if the format is a depth format or the integer [`Byte`](Format::Byte) one.
+    pub fn make_blit(&self, filter: Filter, format: Format) -> Blit {
+        Blit::new(self, filter, format)
+    }
+
+    /// Creates a 2D [canvas](Canvas) in pixel coordinates of the given target size
+    /// drawing to the given format.
+    pub fn make_2d_layer(&self, size: (u32, u32), format: Format) -> Canvas {
+        Canvas::new(self, size, format)
+    }
+
+    /// Creates a [sprite batch](SpriteBatch) sampling the atlas texture
+    /// and drawing to the given format.
+    pub fn make_sprite_batch<T>(&self, atlas: &T, filter: Filter, format: Format) -> SpriteBatch
+    where
+        T: texture::BindTexture,
+    {
+        SpriteBatch::new(self, atlas, filter, format)
+    }
+
+    /// Creates an [indirect buffer](IndirectBuffer) with the given draw arguments.
+    pub fn make_indirect_buffer(&self, args: &[DrawIndirectArgs]) -> IndirectBuffer {
+        IndirectBuffer::new(&self.0, args)
+    }
+
+    /// Creates an [occlusion query](OcclusionQuery) set with the given number of queries.
+    pub fn make_occlusion_query(&self, len: u32) -> OcclusionQuery {
+        OcclusionQuery::new(&self.0, len)
+    }
+
+    /// Reads the resolved results of the [occlusion query](OcclusionQuery) set.
+    ///
+    /// Returns the number of passed samples for each query. Like
+    /// [`map_view`](Self::map_view), it blocks the thread without an event loop
+    /// and yields until the data is available when the loop is running.
+    pub async fn read_occlusion_query<S, R>(&self, query: &OcclusionQuery, tx: S, rx: R) -> Vec<u64>
+    where
+        S: FnOnce(MapResult) + wgpu::WasmNotSend + 'static,
+        R: IntoFuture<Output = MapResult>,
+    {
+        query.read(&self.0, tx, rx).await
     }
 
     pub fn make_copy_buffer(&self, size: (u32, u32)) -> CopyBuffer {
         CopyBuffer::new(&self.0, size)
     }
 
+    /// Maps the buffer view to read it on the host.
+    ///
+    /// Without an event loop it blocks the thread until the GPU finishes the work.
+    /// When the window event loop is running, a mapping awaited on another thread
+    /// doesn't block. Instead, the loop polls the device while there are pending
+    /// mappings, and the `tx` callback is called from the loop once the buffer
+    /// is ready, so awaiting the `rx` future yields and wakes up when the data
+    /// is available. The loop polls the device only between its callbacks,
+    /// so a mapping on the loop thread itself, for example, inside the update
+    /// handler, still blocks until the GPU finishes to not wait for itself.
+    ///
+    /// Each mapping has its own `tx` and `rx` pair and nothing is shared between
+    /// mappings except the counter of pending ones. So several readbacks of
+    /// different buffers can be in flight at once, each one completes independently
+    /// and the loop keeps polling until the last one is done. A single buffer
+    /// can be mapped only once at a time, the next mapping of it must wait
+    /// until the previous [mapped](Mapped) data is dropped.
     pub async fn map_view<'a, S, R>(&self, view: CopyBufferView<'a>, tx: S, rx: R) -> Mapped<'a>
     where
         S: FnOnce(MapResult) + wgpu::WasmNotSend + 'static,
@@ -130,6 +584,28 @@ impl Context {
         view.map(&self.0, tx, rx).await
     }
 
+    /// Polls the device to make progress on the submitted work.
+    ///
+    /// Returns `true` if all submitted work is done.
+    ///
+    /// Usually it's not needed. Functions waiting for the GPU, like
+    /// [`map_view`](Self::map_view), poll the device automatically:
+    /// without an event loop they block until the work is done
+    /// and the window event loop polls the device while they are pending.
+    /// Call it in a headless loop to wait until the GPU is idle with
+    /// [`Maintain::Wait`](wgpu::Maintain::Wait), for example to measure the
+    /// frame time, or to release resources of finished work without blocking with
+    /// [`Maintain::Poll`](wgpu::Maintain::Poll).
+    pub fn poll(&self, maintain: wgpu::Maintain) -> bool {
+        self.0.device().poll(maintain).is_queue_empty()
+    }
+
+    /// Replaces a group in the binding.
+    ///
+    /// The same as the binding's [`rebind`](UniqueBinding::rebind) function.
+    ///
+    /// # Errors
+    /// Returns the [`ForeignShader`] error if the handler belongs to another shader.
     pub fn update_group<G>(
         &self,
         uni: &mut UniqueBinding,
@@ -150,12 +626,95 @@ impl Context {
         let target = target.as_target();
         self.0.draw(target, draw);
     }
+
+    /// Resolves a multisampled texture into a single sampled one.
+    ///
+    /// The source is a texture created with [samples](crate::texture::TextureData::with_samples)
+    /// greater than one and the destination is a texture of the same size and format
+    /// with the sample count of one. If the destination also has the `with_bind` usage,
+    /// it can be used as a [bound texture](crate::group::BoundTexture) after that:
+    /// ```rust,ignore
+    /// cx.draw_to(&msaa, draw);
+    /// cx.resolve(&msaa, &resolved);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the textures don't match as described above.
+    pub fn resolve<S, D>(&self, src: &S, dst: &D)
+    where
+        S: DrawTexture,
+        D: DrawTexture,
+    {
+        self.0.resolve(src.draw_texture(), dst.draw_texture());
+    }
+}
+
+/// The device limits.
+///
+/// Can be obtained using the context's [`limits`](Context::limits) function.
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    /// Maximum width and height of a 2D texture.
+    pub max_texture_size: u32,
+
+    /// Maximum number of layers of a texture array.
+    pub max_texture_layers: u32,
+
+    /// Maximum number of groups in a shader.
+    pub max_bind_groups: u32,
+
+    /// Maximum number of members in a group.
+    pub max_bindings_per_group: u32,
+
+    /// Maximum size of a uniform in bytes.
+    pub max_uniform_size: u32,
+
+    /// Maximum size of a storage buffer in bytes.
+    pub max_storage_size: u32,
+
+    /// Maximum number of vertex and instance buffers.
+    pub max_vertex_buffers: u32,
+
+    /// Maximum number of vertex and instance attributes.
+    pub max_vertex_attributes: u32,
+
+    /// Maximum size of a compute workgroup by each dimension.
+    pub max_compute_workgroup_size: (u32, u32, u32),
+
+    /// Maximum number of invocations in a compute workgroup.
+    pub max_compute_invocations: u32,
+
+    /// Maximum number of compute workgroups by each dimension.
+    pub max_compute_workgroups: u32,
+}
+
+impl Limits {
+    fn from_wgpu(limits: &wgpu::Limits) -> Self {
+        Self {
+            max_texture_size: limits.max_texture_dimension_2d,
+            max_texture_layers: limits.max_texture_array_layers,
+            max_bind_groups: limits.max_bind_groups,
+            max_bindings_per_group: limits.max_bindings_per_bind_group,
+            max_uniform_size: limits.max_uniform_buffer_binding_size,
+            max_storage_size: limits.max_storage_buffer_binding_size,
+            max_vertex_buffers: limits.max_vertex_buffers,
+            max_vertex_attributes: limits.max_vertex_attributes,
+            max_compute_workgroup_size: (
+                limits.max_compute_workgroup_size_x,
+                limits.max_compute_workgroup_size_y,
+                limits.max_compute_workgroup_size_z,
+            ),
+            max_compute_invocations: limits.max_compute_invocations_per_workgroup,
+            max_compute_workgroups: limits.max_compute_workgroups_per_dimension,
+        }
+    }
 }
 
 /// An error returned from the [context](Context) constructor.
 #[derive(Debug)]
 pub enum FailedMakeContext {
     BackendSelection,
+    MissingFeatures(Features),
     RequestDevice(wgpu::RequestDeviceError),
 }
 
@@ -163,6 +722,7 @@ impl fmt::Display for FailedMakeContext {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::BackendSelection => write!(f, "failed to select backend"),
+            Self::MissingFeatures(features) => write!(f, "unsupported features: {features:?}"),
             Self::RequestDevice(err) => write!(f, "failed to get device: {err}"),
         }
     }
@@ -171,7 +731,7 @@ impl fmt::Display for FailedMakeContext {
 impl error::Error for FailedMakeContext {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
-            Self::BackendSelection => None,
+            Self::BackendSelection | Self::MissingFeatures(_) => None,
             Self::RequestDevice(err) => Some(err),
         }
     }
@@ -2,11 +2,15 @@ use {
     crate::{
         context::Context,
         state::State,
-        time::{Fps, Time},
+        time::{self, Bench, BenchReport, Fps, Time},
         update::{IntoUpdate, Update},
-        window::{self, View, WindowState},
+        window::{self, Message, View, WindowState},
+    },
+    std::{
+        cell::{Cell, RefCell},
+        error, fmt, ops,
+        time::Duration,
     },
-    std::{cell::Cell, error, fmt, ops, time::Duration},
     wgpu::SurfaceError,
     winit::{
         application::ApplicationHandler,
@@ -24,9 +28,15 @@ pub type KeyCode = keyboard::KeyCode;
 /// String type from `winit` crate.
 pub type SmolStr = keyboard::SmolStr;
 
+/// The state of modifier keys such as Ctrl, Shift or Alt.
+pub type Modifiers = keyboard::ModifiersState;
+
 /// Describes a button of a mouse controller.
 pub type MouseButton = event::MouseButton;
 
+/// Describes the phase of a touch.
+pub type TouchPhase = event::TouchPhase;
+
 pub(crate) fn run<U>(ws: WindowState<U::Event>, cx: Context, upd: U) -> Result<(), LoopError>
 where
     U: IntoUpdate + 'static,
@@ -145,22 +155,37 @@ where
     U: IntoUpdate,
 {
     const WAIT_TIME: Duration = Duration::from_millis(100);
+    const POLL_TIME: Duration = Duration::from_millis(1);
 
     fn new(cx: Context, view: View, into_upd: U) -> Self {
         let ctrl = Control {
             view,
             resized: None,
+            out_of_memory: false,
             min_delta_time: Cell::new(Duration::from_secs_f32(1. / 60.)),
+            max_delta_time: Cell::new(Duration::MAX),
+            delta_smoothing: Cell::new(0.),
+            pause_occluded: Cell::new(true),
+            occluded: false,
             delta_time: Duration::ZERO,
+            smoothed_delta_time: Duration::ZERO,
             fps: 0,
+            bench: RefCell::default(),
             pressed_keys: vec![],
             released_keys: vec![],
+            held_keys: vec![],
+            modifiers: Modifiers::empty(),
+            text_input: String::new(),
+            preedit: None,
             cursor_position: None,
             mouse: Mouse {
                 wheel_delta: (0., 0.),
                 pressed_buttons: Buttons(vec![]),
                 released_buttons: Buttons(vec![]),
             },
+            touch_events: vec![],
+            touches: vec![],
+            prev_pair: None,
         };
 
         Self {
@@ -175,12 +200,26 @@ where
     }
 }
 
-impl<U> ApplicationHandler<U::Event> for Handler<U>
+impl<U> ApplicationHandler<Message<U::Event>> for Handler<U>
 where
     U: IntoUpdate,
 {
     fn resumed(&mut self, el: &ActiveEventLoop) {
         log::debug!("resumed");
+        match self.ctrl.view.resume(self.cx.state()) {
+            Ok(true) => {
+                log::debug!("surface recreated");
+                self.ctrl.resized = Some(self.ctrl.view.size());
+                self.upd.get().resume(&self.cx, &self.ctrl.view);
+            }
+            Ok(false) => {}
+            Err(err) => {
+                self.out = Err(LoopError::Window(err));
+                el.exit();
+                return;
+            }
+        }
+
         self.active = true;
         self.ctrl.view.request_redraw();
         el.set_control_flow(ControlFlow::wait_duration(Self::WAIT_TIME));
@@ -192,11 +231,14 @@ where
     fn suspended(&mut self, _: &ActiveEventLoop) {
         log::debug!("suspended");
         self.active = false;
+
+        // Some platforms like Android destroy the native window on suspend
+        self.ctrl.view.suspend();
     }
 
     fn window_event(&mut self, el: &ActiveEventLoop, id: WindowId, event: WindowEvent) {
         use {
-            event::{ElementState, KeyEvent, MouseScrollDelta, WindowEvent},
+            event::{ElementState, Ime, KeyEvent, MouseScrollDelta, WindowEvent},
             event_loop::ControlFlow,
             keyboard::PhysicalKey,
             winit::dpi::{PhysicalPosition, PhysicalSize},
@@ -219,6 +261,24 @@ where
                 log::debug!("focused");
                 self.ctrl.view.request_redraw();
             }
+            WindowEvent::Focused(false) => {
+                log::debug!("unfocused");
+
+                // Release events won't come to the unfocused window
+                self.ctrl.held_keys.clear();
+                self.ctrl.modifiers = Modifiers::empty();
+                self.ctrl.touches.clear();
+            }
+            WindowEvent::Occluded(occluded) => {
+                log::debug!("occluded: {occluded}");
+                self.ctrl.occluded = occluded;
+                if !occluded {
+                    self.ctrl.view.request_redraw();
+
+                    // Don't count the hidden time as a frame time
+                    self.time.reset();
+                }
+            }
             WindowEvent::KeyboardInput {
                 event:
                     KeyEvent {
@@ -245,12 +305,50 @@ where
                 // TODO: Support key location
                 _ = location;
 
-                let key = Key { code, text };
+                let key = Key {
+                    code,
+                    text,
+                    modifiers: self.ctrl.modifiers,
+                };
+
+                // While composing, the text comes from the input method
+                if state == ElementState::Pressed && key.is_text() && self.ctrl.preedit.is_none() {
+                    let text = key.text.as_deref().unwrap_or_default();
+                    self.ctrl.text_input.push_str(text);
+                }
+
                 match state {
-                    ElementState::Pressed => self.ctrl.pressed_keys.push(key),
-                    ElementState::Released => self.ctrl.released_keys.push(key),
+                    ElementState::Pressed => {
+                        if !self.ctrl.held_keys.contains(&code) {
+                            self.ctrl.held_keys.push(code);
+                        }
+
+                        self.ctrl.pressed_keys.push(key);
+                    }
+                    ElementState::Released => {
+                        self.ctrl.held_keys.retain(|&held| held != code);
+                        self.ctrl.released_keys.push(key);
+                    }
                 }
             }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                log::debug!("modifiers changed: {:?}", modifiers.state());
+                self.ctrl.modifiers = modifiers.state();
+            }
+            WindowEvent::Ime(ime) => match ime {
+                Ime::Enabled => log::debug!("ime enabled"),
+                Ime::Preedit(text, _) => {
+                    self.ctrl.preedit = Some(text).filter(|text| !text.is_empty());
+                }
+                Ime::Commit(text) => {
+                    self.ctrl.preedit = None;
+                    push_text(&mut self.ctrl.text_input, &text);
+                }
+                Ime::Disabled => {
+                    log::debug!("ime disabled");
+                    self.ctrl.preedit = None;
+                }
+            },
             WindowEvent::CursorMoved {
                 position: PhysicalPosition { x, y },
                 ..
@@ -263,6 +361,32 @@ where
                 self.ctrl.mouse.wheel_delta.0 += x;
                 self.ctrl.mouse.wheel_delta.1 += y;
             }
+            WindowEvent::Touch(event::Touch {
+                id,
+                phase,
+                location: PhysicalPosition { x, y },
+                ..
+            }) => {
+                let position = (x as f32, y as f32);
+                self.ctrl.touch_events.push(TouchEvent {
+                    id,
+                    phase,
+                    position,
+                });
+
+                let touches = &mut self.ctrl.touches;
+                match phase {
+                    TouchPhase::Started => touches.push(Touch { id, position }),
+                    TouchPhase::Moved => {
+                        if let Some(touch) = touches.iter_mut().find(|touch| touch.id == id) {
+                            touch.position = position;
+                        }
+                    }
+                    TouchPhase::Ended | TouchPhase::Cancelled => {
+                        touches.retain(|touch| touch.id != id);
+                    }
+                }
+            }
             WindowEvent::MouseInput { state, button, .. } => match state {
                 ElementState::Pressed => self.ctrl.mouse.pressed_buttons.push(button),
                 ElementState::Released => self.ctrl.mouse.released_buttons.push(button),
@@ -278,6 +402,14 @@ where
                     return;
                 }
 
+                if self.ctrl.occluded && self.ctrl.pause_occluded.get() {
+                    log::debug!("redraw requested (occluded)");
+
+                    // Wait until the window becomes visible
+                    el.set_control_flow(ControlFlow::Wait);
+                    return;
+                }
+
                 let delta_time = self.time.delta();
                 let min_delta_time = self.ctrl.min_delta_time.get();
                 if delta_time < min_delta_time {
@@ -287,11 +419,19 @@ where
                 }
 
                 self.time.reset();
-                self.ctrl.delta_time = delta_time;
+                let max_delta_time = self.ctrl.max_delta_time.get();
+                self.ctrl.delta_time = Duration::min(delta_time, max_delta_time);
+                self.ctrl.smoothed_delta_time = time::smooth(
+                    self.ctrl.smoothed_delta_time,
+                    self.ctrl.delta_time,
+                    self.ctrl.delta_smoothing.get(),
+                );
+
                 if let Some(fps) = self.fps.count(delta_time) {
                     self.ctrl.fps = fps;
                 }
 
+                let start = instant::Instant::now();
                 let upd = self.upd.get();
                 match upd.update(&self.ctrl).flow() {
                     Then::Run => {}
@@ -314,6 +454,10 @@ where
                         let target = output.target();
                         self.cx.state().draw(target, &*upd);
                         output.present();
+                        self.ctrl
+                            .bench
+                            .get_mut()
+                            .record(delta_time, start.elapsed());
                     }
                     Err(SurfaceError::Timeout) => log::info!("suface error: timeout"),
                     Err(SurfaceError::Outdated) => log::info!("suface error: outdated"),
@@ -323,7 +467,9 @@ where
                     }
                     Err(SurfaceError::OutOfMemory) => {
                         log::error!("suface error: out of memory");
-                        el.exit();
+
+                        // Let the update stage free some memory, the frame is retried next time
+                        self.ctrl.out_of_memory = true;
                     }
                 }
             }
@@ -352,6 +498,7 @@ where
             StartCause::Poll => log::debug!("poll"),
             StartCause::Init => {
                 log::debug!("init");
+                self.cx.state().set_driven();
                 self.out = self
                     .ctrl
                     .view
@@ -367,8 +514,22 @@ where
         }
     }
 
-    fn user_event(&mut self, _: &ActiveEventLoop, ev: U::Event) {
-        self.upd.get().event(ev);
+    fn user_event(&mut self, _: &ActiveEventLoop, mes: Message<U::Event>) {
+        match mes {
+            Message::Wake => log::debug!("wake"),
+            Message::Event(ev) => self.upd.get().event(ev),
+        }
+
+        if self.active {
+            self.ctrl.view.request_redraw();
+        }
+    }
+
+    fn about_to_wait(&mut self, el: &ActiveEventLoop) {
+        if self.cx.state().poll_maps() {
+            // Come back soon to complete pending buffer mappings
+            el.set_control_flow(ControlFlow::wait_duration(Self::POLL_TIME));
+        }
     }
 }
 
@@ -376,13 +537,27 @@ where
 pub struct Control {
     view: View,
     resized: Option<(u32, u32)>,
+    out_of_memory: bool,
     min_delta_time: Cell<Duration>,
+    max_delta_time: Cell<Duration>,
+    delta_smoothing: Cell<f32>,
+    pause_occluded: Cell<bool>,
+    occluded: bool,
     delta_time: Duration,
+    smoothed_delta_time: Duration,
     fps: u32,
+    bench: RefCell<Bench>,
     pressed_keys: Vec<Key>,
     released_keys: Vec<Key>,
+    held_keys: Vec<KeyCode>,
+    modifiers: Modifiers,
+    text_input: String,
+    preedit: Option<String>,
     cursor_position: Option<(f32, f32)>,
     mouse: Mouse,
+    touch_events: Vec<TouchEvent>,
+    touches: Vec<Touch>,
+    prev_pair: Option<[Touch; 2]>,
 }
 
 impl Control {
@@ -390,23 +565,138 @@ impl Control {
         self.resized
     }
 
+    /// Checks if the previous frame failed because the surface ran out of memory.
+    ///
+    /// The loop keeps running and tries to draw the next frame as usual,
+    /// so this is the chance to free caches or lower the render resolution.
+    /// Return [`Then::Close`] or [`Then::Fail`] to stop the loop instead.
+    pub fn out_of_memory(&self) -> bool {
+        self.out_of_memory
+    }
+
     fn resize(&mut self, state: &State) {
         self.view.resize(state);
         self.resized = Some(self.view.size());
     }
 
+    /// Sets the minimum delta time.
+    ///
+    /// The loop doesn't draw a new frame until this time has passed since
+    /// the previous one, which limits the frame rate and saves the power.
+    /// Zero disables the limit. It's 1/60 of a second by default.
     pub fn set_min_delta_time(&self, min_delta_time: Duration) {
         self.min_delta_time.set(min_delta_time);
     }
 
+    /// Limits the frame rate to the given number of frames per second.
+    ///
+    /// The same as the [`set_min_delta_time`](Control::set_min_delta_time)
+    /// with the frame duration. Zero disables the limit.
+    pub fn set_max_fps(&self, fps: u32) {
+        let min_delta_time = match fps {
+            0 => Duration::ZERO,
+            fps => Duration::from_secs(1) / fps,
+        };
+
+        self.set_min_delta_time(min_delta_time);
+    }
+
+    /// Sets the maximum delta time.
+    ///
+    /// Longer frames, for example after the window was unfocused or the
+    /// process was paused, report this value as the [delta time](Control::delta_time),
+    /// so time-based animation doesn't jump too far ahead. Not limited by default.
+    pub fn set_max_delta_time(&self, max_delta_time: Duration) {
+        self.max_delta_time.set(max_delta_time);
+    }
+
+    /// Sets the smoothing factor of the [smoothed delta time](Control::smoothed_delta_time).
+    ///
+    /// The factor is clamped to the `[0, 1)` range. Zero disables the smoothing
+    /// and values close to one make the delta time change slower. Zero by default.
+    pub fn set_delta_smoothing(&self, factor: f32) {
+        self.delta_smoothing.set(factor.clamp(0., 0.99));
+    }
+
+    /// Sets whether the loop pauses while the window is occluded.
+    ///
+    /// When the window is minimized or completely hidden by other windows,
+    /// the update and draw stages aren't called until it becomes visible again,
+    /// so the app doesn't waste the battery. The delta time doesn't include
+    /// the paused time. Enabled by default.
+    ///
+    /// Note that not all platforms report the occlusion.
+    pub fn set_pause_when_occluded(&self, pause: bool) {
+        self.pause_occluded.set(pause);
+    }
+
+    /// Returns `true` if the window is occluded.
+    ///
+    /// It can be only observed if the [pause](Control::set_pause_when_occluded) is disabled.
+    pub fn occluded(&self) -> bool {
+        self.occluded
+    }
+
+    /// Returns the time elapsed since the previous frame.
+    ///
+    /// The value is limited by the [maximum delta time](Control::set_max_delta_time).
     pub fn delta_time(&self) -> Duration {
         self.delta_time
     }
 
+    /// Returns the exponential moving average of the [delta time](Control::delta_time).
+    ///
+    /// Unlike the raw delta time it doesn't spike on occasional hitches,
+    /// so it's better suited for animation. See [`set_delta_smoothing`](Control::set_delta_smoothing).
+    pub fn smoothed_delta_time(&self) -> Duration {
+        self.smoothed_delta_time
+    }
+
+    /// Returns the number of frames drawn during the last second.
+    ///
+    /// The value is updated once per second, so it can be shown in a HUD as is.
     pub fn fps(&self) -> u32 {
         self.fps
     }
 
+    /// Starts a benchmark of the given number of frames.
+    ///
+    /// The loop measures the time between frames and the CPU time
+    /// spent to update, draw and present each frame. When all frames are
+    /// measured, the [report](BenchReport) is available via the
+    /// [`benchmark_report`](Control::benchmark_report) function:
+    /// ```rust,ignore
+    /// if ctrl.pressed_keys().iter().any(|key| key.code == KeyCode::KeyB) {
+    ///     ctrl.set_max_fps(0);
+    ///     ctrl.start_benchmark(1000);
+    /// }
+    ///
+    /// if let Some(report) = ctrl.benchmark_report() {
+    ///     println!("{report}");
+    /// }
+    /// ```
+    /// To measure the actual cost of frames, disable the
+    /// [frame rate cap](Control::set_max_fps) and the window
+    /// [vsync](crate::window::WindowState::with_vsync).
+    /// Starting a new benchmark discards the previous one.
+    pub fn start_benchmark(&self, frames: usize) {
+        *self.bench.borrow_mut() = Bench::start(frames);
+    }
+
+    /// Takes the report of the finished benchmark.
+    ///
+    /// Returns `None` if there is no benchmark or it's still running.
+    pub fn benchmark_report(&self) -> Option<BenchReport> {
+        let mut bench = self.bench.borrow_mut();
+        match *bench {
+            Bench::Done(report) => {
+                *bench = Bench::Idle;
+                Some(report)
+            }
+            _ => None,
+        }
+    }
+
     pub fn pressed_keys(&self) -> &[Key] {
         &self.pressed_keys
     }
@@ -415,6 +705,42 @@ impl Control {
         &self.released_keys
     }
 
+    /// Returns `true` if the key is currently held down.
+    ///
+    /// Unlike [`pressed_keys`](Control::pressed_keys), the state persists
+    /// between frames until the key is released, which is useful for continuous motion.
+    pub fn is_key_down(&self, code: KeyCode) -> bool {
+        self.held_keys.contains(&code)
+    }
+
+    /// Returns the current state of modifier keys.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Returns the text entered since the previous frame.
+    ///
+    /// The text includes characters typed on the keyboard
+    /// and the text committed by an input method.
+    pub fn text_input(&self) -> &str {
+        &self.text_input
+    }
+
+    /// Returns the text being composed by an input method.
+    ///
+    /// When the composition is done, the result appears in the [text input](Control::text_input).
+    pub fn preedit(&self) -> Option<&str> {
+        self.preedit.as_deref()
+    }
+
+    /// Sets whether the input method is allowed for the window.
+    ///
+    /// The input method is disabled by default, enable it
+    /// when a text field is focused to receive composed text.
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        self.view.window().set_ime_allowed(allowed);
+    }
+
     pub fn cursor_position(&self) -> Option<(f32, f32)> {
         self.cursor_position
     }
@@ -434,11 +760,58 @@ impl Control {
         &self.mouse
     }
 
+    /// Returns touch events since the previous update in order of arrival.
+    pub fn touch_events(&self) -> &[TouchEvent] {
+        &self.touch_events
+    }
+
+    /// Returns touches currently held on the screen in order they started.
+    ///
+    /// Each touch has the latest position, so several touches together
+    /// describe a multi-touch gesture like a pinch.
+    pub fn touches(&self) -> &[Touch] {
+        &self.touches
+    }
+
+    /// Returns the scale change of a two-finger pinch since the previous update.
+    ///
+    /// The value is the ratio of the current distance between the first two
+    /// [touches](Control::touches) to the previous one, so it's greater than
+    /// one when the fingers spread and `1` when there is no pinch.
+    /// Multiply a zoom level by it to follow the fingers.
+    pub fn pinch(&self) -> f32 {
+        self.two_finger().map_or(1., |(scale, _)| scale)
+    }
+
+    /// Returns the angle in radians the first two [touches](Control::touches)
+    /// turned by since the previous update, or `0` when there is no rotation.
+    ///
+    /// The angle is positive when the fingers turn clockwise on the screen.
+    pub fn rotation(&self) -> f32 {
+        self.two_finger().map_or(0., |(_, angle)| angle)
+    }
+
+    fn two_finger(&self) -> Option<(f32, f32)> {
+        let prev = self.prev_pair?;
+        let [a, b, ..] = self.touches[..] else {
+            return None;
+        };
+
+        (prev[0].id == a.id && prev[1].id == b.id).then(|| two_finger(prev, [a, b]))?
+    }
+
     fn clear_state(&mut self) {
         self.pressed_keys.clear();
         self.released_keys.clear();
+        self.text_input.clear();
         self.resized = None;
+        self.out_of_memory = false;
         self.mouse.clear();
+        self.touch_events.clear();
+        self.prev_pair = match self.touches[..] {
+            [a, b, ..] => Some([a, b]),
+            _ => None,
+        };
     }
 }
 
@@ -455,6 +828,29 @@ impl ops::Deref for Control {
 pub struct Key {
     pub code: KeyCode,
     pub text: Option<SmolStr>,
+
+    /// The modifiers state at the moment the key was pressed or released.
+    pub modifiers: Modifiers,
+}
+
+impl Key {
+    /// Checks if the key produces a text character rather than
+    /// being a control key like arrows, backspace or escape.
+    ///
+    /// The text respects the keyboard layout, so use it instead
+    /// of mapping the [key code](Key::code) to characters.
+    pub fn is_text(&self) -> bool {
+        self.text
+            .as_ref()
+            .is_some_and(|text| !text.is_empty() && !text.chars().any(char::is_control))
+    }
+
+    /// Returns the character produced by the key if it's a single [text](Key::is_text) character.
+    pub fn char(&self) -> Option<char> {
+        let mut chars = self.text.as_ref().filter(|_| self.is_text())?.chars();
+        let ch = chars.next()?;
+        chars.next().is_none().then_some(ch)
+    }
 }
 
 /// Mouse input.
@@ -472,6 +868,52 @@ impl Mouse {
     }
 }
 
+/// A touch screen event.
+#[derive(Clone, Copy, Debug)]
+pub struct TouchEvent {
+    /// The unique identifier of a finger for the duration of the touch.
+    pub id: u64,
+    pub phase: TouchPhase,
+
+    /// The position in physical pixels.
+    pub position: (f32, f32),
+}
+
+/// A touch held on the screen.
+#[derive(Clone, Copy, Debug)]
+pub struct Touch {
+    /// The unique identifier of a finger for the duration of the touch.
+    pub id: u64,
+
+    /// The position in physical pixels.
+    pub position: (f32, f32),
+}
+
+/// Appends the text skipping control characters like backspace, enter or escape.
+fn push_text(input: &mut String, text: &str) {
+    input.extend(text.chars().filter(|c| !c.is_control()));
+}
+
+/// Returns the scale and the angle a pair of touches changed by.
+fn two_finger(prev: [Touch; 2], curr: [Touch; 2]) -> Option<(f32, f32)> {
+    let span = |[a, b]: [Touch; 2]| {
+        let (ax, ay) = a.position;
+        let (bx, by) = b.position;
+        (bx - ax, by - ay)
+    };
+
+    let (px, py) = span(prev);
+    let (cx, cy) = span(curr);
+    let prev_len = f32::hypot(px, py);
+    if prev_len == 0. {
+        return None;
+    }
+
+    let scale = f32::hypot(cx, cy) / prev_len;
+    let angle = f32::atan2(px * cy - py * cx, px * cx + py * cy);
+    Some((scale, angle))
+}
+
 /// Mouse buttons.
 pub struct Buttons(Vec<MouseButton>);
 
@@ -531,3 +973,31 @@ macro_rules! then {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_finger() {
+        let touch = |id, position| Touch { id, position };
+        let prev = [touch(0, (0., 0.)), touch(1, (2., 0.))];
+        let curr = [touch(0, (0., 0.)), touch(1, (0., 4.))];
+        let (scale, angle) = super::two_finger(prev, curr).expect("gesture");
+        assert_eq!(scale, 2.);
+        assert!((angle - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+
+        let prev = [touch(0, (1., 1.)), touch(1, (1., 1.))];
+        assert!(super::two_finger(prev, curr).is_none());
+    }
+
+    #[test]
+    fn push_text() {
+        let mut input = String::new();
+        for text in ["a", "\u{8}", "\r", "\u{1b}", "\t", "б", "c\r"] {
+            super::push_text(&mut input, text);
+        }
+
+        assert_eq!(input, "aбc");
+    }
+}
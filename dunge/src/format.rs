@@ -1,14 +1,36 @@
-use wgpu::TextureFormat;
+use wgpu::{Features, TextureFormat, TextureFormatFeatureFlags};
 
 /// The texture format type.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+///
+/// Shaders always work with linear colors. The sRGB formats are converted
+/// by the hardware: sampling decodes them to linear and drawing encodes
+/// the result back, so a linear color must not be encoded in the shader.
+/// Other formats store the values as is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum Format {
+    /// 8-bit RGBA color converted from and to sRGB by the hardware.
     #[default]
     SrgbAlpha,
+
+    /// 8-bit BGRA color converted from and to sRGB by the hardware.
     SbgrAlpha,
+
+    /// 8-bit RGBA values stored as is.
     RgbAlpha,
+
+    /// 8-bit BGRA values stored as is.
     BgrAlpha,
+
+    /// 16-bit float RGBA values stored as is, suitable for linear HDR colors.
+    RgbAlphaHalf,
+
+    /// 32-bit float depth.
     Depth,
+
+    /// 16-bit normalized depth.
+    Depth16,
+
+    /// 8-bit unsigned integer.
     Byte,
 }
 
@@ -16,30 +38,101 @@ impl Format {
     pub(crate) const fn bytes(self) -> u32 {
         match self {
             Self::SrgbAlpha | Self::SbgrAlpha | Self::RgbAlpha | Self::BgrAlpha | Self::Depth => 4,
+            Self::RgbAlphaHalf => 8,
+            Self::Depth16 => 2,
             Self::Byte => 1,
         }
     }
 
+    /// Checks if the format is converted from and to sRGB by the hardware.
+    pub const fn is_srgb(self) -> bool {
+        matches!(self, Self::SrgbAlpha | Self::SbgrAlpha)
+    }
+
+    /// Checks if the format is a depth format.
+    pub const fn is_depth(self) -> bool {
+        matches!(self, Self::Depth | Self::Depth16)
+    }
+
+    /// Checks if a layer can blend into a target of the format on any backend.
+    ///
+    /// Use the context's [`supports_blending`](crate::Context::supports_blending)
+    /// function to check it for the current adapter.
+    pub fn is_blendable(self) -> bool {
+        self.guaranteed_flags()
+            .contains(TextureFormatFeatureFlags::BLENDABLE)
+    }
+
+    /// Checks if a texture of the format can be sampled with
+    /// the [linear](crate::texture::Filter::Linear) filter on any backend.
+    ///
+    /// Use the context's [`supports_filtering`](crate::Context::supports_filtering)
+    /// function to check it for the current adapter.
+    pub fn is_filterable(self) -> bool {
+        self.guaranteed_flags()
+            .contains(TextureFormatFeatureFlags::FILTERABLE)
+    }
+
+    fn guaranteed_flags(self) -> TextureFormatFeatureFlags {
+        self.wgpu()
+            .guaranteed_format_features(Features::empty())
+            .flags
+    }
+
     pub(crate) const fn wgpu(self) -> TextureFormat {
         match self {
             Self::SrgbAlpha => TextureFormat::Rgba8UnormSrgb,
             Self::SbgrAlpha => TextureFormat::Bgra8UnormSrgb,
             Self::RgbAlpha => TextureFormat::Rgba8Unorm,
             Self::BgrAlpha => TextureFormat::Bgra8Unorm,
+            Self::RgbAlphaHalf => TextureFormat::Rgba16Float,
             Self::Depth => TextureFormat::Depth32Float,
+            Self::Depth16 => TextureFormat::Depth16Unorm,
             Self::Byte => TextureFormat::R8Uint,
         }
     }
 
     pub(crate) const fn from_wgpu(format: TextureFormat) -> Self {
+        match Self::try_from_wgpu(format) {
+            Some(format) => format,
+            None => panic!("unsupported format"),
+        }
+    }
+
+    pub(crate) const fn try_from_wgpu(format: TextureFormat) -> Option<Self> {
         match format {
-            TextureFormat::Rgba8UnormSrgb => Self::SrgbAlpha,
-            TextureFormat::Bgra8UnormSrgb => Self::SbgrAlpha,
-            TextureFormat::Rgba8Unorm => Self::RgbAlpha,
-            TextureFormat::Bgra8Unorm => Self::BgrAlpha,
-            TextureFormat::Depth32Float => Self::Depth,
-            TextureFormat::R8Uint => Self::Byte,
-            _ => panic!("unsupported format"),
+            TextureFormat::Rgba8UnormSrgb => Some(Self::SrgbAlpha),
+            TextureFormat::Bgra8UnormSrgb => Some(Self::SbgrAlpha),
+            TextureFormat::Rgba8Unorm => Some(Self::RgbAlpha),
+            TextureFormat::Bgra8Unorm => Some(Self::BgrAlpha),
+            TextureFormat::Rgba16Float => Some(Self::RgbAlphaHalf),
+            TextureFormat::Depth32Float => Some(Self::Depth),
+            TextureFormat::Depth16Unorm => Some(Self::Depth16),
+            TextureFormat::R8Uint => Some(Self::Byte),
+            _ => None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guaranteed_features() {
+        assert!(Format::RgbAlphaHalf.is_blendable());
+        assert!(Format::RgbAlphaHalf.is_filterable());
+        assert!(Format::SrgbAlpha.is_blendable());
+        assert!(!Format::Byte.is_blendable());
+        assert!(!Format::Byte.is_filterable());
+        assert!(!Format::Depth.is_filterable());
+    }
+
+    #[test]
+    fn srgb() {
+        assert!(Format::SrgbAlpha.is_srgb());
+        assert!(Format::SbgrAlpha.is_srgb());
+        assert!(!Format::RgbAlpha.is_srgb());
+        assert!(!Format::RgbAlphaHalf.is_srgb());
+    }
+}
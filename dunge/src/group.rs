@@ -1,10 +1,49 @@
 //! Shader group types and traits.
+//!
+//! # Optional members
+//! A group always binds all of its members, so there are no optional ones.
+//! If some resource is needed only on some layers, move it into a separate
+//! group and combine groups in a tuple. Then shaders that don't need the
+//! resource don't reference it, while the rest of the group is shared:
+//! ```rust
+//! use dunge::{
+//!     group::BoundTexture,
+//!     prelude::*,
+//!     sl::{self, Groups, Out},
+//!     texture::Sampler,
+//!     uniform::Uniform,
+//! };
+//!
+//! #[derive(Group)]
+//! struct Map<'a> {
+//!     tex: BoundTexture<'a>,
+//!     sam: &'a Sampler,
+//! }
+//!
+//! #[derive(Group)]
+//! struct Offset<'a>(&'a Uniform<f32>);
+//!
+//! // The shader only with the map
+//! let plain = |Groups(map): Groups<Map>| Out {
+//!     place: sl::splat_vec4(1.),
+//!     color: sl::texture_sample(map.tex, map.sam, sl::splat_vec2(0.)),
+//! };
+//!
+//! // The shader with the map and the offset
+//! let shifted = |Groups((map, offset)): Groups<(Map, Offset)>| Out {
+//!     place: sl::splat_vec4(offset.0),
+//!     color: sl::texture_sample(map.tex, map.sam, sl::splat_vec2(0.)),
+//! };
+//! # _ = (plain, shifted);
+//! ```
+//!
+//! The same `Map` value can be added to binders of both shaders.
 
 use crate::{
     sl::{GlobalOut, ReadGlobal, Ret},
     texture::{BindTexture, Sampler, Texture2d},
     types::{self, MemberType},
-    uniform::{Uniform, Value},
+    uniform::{DynamicUniform, Uniform, Value},
 };
 
 pub use dunge_shader::group::Projection;
@@ -23,6 +62,9 @@ impl<'a> BoundTexture<'a> {
 
 /// Describes a group member type projection.
 ///
+/// Members can be stored in a group either by value or by reference,
+/// both ways produce the same binding.
+///
 /// The trait is sealed because the derive macro relies on no new types being used.
 pub trait MemberProjection: private::Sealed {
     const TYPE: MemberType;
@@ -30,9 +72,23 @@ pub trait MemberProjection: private::Sealed {
     fn member_projection(id: u32, binding: u32, out: GlobalOut) -> Self::Field;
 }
 
-impl<V> private::Sealed for &Uniform<V> where V: Value {}
+impl<M> private::Sealed for &M where M: private::Sealed {}
+
+impl<M> MemberProjection for &M
+where
+    M: MemberProjection,
+{
+    const TYPE: MemberType = M::TYPE;
+    type Field = M::Field;
+
+    fn member_projection(id: u32, binding: u32, out: GlobalOut) -> Self::Field {
+        M::member_projection(id, binding, out)
+    }
+}
+
+impl<V> private::Sealed for Uniform<V> where V: Value {}
 
-impl<V> MemberProjection for &Uniform<V>
+impl<V> MemberProjection for Uniform<V>
 where
     V: Value,
 {
@@ -44,6 +100,20 @@ where
     }
 }
 
+impl<V> private::Sealed for DynamicUniform<V> where V: Value {}
+
+impl<V> MemberProjection for DynamicUniform<V>
+where
+    V: Value,
+{
+    const TYPE: MemberType = MemberType::Dynamic(V::TYPE);
+    type Field = Ret<ReadGlobal, V::Type>;
+
+    fn member_projection(id: u32, binding: u32, out: GlobalOut) -> Self::Field {
+        ReadGlobal::new(id, binding, Self::TYPE.is_value(), out)
+    }
+}
+
 impl private::Sealed for BoundTexture<'_> {}
 
 impl MemberProjection for BoundTexture<'_> {
@@ -55,9 +125,9 @@ impl MemberProjection for BoundTexture<'_> {
     }
 }
 
-impl private::Sealed for &Sampler {}
+impl private::Sealed for Sampler {}
 
-impl MemberProjection for &Sampler {
+impl MemberProjection for Sampler {
     const TYPE: MemberType = MemberType::Sampl;
     type Field = Ret<ReadGlobal, types::Sampler>;
 
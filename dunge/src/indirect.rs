@@ -0,0 +1,148 @@
+//! Indirect draw types.
+
+use {
+    crate::{context::Context, state::State},
+    wgpu::Buffer,
+};
+
+/// The arguments of one indirect draw.
+///
+/// The layout matches the one the GPU reads from an [indirect buffer](IndirectBuffer).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DrawIndirectArgs {
+    /// The number of vertices to draw.
+    pub vertex_count: u32,
+
+    /// The number of instances to draw.
+    pub instance_count: u32,
+
+    /// The index of the first vertex to draw.
+    pub first_vertex: u32,
+
+    /// The index of the first instance to draw.
+    ///
+    /// Must be zero unless the [`INDIRECT_FIRST_INSTANCE`](wgpu::Features::INDIRECT_FIRST_INSTANCE)
+    /// feature is [enabled](crate::ContextOptions::features) on the context.
+    pub first_instance: u32,
+}
+
+impl DrawIndirectArgs {
+    /// The size of the arguments in the buffer in bytes.
+    pub const SIZE: u64 = size_of::<[u32; 4]>() as u64;
+
+    fn words(self) -> [u32; 4] {
+        [
+            self.vertex_count,
+            self.instance_count,
+            self.first_vertex,
+            self.first_instance,
+        ]
+    }
+}
+
+/// The buffer of indirect draw arguments.
+///
+/// Can be created using the context's [`make_indirect_buffer`](crate::Context::make_indirect_buffer) function.
+///
+/// The buffer has a fixed number of entries and is drawn with the layer's
+/// `draw_indirect` or `multi_draw_indirect` functions:
+/// ```rust,ignore
+/// let args = DrawIndirectArgs {
+///     vertex_count: 3,
+///     instance_count: 1,
+///     ..Default::default()
+/// };
+///
+/// let buf = cx.make_indirect_buffer(&[args; 8]);
+///
+/// // Then in the draw stage
+/// frame.layer(&layer, opts).bind_empty().multi_draw_indirect(&buf, 8);
+/// ```
+///
+/// When the adapter supports storage buffers, the [buffer](IndirectBuffer::buffer)
+/// can also be bound as a storage buffer to fill the arguments on the GPU,
+/// for example, from a culling compute pass made with the context's
+/// [device](crate::Context::device).
+pub struct IndirectBuffer {
+    buf: Buffer,
+    len: u32,
+    multi: bool,
+}
+
+impl IndirectBuffer {
+    pub(crate) fn new(state: &State, args: &[DrawIndirectArgs]) -> Self {
+        use wgpu::{
+            util::{BufferInitDescriptor, DeviceExt},
+            BufferUsages, Features,
+        };
+
+        let len = args.len().try_into().expect("too many indirect draws");
+        let words: Vec<_> = args.iter().map(|args| args.words()).collect();
+        let buf = {
+            let mut usage = BufferUsages::INDIRECT | BufferUsages::COPY_DST;
+            let storage = state.device().limits().max_storage_buffers_per_shader_stage > 0;
+            usage.set(BufferUsages::STORAGE, storage);
+            let desc = BufferInitDescriptor {
+                label: None,
+                contents: bytemuck::cast_slice(&words),
+                usage,
+            };
+
+            state.device().create_buffer_init(&desc)
+        };
+
+        let multi = state
+            .device()
+            .features()
+            .contains(Features::MULTI_DRAW_INDIRECT);
+
+        Self { buf, len, multi }
+    }
+
+    /// Updates the draw arguments starting from the first entry.
+    ///
+    /// # Panics
+    /// Panics if there are more arguments than entries in the buffer.
+    pub fn update(&self, cx: &Context, args: &[DrawIndirectArgs]) {
+        assert!(
+            args.len() <= self.len as usize,
+            "too many arguments for the indirect buffer",
+        );
+
+        let words: Vec<_> = args.iter().map(|args| args.words()).collect();
+        let queue = cx.state().queue();
+        queue.write_buffer(&self.buf, 0, bytemuck::cast_slice(&words));
+    }
+
+    /// Returns the number of entries in the buffer.
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Checks if the buffer has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the wgpu buffer of the arguments.
+    ///
+    /// The arguments are laid out as an array of [`DrawIndirectArgs::SIZE`] byte entries,
+    /// each one is four `u32` words. The buffer has the
+    /// [`STORAGE`](wgpu::BufferUsages::STORAGE) usage if the adapter supports storage buffers,
+    /// so it can be bound with [`as_entire_binding`](Buffer::as_entire_binding).
+    pub fn buffer(&self) -> &Buffer {
+        &self.buf
+    }
+
+    pub(crate) fn check_multi(&self, count: u32) {
+        assert!(
+            self.multi,
+            "the MULTI_DRAW_INDIRECT feature is required for the multi draw",
+        );
+
+        assert!(
+            count <= self.len,
+            "the draw count exceeds the indirect buffer length",
+        );
+    }
+}
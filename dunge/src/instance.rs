@@ -3,6 +3,7 @@
 use {
     crate::{
         context::Context,
+        mesh::Aabb,
         sl::{ReadInstance, Ret},
         state::State,
         types::{self, ValueType, VectorType},
@@ -10,7 +11,7 @@ use {
         Instance,
     },
     std::{error, fmt, marker::PhantomData},
-    wgpu::{Buffer, RenderPass},
+    wgpu::{util::RenderEncoder, Buffer},
 };
 
 pub use dunge_shader::instance::Projection;
@@ -64,11 +65,11 @@ pub trait Set: Instance {
 pub struct Setter<'s, 'p> {
     len: Option<u32>,
     slot: u32,
-    pass: &'s mut RenderPass<'p>,
+    pass: &'s mut dyn RenderEncoder<'p>,
 }
 
 impl<'s, 'p> Setter<'s, 'p> {
-    pub(crate) fn new(slot: u32, pass: &'s mut RenderPass<'p>) -> Self {
+    pub(crate) fn new(slot: u32, pass: &'s mut dyn RenderEncoder<'p>) -> Self {
         Self {
             len: None,
             slot,
@@ -125,7 +126,7 @@ impl<U> Row<U> {
             let desc = BufferInitDescriptor {
                 label: None,
                 contents: bytemuck::cast_slice(data),
-                usage: BufferUsages::VERTEX,
+                usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
             };
 
             state.device().create_buffer_init(&desc)
@@ -139,6 +140,34 @@ impl<U> Row<U> {
         }
     }
 
+    /// Writes the data to the row replacing its length.
+    ///
+    /// The buffer is reallocated only when the data doesn't fit in it
+    /// or the buffer can't be written to.
+    pub(crate) fn write(&mut self, state: &State, data: &[U])
+    where
+        U: Value,
+    {
+        use wgpu::{BufferDescriptor, BufferUsages};
+
+        let data: &[u8] = bytemuck::cast_slice(data);
+        let size = data.len() as u64;
+        let usage = BufferUsages::VERTEX | BufferUsages::COPY_DST;
+        if size > self.buf.size() || !self.buf.usage().contains(usage) {
+            let desc = BufferDescriptor {
+                label: None,
+                size: size.next_power_of_two(),
+                usage,
+                mapped_at_creation: false,
+            };
+
+            self.buf = state.device().create_buffer(&desc);
+        }
+
+        state.queue().write_buffer(&self.buf, 0, data);
+        self.len = (data.len() / size_of::<U>()) as u32;
+    }
+
     pub fn update(&self, cx: &Context, data: &[U]) -> Result<(), UpdateError>
     where
         U: Value,
@@ -152,9 +181,36 @@ impl<U> Row<U> {
         queue.write_buffer(&self.buf, 0, data.as_ref());
         Ok(())
     }
+
+    /// Updates a part of the row starting from the element at the `offset`.
+    ///
+    /// Only the given elements are written, so it's cheaper than the whole
+    /// [update](Self::update) when a few instances of a large row change.
+    ///
+    /// # Errors
+    /// Returns an [`UpdateError`] if the range doesn't fit in the row.
+    pub fn update_range(&self, cx: &Context, offset: u32, data: &[U]) -> Result<(), UpdateError>
+    where
+        U: Value,
+    {
+        if !range_fits(offset, data.len(), self.len) {
+            return Err(UpdateError);
+        }
+
+        let queue = cx.state().queue();
+        let start = u64::from(offset) * size_of::<U>() as u64;
+        queue.write_buffer(&self.buf, start, bytemuck::cast_slice(data));
+        Ok(())
+    }
+}
+
+fn range_fits(offset: u32, len: usize, row: u32) -> bool {
+    let end = (offset as usize).checked_add(len);
+    end.is_some_and(|end| end <= row as usize)
 }
 
-/// An error returned from the [update](crate::instance::Row::update) function.
+/// An error returned from the [update](crate::instance::Row::update)
+/// and [`update_range`](crate::instance::Row::update_range) functions.
 ///
 /// Returned when passed data size is invalid.
 #[derive(Debug)]
@@ -168,6 +224,122 @@ impl fmt::Display for UpdateError {
 
 impl error::Error for UpdateError {}
 
+/// A placement of a scattered instance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Placement {
+    /// The position of the instance.
+    pub pos: [f32; 3],
+
+    /// The rotation angle around the y axis in radians.
+    pub angle: f32,
+}
+
+impl Placement {
+    /// Returns the transformation matrix of the placement.
+    pub fn matrix(self) -> glam::Mat4 {
+        use glam::{Quat, Vec3};
+
+        let rot = Quat::from_rotation_y(self.angle);
+        glam::Mat4::from_rotation_translation(rot, Vec3::from(self.pos))
+    }
+}
+
+/// Scatters instances in the bounding box with pseudo-random placements.
+///
+/// The placements are fully determined by the seed, the same seed gives
+/// bit-identical positions and angles on every platform, so a scene stays
+/// reproducible across runs. For example, to scatter trees on the ground:
+/// ```rust,ignore
+/// let ground = Aabb {
+///     min: [-50., 0., -50.],
+///     max: [50., 0., 50.],
+/// };
+///
+/// let matrices: Vec<_> = instance::scatter(42, 1000, ground)
+///     .into_iter()
+///     .map(|p| p.matrix())
+///     .collect();
+/// ```
+pub fn scatter(seed: u64, count: usize, bounds: Aabb) -> Vec<Placement> {
+    use std::f32::consts::TAU;
+
+    let mut rng = SplitMix(seed);
+    (0..count)
+        .map(|_| {
+            let pos = [0, 1, 2].map(|i| {
+                let (min, max) = (bounds.min[i], bounds.max[i]);
+                min + (max - min) * rng.unit()
+            });
+
+            let angle = rng.unit() * TAU;
+            Placement { pos, angle }
+        })
+        .collect()
+}
+
+/// The SplitMix64 generator, it's tiny and has no platform-dependent parts.
+struct SplitMix(u64);
+
+impl SplitMix {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in the `[0, 1)` range.
+    fn unit(&mut self) -> f32 {
+        (self.next() >> 40) as f32 / (1 << 24) as f32
+    }
+}
+
 mod private {
     pub trait Sealed {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_fits() {
+        assert!(super::range_fits(0, 3, 3));
+        assert!(super::range_fits(1, 2, 3));
+        assert!(super::range_fits(3, 0, 3));
+        assert!(!super::range_fits(2, 2, 3));
+        assert!(!super::range_fits(4, 0, 3));
+        assert!(!super::range_fits(u32::MAX, 1, 3));
+        assert!(
+            !super::range_fits(u32::MAX, usize::MAX, 3),
+            "the end overflows"
+        );
+    }
+
+    #[test]
+    fn scatter() {
+        let bounds = Aabb {
+            min: [-1., 0., 2.],
+            max: [1., 0., 4.],
+        };
+
+        let placements = super::scatter(7, 100, bounds);
+        assert_eq!(placements.len(), 100);
+        assert_eq!(placements, super::scatter(7, 100, bounds));
+        assert_ne!(placements, super::scatter(8, 100, bounds));
+        for Placement {
+            pos: [x, y, z],
+            angle,
+        } in placements
+        {
+            assert!((-1. ..1.).contains(&x));
+            assert_eq!(y, 0.);
+            assert!((2. ..4.).contains(&z));
+            assert!((0. ..std::f32::consts::TAU).contains(&angle));
+        }
+
+        let mut rng = SplitMix(0);
+        assert_eq!(rng.next(), 0xe220_a839_7b1d_cdaf);
+    }
+}
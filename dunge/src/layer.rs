@@ -4,19 +4,32 @@ use {
     crate::{
         bind::Binding,
         format::Format,
+        indirect::IndirectBuffer,
         instance::{Set, Setter},
         mesh::Mesh,
         shader::{Shader, Slots},
         state::State,
     },
-    std::{iter, marker::PhantomData},
-    wgpu::{BlendState, PrimitiveTopology, RenderPass, RenderPipeline},
+    std::{
+        collections::HashMap,
+        iter,
+        marker::PhantomData,
+        sync::{
+            atomic::{self, AtomicUsize},
+            Arc, Mutex, OnceLock, Weak,
+        },
+    },
+    wgpu::{
+        util::RenderEncoder, BlendState, Buffer, Face, PrimitiveTopology, RenderBundle,
+        RenderBundleEncoder, RenderPass, RenderPipeline,
+    },
 };
 
 pub struct SetLayer<'p, V, I> {
     shader_id: usize,
     no_bindings: bool,
     only_indexed_mesh: bool,
+    queries: u32,
     slots: Slots,
     pass: RenderPass<'p>,
     ty: PhantomData<(V, I)>,
@@ -35,12 +48,39 @@ impl<'p, V, I> SetLayer<'p, V, I> {
         );
 
         for (id, group) in iter::zip(0.., bind.groups) {
-            self.pass.set_bind_group(id, group, &[]);
+            self.pass
+                .set_bind_group(id, group.group(), &group.offsets());
         }
 
         SetBinding::new(self.only_indexed_mesh, self.slots, &mut self.pass)
     }
 
+    /// Inserts a debug marker into the layer's commands.
+    pub fn insert_debug_marker(&mut self, label: &str) {
+        self.pass.insert_debug_marker(label);
+    }
+
+    /// Starts the occlusion query with the given index.
+    ///
+    /// The query counts samples of the following draws which pass the depth test.
+    ///
+    /// # Panics
+    /// Panics if the frame has no [occlusion query](crate::query::OcclusionQuery)
+    /// set attached or the index is out of its range.
+    pub fn begin_occlusion_query(&mut self, index: u32) {
+        assert!(
+            index < self.queries,
+            "the occlusion query index is out of the attached query set",
+        );
+
+        self.pass.begin_occlusion_query(index);
+    }
+
+    /// Ends the current occlusion query.
+    pub fn end_occlusion_query(&mut self) {
+        self.pass.end_occlusion_query();
+    }
+
     #[inline]
     pub fn bind_empty(&mut self) -> SetBinding<'_, 'p, V, I> {
         assert!(self.no_bindings, "ths shader has any bindings");
@@ -51,12 +91,12 @@ impl<'p, V, I> SetLayer<'p, V, I> {
 pub struct SetBinding<'s, 'p, V, I> {
     only_indexed_mesh: bool,
     slots: Slots,
-    pass: &'s mut RenderPass<'p>,
+    pass: &'s mut dyn Encoder<'p>,
     ty: PhantomData<(V, I)>,
 }
 
 impl<'s, 'p, V, I> SetBinding<'s, 'p, V, I> {
-    fn new(only_indexed_mesh: bool, slots: Slots, pass: &'s mut RenderPass<'p>) -> Self {
+    fn new(only_indexed_mesh: bool, slots: Slots, pass: &'s mut dyn Encoder<'p>) -> Self {
         Self {
             only_indexed_mesh,
             slots,
@@ -70,7 +110,7 @@ impl<'s, 'p, V, I> SetBinding<'s, 'p, V, I> {
     where
         I: Set,
     {
-        let mut setter = Setter::new(self.slots.instance, self.pass);
+        let mut setter = Setter::new(self.slots.instance, self.pass.render());
         instance.set(&mut setter);
         SetInstance {
             only_indexed_mesh: self.only_indexed_mesh,
@@ -90,11 +130,11 @@ impl<'p, V> SetBinding<'_, 'p, V, ()> {
             "only an indexed mesh can be drawn on this layer",
         );
 
-        mesh.draw(self.pass, self.slots.vertex, 1);
+        mesh.draw(self.pass.render(), self.slots.vertex, 1);
     }
 }
 
-impl SetBinding<'_, '_, (), ()> {
+impl<'p> SetBinding<'_, 'p, (), ()> {
     #[inline]
     pub fn draw_points(&mut self, n: u32) {
         assert!(
@@ -102,7 +142,27 @@ impl SetBinding<'_, '_, (), ()> {
             "only an indexed mesh can be drawn on this layer",
         );
 
-        self.pass.draw(0..n, 0..1);
+        self.pass.render().draw(0..n, 0..1);
+    }
+
+    /// Draws points with the first arguments of the indirect buffer.
+    #[inline]
+    pub fn draw_indirect(&mut self, buf: &'p IndirectBuffer) {
+        draw_indirect(self.only_indexed_mesh, self.pass, buf);
+    }
+
+    /// Draws points with the first `count` arguments of the indirect buffer
+    /// in a single call.
+    ///
+    /// It requires the [`MULTI_DRAW_INDIRECT`](wgpu::Features::MULTI_DRAW_INDIRECT)
+    /// feature to be [enabled](crate::ContextOptions::features) on the context.
+    ///
+    /// # Panics
+    /// Panics if the feature is not enabled or
+    /// the `count` exceeds the [length](IndirectBuffer::len) of the buffer.
+    #[inline]
+    pub fn multi_draw_indirect(&mut self, buf: &'p IndirectBuffer, count: u32) {
+        multi_draw_indirect(self.only_indexed_mesh, self.pass, buf, count);
     }
 }
 
@@ -110,7 +170,7 @@ pub struct SetInstance<'s, 'p, V> {
     only_indexed_mesh: bool,
     len: u32,
     slots: Slots,
-    pass: &'s mut RenderPass<'p>,
+    pass: &'s mut dyn Encoder<'p>,
     ty: PhantomData<V>,
 }
 
@@ -122,11 +182,11 @@ impl<'p, V> SetInstance<'_, 'p, V> {
             "only an indexed mesh can be drawn on this layer",
         );
 
-        mesh.draw(self.pass, self.slots.vertex, self.len);
+        mesh.draw(self.pass.render(), self.slots.vertex, self.len);
     }
 }
 
-impl SetInstance<'_, '_, ()> {
+impl<'p> SetInstance<'_, 'p, ()> {
     #[inline]
     pub fn draw_points(&mut self, n: u32) {
         assert!(
@@ -134,16 +194,189 @@ impl SetInstance<'_, '_, ()> {
             "only an indexed mesh can be drawn on this layer",
         );
 
-        self.pass.draw(0..n, 0..self.len);
+        self.pass.render().draw(0..n, 0..self.len);
+    }
+
+    /// Draws points with the first arguments of the indirect buffer.
+    ///
+    /// The instance count is taken from the arguments.
+    #[inline]
+    pub fn draw_indirect(&mut self, buf: &'p IndirectBuffer) {
+        draw_indirect(self.only_indexed_mesh, self.pass, buf);
+    }
+
+    /// Draws points with the first `count` arguments of the indirect buffer
+    /// in a single call.
+    ///
+    /// See the binding's [`multi_draw_indirect`](SetBinding::multi_draw_indirect) for details.
+    #[inline]
+    pub fn multi_draw_indirect(&mut self, buf: &'p IndirectBuffer, count: u32) {
+        multi_draw_indirect(self.only_indexed_mesh, self.pass, buf, count);
+    }
+}
+
+fn draw_indirect<'p>(only_indexed_mesh: bool, pass: &mut dyn Encoder<'p>, buf: &'p IndirectBuffer) {
+    assert!(
+        !only_indexed_mesh,
+        "only an indexed mesh can be drawn on this layer",
+    );
+
+    assert!(!buf.is_empty(), "the indirect buffer is empty");
+    pass.render().draw_indirect(buf.buffer(), 0);
+}
+
+fn multi_draw_indirect<'p>(
+    only_indexed_mesh: bool,
+    pass: &mut dyn Encoder<'p>,
+    buf: &'p IndirectBuffer,
+    count: u32,
+) {
+    assert!(
+        !only_indexed_mesh,
+        "only an indexed mesh can be drawn on this layer",
+    );
+
+    buf.check_multi(count);
+    pass.multi_draw_indirect(buf.buffer(), count);
+}
+
+/// The render pass or the bundle encoder.
+trait Encoder<'p> {
+    fn render(&mut self) -> &mut dyn RenderEncoder<'p>;
+    fn multi_draw_indirect(&mut self, buf: &'p Buffer, count: u32);
+}
+
+impl<'p> Encoder<'p> for RenderPass<'p> {
+    fn render(&mut self) -> &mut dyn RenderEncoder<'p> {
+        self
+    }
+
+    fn multi_draw_indirect(&mut self, buf: &'p Buffer, count: u32) {
+        RenderPass::multi_draw_indirect(self, buf, 0, count);
+    }
+}
+
+impl<'p> Encoder<'p> for RenderBundleEncoder<'p> {
+    fn render(&mut self) -> &mut dyn RenderEncoder<'p> {
+        self
+    }
+
+    fn multi_draw_indirect(&mut self, buf: &'p Buffer, count: u32) {
+        use crate::indirect::DrawIndirectArgs;
+
+        // Bundles have no multi draw command, so the draws are recorded one by one
+        for n in 0..u64::from(count) {
+            self.draw_indirect(buf, n * DrawIndirectArgs::SIZE);
+        }
     }
 }
 
-#[derive(Clone, Copy, Default)]
+/// The recorder of a [bundle](Bundle).
+///
+/// It's passed to the closure of the context's
+/// [`make_bundle`](crate::Context::make_bundle) function
+/// and records draw commands the same way as a frame [layer](crate::Frame::layer).
+pub struct Recorder<'p, V, I> {
+    shader_id: usize,
+    no_bindings: bool,
+    only_indexed_mesh: bool,
+    slots: Slots,
+    encoder: RenderBundleEncoder<'p>,
+    ty: PhantomData<(V, I)>,
+}
+
+impl<'p, V, I> Recorder<'p, V, I> {
+    #[inline]
+    pub fn bind<B>(&mut self, bind: &'p B) -> SetBinding<'_, 'p, V, I>
+    where
+        B: Binding,
+    {
+        let bind = bind.binding();
+        assert!(
+            self.shader_id == bind.shader_id,
+            "the binding doesn't belong to this shader",
+        );
+
+        for (id, group) in iter::zip(0.., bind.groups) {
+            assert!(
+                !group.is_dynamic(),
+                "a bundle can't use dynamic uniforms, their offsets change on each update",
+            );
+
+            self.encoder
+                .set_bind_group(id, group.group(), &group.offsets());
+        }
+
+        SetBinding::new(self.only_indexed_mesh, self.slots, &mut self.encoder)
+    }
+
+    #[inline]
+    pub fn bind_empty(&mut self) -> SetBinding<'_, 'p, V, I> {
+        assert!(self.no_bindings, "ths shader has any bindings");
+        SetBinding::new(self.only_indexed_mesh, self.slots, &mut self.encoder)
+    }
+}
+
+/// Prerecorded draw commands.
+///
+/// Recording draw commands once and replaying them every frame is much cheaper
+/// for the CPU than recording them again, so a bundle suits static geometry.
+/// It can be created with the context's [`make_bundle`](crate::Context::make_bundle)
+/// function and executed with the frame's
+/// [`execute_bundle`](crate::Frame::execute_bundle) function.
+///
+/// The bundle keeps resources used in it alive, but it doesn't see later
+/// changes of the bindings or instances set, so it must be recorded again
+/// to draw something different. Updates of the uniforms and buffer contents
+/// are still visible, except for [dynamic uniforms](crate::uniform::DynamicUniform)
+/// which move to another slot on each update, so a bundle can't use them.
+pub struct Bundle {
+    depth: Option<Format>,
+    format: Format,
+    samples: u32,
+    bundle: RenderBundle,
+}
+
+impl Bundle {
+    pub fn depth(&self) -> bool {
+        self.depth.is_some()
+    }
+
+    /// Returns the depth format if the bundle has depth.
+    pub fn depth_format(&self) -> Option<Format> {
+        self.depth
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    pub(crate) fn bundle(&self) -> &RenderBundle {
+        &self.bundle
+    }
+}
+
+/// The blend mode of a layer.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub enum Blend {
+    /// Blending is disabled.
     #[default]
     None,
+
+    /// The new color replaces the old one.
     Replace,
+
+    /// Alpha blending with straight (non-premultiplied) colors.
     Alpha,
+
+    /// Alpha blending which expects colors with premultiplied alpha.
+    ///
+    /// See the [`premultiplied`](crate::color::Rgba::premultiplied) function.
+    Premultiplied,
 }
 
 impl Blend {
@@ -152,11 +385,12 @@ impl Blend {
             Self::None => None,
             Self::Replace => Some(BlendState::REPLACE),
             Self::Alpha => Some(BlendState::ALPHA_BLENDING),
+            Self::Premultiplied => Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
         }
     }
 }
 
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub enum Topology {
     PointList,
     LineList,
@@ -178,13 +412,125 @@ impl Topology {
     }
 }
 
-#[derive(Default)]
+/// The winding order of front-facing triangles.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum FrontFace {
+    /// Counter-clockwise triangles face front.
+    #[default]
+    Ccw,
+
+    /// Clockwise triangles face front.
+    Cw,
+}
+
+impl FrontFace {
+    fn wgpu(self) -> wgpu::FrontFace {
+        match self {
+            Self::Ccw => wgpu::FrontFace::Ccw,
+            Self::Cw => wgpu::FrontFace::Cw,
+        }
+    }
+}
+
+/// The faces of triangles which are not drawn.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CullMode {
+    /// Draw both faces.
+    None,
+
+    /// Don't draw front faces, for example, to draw an inverted hull outline.
+    Front,
+
+    /// Don't draw back faces.
+    #[default]
+    Back,
+}
+
+impl CullMode {
+    fn wgpu(self) -> Option<Face> {
+        match self {
+            Self::None => None,
+            Self::Front => Some(Face::Front),
+            Self::Back => Some(Face::Back),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Config {
     pub format: Format,
     pub blend: Blend,
     pub topology: Topology,
+
+    /// The winding order of front-facing triangles.
+    pub front_face: FrontFace,
+
+    /// The faces of triangles to cull.
+    pub cull: CullMode,
     pub indexed_mesh: bool,
     pub depth: bool,
+
+    /// The format of the depth buffer, it must match the format of
+    /// the target's depth texture. It's [`Format::Depth`] by default,
+    /// [`Format::Depth16`] saves the memory bandwidth on mobile GPUs.
+    pub depth_format: Format,
+
+    /// Writes the depth of drawn fragments to the depth buffer.
+    ///
+    /// It's `true` by default. Disable it to test transparent geometry against
+    /// the depth of opaque one without occluding other transparent fragments.
+    /// It has effect only when the [depth](Config::depth) test is enabled.
+    pub depth_write: bool,
+
+    /// The number of samples per pixel, it must match the
+    /// [sample count](crate::texture::TextureData::with_samples) of the target.
+    ///
+    /// If the count isn't [supported](crate::Context::supported_sample_counts)
+    /// for the formats, the layer falls back to the closest lower one with a warning,
+    /// so check the layer's [`samples`](Layer::samples) to create a matching target.
+    pub samples: u32,
+
+    /// Turns the output alpha into the coverage mask of the samples.
+    ///
+    /// This gives smooth edges to cutout geometry like foliage without
+    /// sorting it for blending. It only has effect with multisampling,
+    /// so it requires more than one [sample](Config::samples).
+    pub alpha_to_coverage: bool,
+
+    /// Clamps the depth of fragments instead of clipping the geometry
+    /// outside of the depth range, for example, to keep shadow casters
+    /// behind the light's near plane.
+    ///
+    /// It requires the [`DEPTH_CLIP_CONTROL`](wgpu::Features::DEPTH_CLIP_CONTROL)
+    /// feature to be [enabled](crate::ContextOptions::features) on the context.
+    pub depth_clamp: bool,
+
+    /// The color channels written to the target, others keep their previous values.
+    ///
+    /// For example, a motion vector pass can write only [`RED`](wgpu::ColorWrites::RED)
+    /// and [`GREEN`](wgpu::ColorWrites::GREEN) channels. It's
+    /// [`ALL`](wgpu::ColorWrites::ALL) by default.
+    pub write_mask: wgpu::ColorWrites,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            format: Format::default(),
+            blend: Blend::default(),
+            topology: Topology::default(),
+            front_face: FrontFace::default(),
+            cull: CullMode::default(),
+            indexed_mesh: false,
+            depth: false,
+            depth_format: Format::Depth,
+            depth_write: true,
+            samples: 1,
+            alpha_to_coverage: false,
+            depth_clamp: false,
+            write_mask: wgpu::ColorWrites::ALL,
+        }
+    }
 }
 
 impl From<Format> for Config {
@@ -201,81 +547,110 @@ pub struct Layer<V, I> {
     no_bindings: bool,
     only_indexed_mesh: bool,
     slots: Slots,
-    depth: bool,
+    depth: Option<Format>,
     format: Format,
-    render: RenderPipeline,
+    samples: u32,
+    render: Arc<RenderPipeline>,
     ty: PhantomData<(V, I)>,
 }
 
 impl<V, I> Layer<V, I> {
-    pub(crate) fn new(state: &State, shader: &Shader<V, I>, conf: &Config) -> Self {
-        use wgpu::*;
+    pub(crate) fn new(
+        state: &State,
+        shader: &Shader<V, I>,
+        conf: &Config,
+        label: Option<&str>,
+    ) -> Self {
+        assert!(
+            conf.depth_format.is_depth(),
+            "the layer's depth format must be a depth format",
+        );
 
-        let Config {
-            format,
-            blend,
-            topology,
-            indexed_mesh,
-            depth,
-        } = conf;
-
-        let targets = [Some(ColorTargetState {
-            format: format.wgpu(),
-            blend: blend.wgpu(),
-            write_mask: ColorWrites::ALL,
-        })];
-
-        let module = shader.module();
-        let buffers = shader.buffers();
-        let topology = topology.wgpu();
-        let only_indexed_mesh = *indexed_mesh && topology.is_strip();
-        let desc = RenderPipelineDescriptor {
-            label: None,
-            layout: Some(shader.layout()),
-            vertex: VertexState {
-                module,
-                entry_point: "vs",
-                compilation_options: PipelineCompilationOptions::default(),
-                buffers: &buffers,
-            },
-            primitive: PrimitiveState {
-                topology,
-                strip_index_format: only_indexed_mesh.then_some(IndexFormat::Uint16),
-                cull_mode: Some(Face::Back),
-                ..Default::default()
-            },
-            depth_stencil: depth.then_some(DepthStencilState {
-                format: Format::Depth.wgpu(),
-                depth_write_enabled: true,
-                depth_compare: CompareFunction::LessEqual,
-                stencil: StencilState::default(),
-                bias: DepthBiasState::default(),
-            }),
-            multisample: MultisampleState::default(),
-            fragment: Some(FragmentState {
-                module,
-                entry_point: "fs",
-                compilation_options: PipelineCompilationOptions::default(),
-                targets: &targets,
-            }),
-            multiview: None,
-            cache: None,
+        assert!(
+            !conf.depth_clamp
+                || state
+                    .device()
+                    .features()
+                    .contains(wgpu::Features::DEPTH_CLIP_CONTROL),
+            "depth clamp requires the DEPTH_CLIP_CONTROL feature enabled on the context",
+        );
+
+        let conf = &Config {
+            samples: fit_samples(state, conf),
+            ..*conf
         };
 
-        let render = state.device().create_render_pipeline(&desc);
+        assert!(
+            !conf.alpha_to_coverage || conf.samples > 1,
+            "alpha to coverage requires multisampling",
+        );
+
+        let only_indexed_mesh = conf.indexed_mesh && conf.topology.wgpu().is_strip();
+        let render = state.pipelines().get_or_insert((shader.id(), *conf), || {
+            make_pipeline(state, shader, conf, label)
+        });
+
         Self {
             shader_id: shader.id(),
             no_bindings: shader.groups().is_empty(),
             only_indexed_mesh,
             slots: shader.slots(),
-            depth: *depth,
-            format: *format,
+            depth: conf.depth.then_some(conf.depth_format),
+            format: conf.format,
+            samples: conf.samples,
             render,
             ty: PhantomData,
         }
     }
 
+    pub(crate) fn new_many(state: &State, layers: &[(&Shader<V, I>, Config)]) -> Vec<Self>
+    where
+        V: Send + Sync,
+        I: Send + Sync,
+    {
+        let make = |start: usize, layers: &[(&Shader<V, I>, Config)]| -> Vec<Self> {
+            iter::zip(start.., layers)
+                .map(|(n, (shader, conf))| {
+                    let label = format!("layer {n}");
+                    Self::new(state, shader, conf, Some(&label))
+                })
+                .collect()
+        };
+
+        #[cfg(not(target_family = "wasm"))]
+        {
+            use std::{num::NonZero, thread};
+
+            let threads = thread::available_parallelism().map_or(1, NonZero::get);
+            let chunk = usize::max(layers.len().div_ceil(threads), 1);
+            if chunk == layers.len() {
+                return make(0, layers);
+            }
+
+            thread::scope(|s| {
+                let handles: Vec<_> = iter::zip((0..).step_by(chunk), layers.chunks(chunk))
+                    .map(|(start, layers)| s.spawn(move || make(start, layers)))
+                    .collect();
+
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().expect("compile pipelines"))
+                    .collect()
+            })
+        }
+
+        #[cfg(target_family = "wasm")]
+        {
+            make(0, layers)
+        }
+    }
+
     pub fn depth(&self) -> bool {
+        self.depth.is_some()
+    }
+
+    /// Returns the depth format if the layer has depth.
+    pub fn depth_format(&self) -> Option<Format> {
         self.depth
     }
 
@@ -283,15 +658,260 @@ impl<V, I> Layer<V, I> {
         self.format
     }
 
-    pub(crate) fn set<'p>(&'p self, mut pass: RenderPass<'p>) -> SetLayer<'p, V, I> {
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    pub(crate) fn bundle<'p, F>(&'p self, state: &'p State, record: F) -> Bundle
+    where
+        F: FnOnce(&mut Recorder<'p, V, I>),
+    {
+        use wgpu::*;
+
+        let desc = RenderBundleEncoderDescriptor {
+            label: None,
+            color_formats: &[Some(self.format.wgpu())],
+            depth_stencil: self.depth.map(|format| RenderBundleDepthStencil {
+                format: format.wgpu(),
+                depth_read_only: false,
+                stencil_read_only: true,
+            }),
+            sample_count: self.samples,
+            multiview: None,
+        };
+
+        let mut encoder = state.device().create_render_bundle_encoder(&desc);
+        encoder.set_pipeline(&self.render);
+        let mut rec = Recorder {
+            shader_id: self.shader_id,
+            no_bindings: self.no_bindings,
+            only_indexed_mesh: self.only_indexed_mesh,
+            slots: self.slots,
+            encoder,
+            ty: PhantomData,
+        };
+
+        record(&mut rec);
+        let bundle = rec.encoder.finish(&RenderBundleDescriptor::default());
+        Bundle {
+            depth: self.depth,
+            format: self.format,
+            samples: self.samples,
+            bundle,
+        }
+    }
+
+    pub(crate) fn set<'p>(&'p self, mut pass: RenderPass<'p>, queries: u32) -> SetLayer<'p, V, I> {
         pass.set_pipeline(&self.render);
         SetLayer {
             shader_id: self.shader_id,
             no_bindings: self.no_bindings,
             only_indexed_mesh: self.only_indexed_mesh,
+            queries,
             slots: self.slots,
             pass,
             ty: PhantomData,
         }
     }
 }
+
+/// Returns the sample count closest to the requested one
+/// which is supported by both color and depth formats.
+fn fit_samples(state: &State, conf: &Config) -> u32 {
+    let mut counts = state.sample_counts(conf.format);
+    if conf.depth {
+        let depth = state.sample_counts(conf.depth_format);
+        counts.retain(|count| depth.contains(count));
+    }
+
+    if counts.contains(&conf.samples) {
+        return conf.samples;
+    }
+
+    let fit = counts
+        .iter()
+        .copied()
+        .filter(|&count| count <= conf.samples)
+        .max()
+        .or_else(|| counts.first().copied())
+        .expect("the format should support some sample count");
+
+    log::warn!(
+        "sample count {} is not supported, fall back to {fit}",
+        conf.samples,
+    );
+
+    fit
+}
+
+fn make_pipeline<V, I>(
+    state: &State,
+    shader: &Shader<V, I>,
+    conf: &Config,
+    label: Option<&str>,
+) -> RenderPipeline {
+    use wgpu::*;
+
+    let Config {
+        format,
+        blend,
+        topology,
+        front_face,
+        cull,
+        indexed_mesh,
+        depth,
+        depth_format,
+        depth_write,
+        samples,
+        alpha_to_coverage,
+        depth_clamp,
+        write_mask,
+    } = conf;
+
+    let targets = [Some(ColorTargetState {
+        format: format.wgpu(),
+        blend: blend.wgpu(),
+        write_mask: *write_mask,
+    })];
+
+    let module = shader.module();
+    let buffers = shader.buffers();
+    let topology = topology.wgpu();
+    let only_indexed_mesh = *indexed_mesh && topology.is_strip();
+    let desc = RenderPipelineDescriptor {
+        label,
+        layout: Some(shader.layout()),
+        vertex: VertexState {
+            module,
+            entry_point: "vs",
+            compilation_options: PipelineCompilationOptions::default(),
+            buffers: &buffers,
+        },
+        primitive: PrimitiveState {
+            topology,
+            strip_index_format: only_indexed_mesh.then_some(IndexFormat::Uint16),
+            front_face: front_face.wgpu(),
+            cull_mode: cull.wgpu(),
+            unclipped_depth: *depth_clamp,
+            ..Default::default()
+        },
+        depth_stencil: depth.then_some(DepthStencilState {
+            format: depth_format.wgpu(),
+            depth_write_enabled: *depth_write,
+            depth_compare: CompareFunction::LessEqual,
+            stencil: StencilState::default(),
+            bias: DepthBiasState::default(),
+        }),
+        multisample: MultisampleState {
+            count: *samples,
+            alpha_to_coverage_enabled: *alpha_to_coverage,
+            ..Default::default()
+        },
+        fragment: Some(FragmentState {
+            module,
+            entry_point: "fs",
+            compilation_options: PipelineCompilationOptions::default(),
+            targets: &targets,
+        }),
+        multiview: None,
+        cache: None,
+    };
+
+    state.device().create_render_pipeline(&desc)
+}
+
+/// Statistics of the pipeline cache.
+///
+/// Can be obtained with the context's
+/// [`pipeline_cache_stats`](crate::Context::pipeline_cache_stats) function.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PipelineCacheStats {
+    /// The number of layers that reused an existing pipeline.
+    pub hits: usize,
+
+    /// The number of layers that compiled a new pipeline.
+    pub misses: usize,
+
+    /// The number of pipelines currently alive in the cache.
+    pub len: usize,
+}
+
+type PipelineKey = (usize, Config);
+
+/// The cache of render pipelines shared between layers.
+///
+/// A pipeline is identified by its shader and the layer [config](Config).
+/// The cache holds weak references, so a pipeline is destroyed
+/// as soon as all its layers are dropped.
+#[derive(Default)]
+pub(crate) struct Pipelines {
+    map: Mutex<HashMap<PipelineKey, Slot>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+/// A cached pipeline, it's empty while the pipeline is compiling.
+type Slot = Arc<OnceLock<Weak<RenderPipeline>>>;
+
+fn is_alive(slot: &Slot) -> bool {
+    slot.get().map_or(true, |render| render.strong_count() > 0)
+}
+
+impl Pipelines {
+    fn get_or_insert<F>(&self, key: PipelineKey, make: F) -> Arc<RenderPipeline>
+    where
+        F: FnOnce() -> RenderPipeline,
+    {
+        let mut make = Some(make);
+        loop {
+            let slot = {
+                let mut map = self.map.lock().expect("lock pipeline cache");
+                match map.get(&key) {
+                    Some(slot) if is_alive(slot) => Arc::clone(slot),
+                    _ => {
+                        map.retain(|_, slot| is_alive(slot));
+                        let slot = Slot::default();
+                        map.insert(key, Arc::clone(&slot));
+                        slot
+                    }
+                }
+            };
+
+            // The lock isn't held while the pipeline is compiling, so different
+            // pipelines can be created concurrently, and concurrent requests
+            // of the same pipeline wait until it's compiled once
+            let mut made = None;
+            let render = slot.get_or_init(|| {
+                let make = make.take().expect("the pipeline is made once");
+                let render = Arc::new(make());
+                let weak = Arc::downgrade(&render);
+                made = Some(render);
+                weak
+            });
+
+            if let Some(render) = made {
+                self.misses.fetch_add(1, atomic::Ordering::Relaxed);
+                return render;
+            }
+
+            if let Some(render) = render.upgrade() {
+                self.hits.fetch_add(1, atomic::Ordering::Relaxed);
+                return render;
+            }
+
+            // The pipeline was dropped right after compiling, so make a new one
+        }
+    }
+
+    pub fn stats(&self) -> PipelineCacheStats {
+        let map = self.map.lock().expect("lock pipeline cache");
+        PipelineCacheStats {
+            hits: self.hits.load(atomic::Ordering::Relaxed),
+            misses: self.misses.load(atomic::Ordering::Relaxed),
+            len: map
+                .values()
+                .filter(|slot| slot.get().is_some_and(|render| render.strong_count() > 0))
+                .count(),
+        }
+    }
+}
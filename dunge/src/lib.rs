@@ -1,13 +1,21 @@
+extern crate self as dunge;
+
 pub mod bind;
+pub mod canvas;
 pub mod color;
 mod context;
 mod draw;
 mod format;
 pub mod group;
+pub mod indirect;
 pub mod instance;
 pub mod layer;
+pub mod light;
 pub mod mesh;
+pub mod post;
+pub mod query;
 mod shader;
+pub mod sprite;
 mod state;
 pub mod texture;
 pub mod uniform;
@@ -41,14 +49,17 @@ pub mod prelude {
 
 pub use {
     crate::{
-        context::{context, Context, FailedMakeContext},
+        context::{
+            context, context_from_wgpu, context_with, Context, ContextOptions, FailedMakeContext,
+            Limits,
+        },
         draw::{draw, Draw},
         format::Format,
-        state::{AsTarget, Frame, Options, RenderBuffer, Target},
+        state::{AsTarget, Frame, Load, Options, RenderBuffer, Target},
     },
     dunge_macros::{Group, Instance, Vertex},
     dunge_shader::{group::Group, instance::Instance, sl, types, vertex::Vertex},
-    glam,
+    glam, wgpu,
 };
 
 #[cfg(all(feature = "winit", not(target_arch = "wasm32")))]
@@ -59,6 +70,10 @@ pub use crate::window::from_element;
 
 #[cfg(feature = "winit")]
 pub use crate::{
-    el::{Buttons, Control, Flow, Key, KeyCode, LoopError, Mouse, MouseButton, SmolStr, Then},
+    el::{
+        Buttons, Control, Flow, Key, KeyCode, LoopError, Modifiers, Mouse, MouseButton, SmolStr,
+        Then, Touch, TouchEvent, TouchPhase,
+    },
+    time::{BenchReport, Percentiles},
     update::{make, update, update_with_event, update_with_state, IntoUpdate, Update},
 };
@@ -0,0 +1,205 @@
+//! Point light types.
+
+use {
+    crate::{
+        color::Rgb,
+        context::Context,
+        sl::{self, Eval, Fs, ReadGlobal, Ret, Thunk},
+        state::State,
+        types,
+        uniform::{Uniform, Value},
+    },
+    dunge_macros::Group,
+};
+
+/// The maximum number of point lights in the [lights](Lights) group.
+pub const MAX_POINT_LIGHTS: usize = 4;
+
+/// A point light source.
+#[derive(Clone, Copy)]
+pub struct PointLight {
+    /// The position of the light in world space.
+    pub pos: [f32; 3],
+
+    /// The linear color of the light.
+    pub color: Rgb,
+
+    /// The distance at which the light fades out completely.
+    ///
+    /// Must be positive, the light fades out with its distance divided by the radius.
+    pub radius: f32,
+}
+
+/// The group of point lights.
+///
+/// It can be created with the [`make_lights`](Context::make_lights) function
+/// and used in a shader as a [group](crate::Group) with the [`apply_point_lights`]
+/// function. The group holds up to [`MAX_POINT_LIGHTS`] lights,
+/// unused slots don't affect the result.
+#[derive(Group)]
+pub struct Lights {
+    src: Uniform<[[f32; 4]; 4]>,
+    col: Uniform<[[f32; 4]; 4]>,
+}
+
+impl Lights {
+    pub(crate) fn new(state: &State, lights: &[PointLight]) -> Self {
+        let (src, col) = pack(lights);
+        Self {
+            src: Uniform::new(state, src.value().as_ref(), None),
+            col: Uniform::new(state, col.value().as_ref(), None),
+        }
+    }
+
+    /// Updates the lights.
+    ///
+    /// # Panic
+    /// It will panic if there are more than [`MAX_POINT_LIGHTS`] lights
+    /// or a light [radius](PointLight::radius) isn't positive.
+    pub fn update(&self, cx: &Context, lights: &[PointLight]) {
+        let (src, col) = pack(lights);
+        self.src.update(cx, src);
+        self.col.update(cx, col);
+    }
+}
+
+fn pack(lights: &[PointLight]) -> ([[f32; 4]; 4], [[f32; 4]; 4]) {
+    assert!(
+        lights.len() <= MAX_POINT_LIGHTS,
+        "the number of point lights exceeds the maximum of {MAX_POINT_LIGHTS}",
+    );
+
+    // Positions are stored by components, so each column of the `src`
+    // matrix holds one coordinate of all lights and the last one holds radii.
+    // An unused light has zero color, so it gives no contribution.
+    let mut src = [
+        [0., 0., 0., 0.],
+        [0., 0., 0., 0.],
+        [0., 0., 0., 0.],
+        [1., 1., 1., 1.],
+    ];
+    let mut col = [[0.; 4]; 4];
+    for (n, light) in lights.iter().enumerate() {
+        assert!(
+            light.radius > 0.,
+            "the point light radius must be positive, got {}",
+            light.radius,
+        );
+
+        let [x, y, z] = light.pos;
+        src[0][n] = x;
+        src[1][n] = y;
+        src[2][n] = z;
+        src[3][n] = light.radius;
+        let [r, g, b] = light.color.0;
+        col[n] = [r, g, b, 0.];
+    }
+
+    (src, col)
+}
+
+/// Returns the sum of point lights contribution at the given world position.
+///
+/// The diffuse term of each light follows the Lambert's cosine law
+/// and fades out quadratically to zero at the light radius.
+/// The `normal` must be normalized. Multiply the result by the surface color
+/// and add an ambient term to get the final color:
+/// ```rust,ignore
+/// let light = light::apply_point_lights(sl::fragment(pos), sl::fragment(normal), lights);
+/// let color = sl::vec4_with(light * 0.9 + sl::splat_vec3(0.1), 1.);
+/// ```
+pub fn apply_point_lights<P, N>(
+    pos: P,
+    normal: N,
+    lights: LightsProjection,
+) -> impl Eval<Fs, Out = types::Vec3<f32>>
+where
+    P: Eval<Fs, Out = types::Vec3<f32>>,
+    N: Eval<Fs, Out = types::Vec3<f32>>,
+{
+    let pos = sl::thunk(pos);
+    let normal = sl::thunk(normal);
+    let light = |n| {
+        let src = |c| component(sl::thunk(column(lights.src.clone(), c)), n);
+        let lx = sl::thunk(src(0) - pos.clone().x());
+        let ly = sl::thunk(src(1) - pos.clone().y());
+        let lz = sl::thunk(src(2) - pos.clone().z());
+        let sq = lx.clone() * lx.clone() + ly.clone() * ly.clone() + lz.clone() * lz.clone();
+        let dist = sl::thunk(sl::sqrt(sq));
+        let dot = lx * normal.clone().x() + ly * normal.clone().y() + lz * normal.clone().z();
+        let diffuse = sl::clamp(dot / (dist.clone() + 0.0001), 0., 1.);
+        let fade = sl::thunk(sl::clamp(1. - dist / src(3), 0., 1.));
+        let col = sl::thunk(column(lights.col.clone(), n));
+        let col = sl::vec3(col.clone().x(), col.clone().y(), col.z());
+        col * (diffuse * fade.clone() * fade)
+    };
+
+    sl::thunk(light(0)) + sl::thunk(light(1)) + sl::thunk(light(2)) + sl::thunk(light(3))
+}
+
+fn column(mat: Ret<ReadGlobal, types::Mat4>, n: usize) -> impl Eval<Fs, Out = types::Vec4<f32>> {
+    match n {
+        0 => mat.x(),
+        1 => mat.y(),
+        2 => mat.z(),
+        _ => mat.w(),
+    }
+}
+
+fn component<A>(
+    vec: Ret<Thunk<A, Fs>, types::Vec4<f32>>,
+    n: usize,
+) -> Ret<Thunk<impl Eval<Fs, Out = f32>, Fs>, f32>
+where
+    A: Eval<Fs, Out = types::Vec4<f32>>,
+{
+    let c = match n {
+        0 => vec.x(),
+        1 => vec.y(),
+        2 => vec.z(),
+        _ => vec.w(),
+    };
+
+    sl::thunk(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::color::Color};
+
+    fn light(radius: f32) -> PointLight {
+        PointLight {
+            pos: [1., 2., 3.],
+            color: Color([0.5; 3]),
+            radius,
+        }
+    }
+
+    #[test]
+    fn pack() {
+        let (src, col) = super::pack(&[light(2.)]);
+        assert_eq!(
+            src,
+            [
+                [1., 0., 0., 0.],
+                [2., 0., 0., 0.],
+                [3., 0., 0., 0.],
+                [2., 1., 1., 1.]
+            ]
+        );
+        assert_eq!(col[0], [0.5, 0.5, 0.5, 0.]);
+        assert_eq!(col[1..], [[0.; 4]; 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the point light radius must be positive, got 0")]
+    fn zero_radius() {
+        super::pack(&[light(0.)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the point light radius must be positive, got NaN")]
+    fn nan_radius() {
+        super::pack(&[light(f32::NAN)]);
+    }
+}
@@ -1,9 +1,9 @@
 //! The mesh and mesh data types.
 
 use {
-    crate::{state::State, vertex, Vertex},
-    std::{borrow::Cow, error, fmt, marker::PhantomData},
-    wgpu::{Buffer, RenderPass},
+    crate::{context::Context, state::State, vertex, Vertex},
+    std::{borrow::Cow, error, fmt, iter, marker::PhantomData, sync::Arc},
+    wgpu::{util::RenderEncoder, Buffer, BufferAddress, BufferSlice},
 };
 
 type Face = [u16; 3];
@@ -11,13 +11,18 @@ type Face = [u16; 3];
 #[derive(Clone)]
 pub struct MeshData<'a, V> {
     verts: &'a [V],
+    bytes: Option<&'a [u8]>,
     indxs: Option<Cow<'a, [Face]>>,
 }
 
 impl<'a, V> MeshData<'a, V> {
     /// Creates a [mesh data](crate::mesh::MeshData) from given vertices.
     pub const fn from_verts(verts: &'a [V]) -> Self {
-        Self { verts, indxs: None }
+        Self {
+            verts,
+            bytes: None,
+            indxs: None,
+        }
     }
 
     /// Creates a [mesh data](crate::mesh::MeshData) from given vertices and indices.
@@ -26,12 +31,71 @@ impl<'a, V> MeshData<'a, V> {
     /// Returns an [error](crate::mesh::Error) if the passed data is incorrect.
     pub fn new(verts: &'a [V], indxs: &'a [Face]) -> Result<Self, Error> {
         let len: u16 = verts.len().try_into().map_err(|_| Error::TooManyVertices)?;
-        if let Some(index) = indxs.iter().flatten().copied().find(|&i| i >= len) {
-            return Err(Error::InvalidIndex { index });
+        check_indices(indxs, len)?;
+        let indxs = Some(Cow::Borrowed(indxs));
+        Ok(Self {
+            verts,
+            bytes: None,
+            indxs,
+        })
+    }
+
+    /// Creates a [mesh data](crate::mesh::MeshData) from raw bytes.
+    ///
+    /// The vertex bytes must contain vertices of the type `V`
+    /// laid out as the [vertex](crate::Vertex) type in memory, so the stride
+    /// is the size of the type. The optional index bytes contain triangles
+    /// of native-endian `u16` indices. The vertex bytes are used as is without
+    /// a copy, which is useful to load pre-baked geometry, for example from
+    /// a memory-mapped file. The index bytes are copied only if they are not aligned.
+    ///
+    /// # Errors
+    /// Returns an [error](crate::mesh::Error) if a length of the data isn't a multiple
+    /// of the element size or the indices are incorrect.
+    pub fn from_bytes(verts: &'a [u8], indxs: Option<&'a [u8]>) -> Result<Self, Error>
+    where
+        V: Vertex,
+    {
+        let stride = size_of::<V>();
+        if stride == 0 || verts.len() % stride != 0 {
+            let len = verts.len();
+            return Err(Error::InvalidLength { len, stride });
         }
 
-        let indxs = Some(Cow::Borrowed(indxs));
-        Ok(Self { verts, indxs })
+        let indxs = match indxs {
+            Some(indxs) => {
+                let size = size_of::<Face>();
+                if indxs.len() % size != 0 {
+                    let len = indxs.len();
+                    return Err(Error::InvalidLength { len, stride: size });
+                }
+
+                let indxs = match bytemuck::try_cast_slice(indxs) {
+                    Ok(indxs) => Cow::Borrowed(indxs),
+                    Err(_) => {
+                        let face = |face: &[u8]| {
+                            [0, 2, 4].map(|i| u16::from_ne_bytes([face[i], face[i + 1]]))
+                        };
+
+                        Cow::Owned(indxs.chunks_exact(size).map(face).collect())
+                    }
+                };
+
+                let len: u16 = (verts.len() / stride)
+                    .try_into()
+                    .map_err(|_| Error::TooManyVertices)?;
+
+                check_indices(&indxs, len)?;
+                Some(indxs)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            verts: &[],
+            bytes: Some(verts),
+            indxs,
+        })
     }
 
     /// Creates a [mesh data](crate::mesh::MeshData) from given quadrilaterals.
@@ -50,8 +114,244 @@ impl<'a, V> MeshData<'a, V> {
             Some(faces)
         };
 
-        Ok(Self { verts, indxs })
+        Ok(Self {
+            verts,
+            bytes: None,
+            indxs,
+        })
+    }
+
+    /// Computes the [bounding box](Aabb) of vertex positions.
+    ///
+    /// The position is the first field of the [vertex](crate::Vertex) type,
+    /// a two-dimensional position lies on the `z = 0` plane. Returns `None`
+    /// if the data has no vertices.
+    ///
+    /// # Panics
+    /// Panics if the first field isn't a 2D or 3D float vector.
+    pub fn bounds(&self) -> Option<Aabb>
+    where
+        V: Vertex,
+    {
+        use crate::types::VectorType;
+
+        let dims = match V::DEF.into_iter().next() {
+            Some(VectorType::Vec2f) => 2,
+            Some(VectorType::Vec3f) => 3,
+            _ => panic!("the first vertex field must be a 2D or 3D float vector"),
+        };
+
+        let bytes = self
+            .bytes
+            .unwrap_or_else(|| vertex::verts_as_bytes(self.verts));
+
+        bytes
+            .chunks_exact(size_of::<V>())
+            .map(|vert| {
+                let mut pos = [0.; 3];
+                for (p, bytes) in iter::zip(&mut pos[..dims], vert.chunks_exact(4)) {
+                    *p = f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                }
+
+                Aabb { min: pos, max: pos }
+            })
+            .reduce(Aabb::union)
+    }
+}
+
+/// An axis-aligned bounding box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    /// The minimum corner.
+    pub min: [f32; 3],
+
+    /// The maximum corner.
+    pub max: [f32; 3],
+}
+
+impl Aabb {
+    /// Returns the smallest box containing both boxes.
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            min: [0, 1, 2].map(|i| f32::min(self.min[i], other.min[i])),
+            max: [0, 1, 2].map(|i| f32::max(self.max[i], other.max[i])),
+        }
+    }
+
+    /// Returns the center of the box.
+    pub fn center(self) -> [f32; 3] {
+        [0, 1, 2].map(|i| (self.min[i] + self.max[i]) * 0.5)
+    }
+
+    /// Returns the size of the box along each axis.
+    pub fn size(self) -> [f32; 3] {
+        [0, 1, 2].map(|i| self.max[i] - self.min[i])
+    }
+}
+
+fn check_indices(indxs: &[Face], len: u16) -> Result<(), Error> {
+    match indxs.iter().flatten().position(|&i| i >= len) {
+        Some(pos) => Err(Error::InvalidIndex {
+            index: indxs[pos / 3][pos % 3],
+            pos,
+            len,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Expands line segments into quads of the given width.
+///
+/// Hardware lines are always one pixel wide, so thick lines are drawn as triangles.
+/// Each segment from the first point to the second one becomes a quad,
+/// segments of zero length are skipped. The `vert` function makes a vertex
+/// from its position and the edge coordinate, which is `-1` on the right side
+/// of the segment and `1` on the left one. Interpolated across the quad,
+/// the edge coordinate allows to smooth the line in the fragment shader,
+/// for example, by fading the alpha where its absolute value is close to one.
+///
+/// The quads are counter-clockwise in a coordinate system with the y axis pointing up
+/// and can be turned into a mesh with the [`from_quads`](MeshData::from_quads) function:
+/// ```rust,ignore
+/// let quads = mesh::thick_lines(&segments, 0.1, |pos, edge| Vert { pos, edge });
+/// let data = MeshData::from_quads(&quads)?;
+/// ```
+pub fn thick_lines<V, F>(segments: &[[[f32; 2]; 2]], width: f32, mut vert: F) -> Vec<[V; 4]>
+where
+    F: FnMut([f32; 2], f32) -> V,
+{
+    let half = width * 0.5;
+    segments
+        .iter()
+        .filter_map(|&[a, b]| {
+            let [dx, dy] = [b[0] - a[0], b[1] - a[1]];
+            let len = f32::hypot(dx, dy);
+            if len == 0. {
+                return None;
+            }
+
+            // The left normal scaled to the half width
+            let [nx, ny] = [-dy / len * half, dx / len * half];
+            Some([
+                vert([a[0] - nx, a[1] - ny], -1.),
+                vert([b[0] - nx, b[1] - ny], -1.),
+                vert([b[0] + nx, b[1] + ny], 1.),
+                vert([a[0] + nx, a[1] + ny], 1.),
+            ])
+        })
+        .collect()
+}
+
+/// Computes smooth per-vertex normals of an indexed mesh.
+///
+/// The normal of a vertex is the average of normals of the adjacent faces
+/// weighted by their area. Faces are counter-clockwise when viewed from the front.
+/// Degenerate faces don't contribute, and a vertex with no valid adjacent faces
+/// gets a zero normal. Since the [mesh data](MeshData) doesn't know which vertex
+/// field is a normal, the result is combined with the vertices by the caller:
+/// ```rust,ignore
+/// let normals = mesh::smooth_normals(&positions, &indxs);
+/// let verts: Vec<_> = iter::zip(positions, normals)
+///     .map(|(pos, norm)| Vert { pos, norm })
+///     .collect();
+///
+/// let data = MeshData::new(&verts, &indxs)?;
+/// ```
+///
+/// # Panics
+/// Panics if an index is out of bounds of the positions slice.
+pub fn smooth_normals(positions: &[[f32; 3]], indxs: &[Face]) -> Vec<[f32; 3]> {
+    use glam::Vec3;
+
+    let mut normals = vec![Vec3::ZERO; positions.len()];
+    for &face in indxs {
+        let [a, b, c] = face.map(|i| Vec3::from(positions[usize::from(i)]));
+
+        // The cross product length is twice the face area,
+        // so the sum is weighted by area and degenerate faces add nothing
+        let normal = (b - a).cross(c - a);
+        if !normal.is_finite() {
+            continue;
+        }
+
+        for i in face {
+            normals[usize::from(i)] += normal;
+        }
     }
+
+    normals
+        .into_iter()
+        .map(|normal| normal.normalize_or_zero().to_array())
+        .collect()
+}
+
+/// Computes per-vertex tangents of an indexed mesh for normal mapping.
+///
+/// Tangents point along the `u` texture coordinate. Face tangents are accumulated
+/// the same way as in MikkTSpace and then orthogonalized against the normals.
+/// The `w` component is the handedness: the bitangent is
+/// `cross(normal, tangent.xyz) * w` and points along the `v` coordinate.
+/// Since `v` grows downwards, a face textured as seen from the front gets `-1`
+/// and mirrored texture coordinates get `1`. Faces with degenerate texture
+/// coordinates don't contribute, and a vertex without valid adjacent faces gets
+/// an arbitrary tangent perpendicular to its normal. The normals are expected
+/// to be normalized, for example, made by the [`smooth_normals`] function:
+/// ```rust,ignore
+/// let normals = mesh::smooth_normals(&positions, &indxs);
+/// let tangents = mesh::tangents(&positions, &uvs, &normals, &indxs);
+/// ```
+///
+/// # Panics
+/// Panics if the slices have different lengths or an index is out of bounds.
+pub fn tangents(
+    positions: &[[f32; 3]],
+    uvs: &[[f32; 2]],
+    normals: &[[f32; 3]],
+    indxs: &[Face],
+) -> Vec<[f32; 4]> {
+    use glam::{Vec2, Vec3};
+
+    assert!(
+        positions.len() == uvs.len() && positions.len() == normals.len(),
+        "positions, texture coordinates and normals must have the same length",
+    );
+
+    let mut tans = vec![Vec3::ZERO; positions.len()];
+    let mut bitans = vec![Vec3::ZERO; positions.len()];
+    for &face in indxs {
+        let [a, b, c] = face.map(|i| Vec3::from(positions[usize::from(i)]));
+        let [ta, tb, tc] = face.map(|i| Vec2::from(uvs[usize::from(i)]));
+        let (e1, e2) = (b - a, c - a);
+        let (d1, d2) = (tb - ta, tc - ta);
+        let det = d1.perp_dot(d2);
+        if det == 0. || !det.is_finite() {
+            continue;
+        }
+
+        let tan = (e1 * d2.y - e2 * d1.y) / det;
+        let bitan = (e2 * d1.x - e1 * d2.x) / det;
+        for i in face {
+            tans[usize::from(i)] += tan;
+            bitans[usize::from(i)] += bitan;
+        }
+    }
+
+    iter::zip(normals, iter::zip(tans, bitans))
+        .map(|(&normal, (tan, bitan))| {
+            let normal = Vec3::from(normal);
+            let tan = (tan - normal * normal.dot(tan))
+                .try_normalize()
+                .unwrap_or_else(|| normal.any_orthonormal_vector());
+
+            let w = if normal.cross(tan).dot(bitan) < 0. {
+                -1.
+            } else {
+                1.
+            };
+
+            tan.extend(w).to_array()
+        })
+        .collect()
 }
 
 /// An error returned from the [mesh data](crate::mesh::MeshData) constructors.
@@ -61,14 +361,28 @@ pub enum Error {
     TooManyVertices,
 
     /// The vertex index is out of bounds of the vertex slice.
-    InvalidIndex { index: u16 },
+    ///
+    /// Contains the index value, its position in the flattened
+    /// index slice and the number of vertices.
+    InvalidIndex { index: u16, pos: usize, len: u16 },
+
+    /// The length of raw bytes isn't a multiple of the element size.
+    InvalidLength { len: usize, stride: usize },
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::TooManyVertices => write!(f, "too many vertices"),
-            Self::InvalidIndex { index } => write!(f, "invalid index: {index}"),
+            Self::InvalidIndex { index, pos, len } => write!(
+                f,
+                "invalid index {index} at position {pos} (face {}), \
+                the mesh has {len} vertices",
+                pos / 3,
+            ),
+            Self::InvalidLength { len, stride } => {
+                write!(f, "the length {len} isn't a multiple of {stride}")
+            }
         }
     }
 }
@@ -88,13 +402,14 @@ impl fmt::Display for TooManyVertices {
 impl error::Error for TooManyVertices {}
 
 pub struct Mesh<V> {
-    verts: Buffer,
-    indxs: Option<Buffer>,
+    verts: Box<[Span]>,
+    indxs: Option<Span>,
+    len: u32,
     ty: PhantomData<V>,
 }
 
 impl<V> Mesh<V> {
-    pub(crate) fn new(state: &State, data: &MeshData<V>) -> Self
+    pub(crate) fn new(state: &State, data: &MeshData<V>, label: Option<&str>) -> Self
     where
         V: Vertex,
     {
@@ -104,29 +419,88 @@ impl<V> Mesh<V> {
         };
 
         let device = state.device();
-        let verts = {
-            let desc = BufferInitDescriptor {
-                label: None,
-                contents: vertex::verts_as_bytes(data.verts),
-                usage: BufferUsages::VERTEX,
-            };
+        let bytes = data
+            .bytes
+            .unwrap_or_else(|| vertex::verts_as_bytes(data.verts));
 
-            device.create_buffer_init(&desc)
-        };
+        let verts = vertex::split_streams::<V>(bytes)
+            .iter()
+            .map(|contents| {
+                let desc = BufferInitDescriptor {
+                    label,
+                    contents,
+                    usage: BufferUsages::VERTEX,
+                };
+
+                Span::whole(device.create_buffer_init(&desc), contents.len())
+            })
+            .collect();
 
         let indxs = data.indxs.as_deref().map(|indxs| {
             let desc = BufferInitDescriptor {
-                label: None,
+                label,
                 contents: bytemuck::cast_slice(indxs),
                 usage: BufferUsages::INDEX,
             };
 
-            device.create_buffer_init(&desc)
+            Span::whole(device.create_buffer_init(&desc), desc.contents.len())
         });
 
         Self {
             verts,
             indxs,
+            len: (bytes.len() / size_of::<V>()) as u32,
+            ty: PhantomData,
+        }
+    }
+
+    /// Returns a mesh which shares the buffer of the given vertex stream.
+    ///
+    /// When a [vertex](crate::Vertex) type has several streams, its fields
+    /// are stored in separate buffers. A stream can be drawn on its own
+    /// with a layer for a vertex type containing only the stream's fields,
+    /// for example, to draw a depth pre-pass reading only positions:
+    /// ```rust,ignore
+    /// #[repr(C)]
+    /// #[derive(Vertex)]
+    /// struct Vert {
+    ///     pos: [f32; 3],
+    ///     #[stream(1)]
+    ///     col: [f32; 3],
+    /// }
+    ///
+    /// #[repr(C)]
+    /// #[derive(Vertex)]
+    /// struct Pos([f32; 3]);
+    ///
+    /// let positions: Mesh<Pos> = mesh.stream(0);
+    /// ```
+    /// The new mesh shares indices with this one.
+    ///
+    /// # Panics
+    /// Panics if the stream doesn't exist or
+    /// the fields of the stream don't match the type `P`.
+    pub fn stream<P>(&self, n: usize) -> Mesh<P>
+    where
+        V: Vertex,
+        P: Vertex,
+    {
+        let verts = self.verts.get(n).expect("the stream should exist");
+        let fields = V::DEF
+            .into_iter()
+            .enumerate()
+            .filter(|&(i, _)| V::STREAMS.get(i).map_or(0, |&s| s as usize) == n)
+            .map(|(_, vecty)| vecty);
+
+        assert!(
+            vertex::stream_count::<P>() == 1 && fields.eq(P::DEF),
+            "the vertex type doesn't match the stream",
+        );
+
+        Mesh {
+            verts: Box::new([verts.clone()]),
+            indxs: self.indxs.clone(),
+            len: self.len,
             ty: PhantomData,
         }
     }
@@ -135,20 +509,187 @@ impl<V> Mesh<V> {
         self.indxs.is_some()
     }
 
-    pub(crate) fn draw<'a>(&'a self, pass: &mut RenderPass<'a>, slot: u32, count: u32) {
+    pub(crate) fn draw<'a>(&'a self, pass: &mut dyn RenderEncoder<'a>, slot: u32, count: u32) {
         use wgpu::IndexFormat;
 
-        pass.set_vertex_buffer(slot, self.verts.slice(..));
+        for (slot, verts) in iter::zip(slot.., &self.verts) {
+            pass.set_vertex_buffer(slot, verts.slice());
+        }
+
         match &self.indxs {
             Some(indxs) => {
-                pass.set_index_buffer(indxs.slice(..), IndexFormat::Uint16);
-                let len = indxs.size() as u32 / size_of::<u16>() as u32;
+                pass.set_index_buffer(indxs.slice(), IndexFormat::Uint16);
+                let len = indxs.size as u32 / size_of::<u16>() as u32;
                 pass.draw_indexed(0..len, 0, 0..count);
             }
-            None => {
-                let len = self.verts.size() as u32 / size_of::<V>() as u32;
-                pass.draw(0..len, 0..count);
-            }
+            None => pass.draw(0..self.len, 0..count),
+        }
+    }
+}
+
+/// An arena of transient meshes.
+///
+/// Can be created using the context's [`make_mesh_arena`](crate::Context::make_mesh_arena) function.
+///
+/// Meshes rebuilt every frame, for example, by an immediate mode UI, would
+/// create new GPU buffers each time. Instead, the arena writes their data into
+/// its own buffer and returns meshes referencing parts of it. The arena is
+/// [reset](MeshArena::reset) before each frame, so the memory is reused:
+/// ```rust,ignore
+/// let mut arena = cx.make_mesh_arena();
+///
+/// // Then before each frame
+/// arena.reset();
+/// let mesh = arena.alloc(&cx, &data);
+/// let draw = dunge::draw(|mut frame| {
+///     frame.layer(&layer, opts).bind(&bind).draw(&mesh);
+/// });
+///
+/// cx.draw_to(&target, draw);
+/// ```
+/// When the buffer runs out of space, the arena allocates a bigger one and
+/// keeps it, so after a few frames the allocations stop.
+///
+/// A mesh of the arena remains valid until the next reset,
+/// after that its memory is overwritten with new meshes.
+pub struct MeshArena {
+    bufs: Vec<Arc<Buffer>>,
+    offset: BufferAddress,
+    used: BufferAddress,
+    reserve: BufferAddress,
+}
+
+impl MeshArena {
+    const MIN_SIZE: BufferAddress = 1 << 12;
+
+    pub(crate) fn new() -> Self {
+        Self {
+            bufs: vec![],
+            offset: 0,
+            used: 0,
+            reserve: 0,
+        }
+    }
+
+    /// Allocates a [mesh](Mesh) with the given data in the arena.
+    pub fn alloc<V>(&mut self, cx: &Context, data: &MeshData<V>) -> Mesh<V>
+    where
+        V: Vertex,
+    {
+        let state = cx.state();
+        let bytes = data
+            .bytes
+            .unwrap_or_else(|| vertex::verts_as_bytes(data.verts));
+
+        let verts = vertex::split_streams::<V>(bytes)
+            .iter()
+            .map(|contents| self.push(state, contents))
+            .collect();
+
+        let indxs = data
+            .indxs
+            .as_deref()
+            .map(|indxs| self.push(state, bytemuck::cast_slice(indxs)));
+
+        Mesh {
+            verts,
+            indxs,
+            len: (bytes.len() / size_of::<V>()) as u32,
+            ty: PhantomData,
+        }
+    }
+
+    /// Resets the arena, so new meshes reuse its memory.
+    ///
+    /// If the previous frame didn't fit in one buffer,
+    /// the arena replaces its buffers with a bigger one.
+    pub fn reset(&mut self) {
+        if self.bufs.len() > 1 {
+            self.bufs.clear();
+            self.reserve = self.used;
+        }
+
+        self.offset = 0;
+        self.used = 0;
+    }
+
+    /// Returns the number of bytes allocated since the last reset.
+    pub fn allocated(&self) -> BufferAddress {
+        self.used
+    }
+
+    fn push(&mut self, state: &State, contents: &[u8]) -> Span {
+        use wgpu::{BufferDescriptor, BufferUsages, COPY_BUFFER_ALIGNMENT};
+
+        let size = contents.len() as BufferAddress;
+        let padded = size.next_multiple_of(COPY_BUFFER_ALIGNMENT);
+        let fits = self
+            .bufs
+            .last()
+            .is_some_and(|buf| self.offset + padded <= buf.size());
+
+        if !fits {
+            let size = (self.used + padded)
+                .max(self.reserve)
+                .next_power_of_two()
+                .max(Self::MIN_SIZE);
+
+            let desc = BufferDescriptor {
+                label: None,
+                size,
+                usage: BufferUsages::VERTEX | BufferUsages::INDEX | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            };
+
+            self.bufs
+                .push(Arc::new(state.device().create_buffer(&desc)));
+            self.offset = 0;
+            self.reserve = 0;
+        }
+
+        let buf = self.bufs.last().expect("the arena should have a buffer");
+        if size == padded {
+            state.queue().write_buffer(buf, self.offset, contents);
+        } else {
+            let mut contents = contents.to_vec();
+            contents.resize(padded as usize, 0);
+            state.queue().write_buffer(buf, self.offset, &contents);
+        }
+
+        let span = Span {
+            buf: Arc::clone(buf),
+            offset: self.offset,
+            size,
+        };
+
+        self.offset += padded;
+        self.used += padded;
+        span
+    }
+}
+
+/// A part of a buffer occupied by a mesh.
+#[derive(Clone)]
+struct Span {
+    buf: Arc<Buffer>,
+    offset: BufferAddress,
+    size: BufferAddress,
+}
+
+impl Span {
+    fn whole(buf: Buffer, size: usize) -> Self {
+        Self {
+            buf: Arc::new(buf),
+            offset: 0,
+            size: size as BufferAddress,
+        }
+    }
+
+    fn slice(&self) -> BufferSlice<'_> {
+        // a buffer slice can't be empty, so an empty span takes the rest of the buffer
+        match self.size {
+            0 => self.buf.slice(self.offset..),
+            size => self.buf.slice(self.offset..self.offset + size),
         }
     }
 }
@@ -169,4 +710,237 @@ mod tests {
         assert_eq!([data.verts[4], data.verts[5], data.verts[6]], indxs[2]);
         assert_eq!([data.verts[4], data.verts[6], data.verts[7]], indxs[3]);
     }
+
+    #[test]
+    fn bounds() {
+        #[repr(C)]
+        #[derive(Vertex)]
+        struct Vert([f32; 2], [f32; 3]);
+
+        let verts = [
+            Vert([0., -0.75], [1., 0., 0.]),
+            Vert([0.866, 0.75], [0., 1., 0.]),
+            Vert([-0.866, 0.5], [0., 0., 1.]),
+        ];
+
+        let aabb = MeshData::from_verts(&verts).bounds().expect("bounds");
+        assert_eq!(aabb.min, [-0.866, -0.75, 0.]);
+        assert_eq!(aabb.max, [0.866, 0.75, 0.]);
+        assert_eq!(aabb.center(), [0., 0., 0.]);
+
+        let empty: MeshData<Vert> = MeshData::from_verts(&[]);
+        assert_eq!(empty.bounds(), None);
+    }
+
+    #[test]
+    fn thick_lines() {
+        let segments = [[[0., 0.], [2., 0.]], [[1., 1.], [1., 1.]]];
+        let quads = super::thick_lines(&segments, 1., |pos, edge| (pos, edge));
+        assert_eq!(quads.len(), 1, "zero length segment should be skipped");
+        assert_eq!(
+            quads[0],
+            [
+                ([0., -0.5], -1.),
+                ([2., -0.5], -1.),
+                ([2., 0.5], 1.),
+                ([0., 0.5], 1.),
+            ],
+        );
+    }
+
+    #[test]
+    fn smooth_normals() {
+        let positions = [
+            [0., 0., 0.],
+            [1., 0., 0.],
+            [1., 1., 0.],
+            [0., 1., 0.],
+            [2., 0., 0.],
+        ];
+        let indxs = [[0, 1, 2], [0, 2, 3], [0, 1, 4]];
+        let normals = super::smooth_normals(&positions, &indxs);
+        assert_eq!(normals[..4], [[0., 0., 1.]; 4]);
+        assert_eq!(normals[4], [0., 0., 0.], "degenerate face adds nothing");
+
+        let tilted = [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [0., 0., 1.]];
+        let normals = super::smooth_normals(&tilted, &[[0, 1, 2], [0, 3, 1]]);
+        let [x, y, z] = normals[0];
+        assert!((x - 0.).abs() < 1e-6);
+        assert!((y - 0.70710677).abs() < 1e-6);
+        assert!((z - 0.70710677).abs() < 1e-6);
+    }
+
+    #[test]
+    fn tangents() {
+        // Two quads facing +z, the second one has mirrored texture coordinates
+        let positions = [
+            [0., 0., 0.],
+            [1., 0., 0.],
+            [1., 1., 0.],
+            [0., 1., 0.],
+            [2., 0., 0.],
+            [3., 0., 0.],
+            [3., 1., 0.],
+            [2., 1., 0.],
+        ];
+
+        let uvs = [
+            [0., 1.],
+            [1., 1.],
+            [1., 0.],
+            [0., 0.],
+            [1., 1.],
+            [0., 1.],
+            [0., 0.],
+            [1., 0.],
+        ];
+
+        let indxs = [[0, 1, 2], [0, 2, 3], [4, 5, 6], [4, 6, 7]];
+        let normals = super::smooth_normals(&positions, &indxs);
+        let tangents = super::tangents(&positions, &uvs, &normals, &indxs);
+        assert_eq!(tangents[..4], [[1., 0., 0., -1.]; 4]);
+        assert_eq!(tangents[4..], [[-1., 0., 0., 1.]; 4]);
+
+        let degenerate =
+            super::tangents(&positions[..3], &[[0., 0.]; 3], &normals[..3], &[[0, 1, 2]]);
+        for [x, y, z, _] in degenerate {
+            assert!(z.abs() < 1e-6 && (x * x + y * y - 1.).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn tangents_cube() {
+        use glam::{Mat3, Vec3};
+
+        // Each side of a cube as its outward normal, right and up directions
+        // as seen from the outside
+        let sides = [
+            (Vec3::Z, Vec3::X, Vec3::Y),
+            (Vec3::NEG_Z, Vec3::NEG_X, Vec3::Y),
+            (Vec3::X, Vec3::NEG_Z, Vec3::Y),
+            (Vec3::NEG_X, Vec3::Z, Vec3::Y),
+            (Vec3::Y, Vec3::X, Vec3::NEG_Z),
+            (Vec3::NEG_Y, Vec3::X, Vec3::Z),
+        ];
+
+        let mut positions = vec![];
+        let mut uvs = vec![];
+        let mut normals = vec![];
+        let mut indxs = vec![];
+        for &(normal, right, up) in &sides {
+            let n = positions.len() as u16;
+            let center = normal * 0.5;
+            for ([x, y], uv) in [
+                ([-0.5, 0.5], [0., 0.]),
+                ([-0.5, -0.5], [0., 1.]),
+                ([0.5, -0.5], [1., 1.]),
+                ([0.5, 0.5], [1., 0.]),
+            ] {
+                positions.push((center + right * x + up * y).to_array());
+                uvs.push(uv);
+                normals.push(normal.to_array());
+            }
+
+            indxs.extend([[n, n + 1, n + 2], [n, n + 2, n + 3]]);
+        }
+
+        let tangents = super::tangents(&positions, &uvs, &normals, &indxs);
+        let eq = |a: Vec3, b: Vec3| (a - b).length() < 1e-6;
+        for (side, &(normal, right, up)) in iter::zip(tangents.chunks(4), &sides) {
+            for &[x, y, z, w] in side {
+                let tan = Vec3::new(x, y, z);
+                let bitan = normal.cross(tan) * w;
+                assert!(eq(tan, right), "the tangent follows u on the {normal} side");
+                assert!(
+                    eq(bitan, -up),
+                    "the bitangent follows v on the {normal} side"
+                );
+                assert_eq!(w, -1.);
+
+                // A flat normal map keeps the surface normal, a tilted one leans
+                // to the right or down on the side
+                let tbn = Mat3::from_cols(tan, bitan, normal);
+                assert!(eq(tbn * Vec3::Z, normal));
+                assert!(eq(tbn * Vec3::new(1., 0., 1.), right + normal));
+                assert!(eq(tbn * Vec3::new(0., 1., 1.), normal - up));
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_index() {
+        let verts = [0, 1, 2, 3];
+        let err = MeshData::new(&verts, &[[0, 1, 2], [2, 4, 3]]).err();
+        assert_eq!(
+            err.map(|err| err.to_string()).as_deref(),
+            Some("invalid index 4 at position 4 (face 1), the mesh has 4 vertices"),
+        );
+    }
+
+    #[test]
+    fn from_bytes() {
+        #[repr(C)]
+        #[derive(Vertex)]
+        struct Vert([f32; 2]);
+
+        let verts = [0_u8; 24];
+        let indxs: Vec<u8> = [0_u16, 1, 2].iter().flat_map(|i| i.to_ne_bytes()).collect();
+        let data = MeshData::<Vert>::from_bytes(&verts, Some(&indxs)).expect("mesh data");
+        assert_eq!(data.bytes.map(<[_]>::len), Some(24));
+        assert_eq!(data.indxs.as_deref(), Some(&[[0, 1, 2]][..]));
+
+        let err = MeshData::<Vert>::from_bytes(&verts[..20], None).err();
+        assert!(matches!(
+            err,
+            Some(Error::InvalidLength { len: 20, stride: 8 })
+        ));
+
+        let indxs: Vec<u8> = [0_u16, 1, 3].iter().flat_map(|i| i.to_ne_bytes()).collect();
+        let err = MeshData::<Vert>::from_bytes(&verts, Some(&indxs)).err();
+        assert!(matches!(
+            err,
+            Some(Error::InvalidIndex {
+                index: 3,
+                pos: 2,
+                len: 3,
+            })
+        ));
+    }
+
+    #[test]
+    fn split_streams() {
+        #[repr(C)]
+        #[derive(Vertex)]
+        struct Vert {
+            pos: [f32; 2],
+            #[stream(1)]
+            col: [f32; 3],
+            uv: [f32; 2],
+        }
+
+        let verts = [
+            Vert {
+                pos: [0., 1.],
+                col: [2., 3., 4.],
+                uv: [5., 6.],
+            },
+            Vert {
+                pos: [7., 8.],
+                col: [9., 10., 11.],
+                uv: [12., 13.],
+            },
+        ];
+
+        let streams = vertex::split_streams::<Vert>(vertex::verts_as_bytes(&verts));
+        let floats: Vec<Vec<f32>> = streams
+            .iter()
+            .map(|stream| {
+                let float = |b: &[u8]| f32::from_ne_bytes([b[0], b[1], b[2], b[3]]);
+                stream.chunks_exact(4).map(float).collect()
+            })
+            .collect();
+
+        assert_eq!(floats[0], [0., 1., 5., 6., 7., 8., 12., 13.]);
+        assert_eq!(floats[1], [2., 3., 4., 9., 10., 11.]);
+    }
 }
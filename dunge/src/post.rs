@@ -0,0 +1,752 @@
+//! Post-processing effects.
+
+use {
+    crate::{
+        bind::{Bind, Binding, UniqueBinding},
+        context::Context,
+        draw,
+        format::Format,
+        group::BoundTexture,
+        layer::Layer,
+        shader::Shader,
+        sl::{self, Eval, Fs, Groups, Index, Out, Ret, Vs},
+        state::{Frame, Options},
+        texture::{self, BindTexture, Filter, Sampler, Texture2d, TextureData},
+        types,
+        uniform::Uniform,
+        Group,
+    },
+    std::marker::PhantomData,
+};
+
+/// The post effect type.
+#[derive(Clone, Copy)]
+pub enum PostEffect {
+    /// The Reinhard tonemapping `x / (x + 1)`.
+    Reinhard { exposure: f32 },
+
+    /// The ACES filmic tonemapping curve.
+    Aces { exposure: f32 },
+}
+
+impl PostEffect {
+    fn exposure(self) -> f32 {
+        match self {
+            Self::Reinhard { exposure } | Self::Aces { exposure } => exposure,
+        }
+    }
+}
+
+#[derive(Group)]
+struct Map<'a> {
+    tex: BoundTexture<'a>,
+    sam: &'a Sampler,
+    exp: &'a Uniform<f32>,
+}
+
+/// The full-screen post effect pass.
+///
+/// Can be created using the context's [`make_post`](crate::Context::make_post) function.
+///
+/// The pass samples a source texture, for example an HDR render target,
+/// and draws the result over the whole frame with a single triangle.
+/// The source is expected to hold linear colors and the pass encodes the result
+/// to sRGB exactly once: for [sRGB](Format::is_srgb) formats the hardware
+/// encodes it on write, for the linear `RgbAlpha` and `BgrAlpha` formats
+/// the pass encodes it in the shader. The `RgbAlphaHalf` format stays linear,
+/// so the result can be processed further:
+/// ```rust,ignore
+/// let post = cx.make_post(PostEffect::Aces { exposure: 1. }, view.format());
+/// let bind = post.bind(&cx, &hdr_texture);
+///
+/// // Then in the draw stage
+/// frame.layer(post.layer(), opts).bind(&bind).draw_points(3);
+/// ```
+pub struct Post {
+    shader: Shader<(), ()>,
+    layer: Layer<(), ()>,
+    sampler: Sampler,
+    exposure: Uniform<f32>,
+}
+
+impl Post {
+    pub(crate) fn new(cx: &Context, effect: PostEffect, format: Format) -> Self {
+        let encode = matches!(format, Format::RgbAlpha | Format::BgrAlpha);
+        let shader = match (effect, encode) {
+            (PostEffect::Reinhard { .. }, false) => cx.make_shader(tonemap::<Reinhard>),
+            (PostEffect::Reinhard { .. }, true) => cx.make_shader(tonemap::<Encode<Reinhard>>),
+            (PostEffect::Aces { .. }, false) => cx.make_shader(tonemap::<Aces>),
+            (PostEffect::Aces { .. }, true) => cx.make_shader(tonemap::<Encode<Aces>>),
+        };
+
+        let layer = cx.make_fullscreen_layer(&shader, format);
+        let sampler = cx.make_sampler(Filter::Linear);
+        let exposure = cx.make_uniform(effect.exposure());
+        Self {
+            shader,
+            layer,
+            sampler,
+            exposure,
+        }
+    }
+
+    /// Returns the layer to draw the effect.
+    ///
+    /// The layer has no vertex input, so draw it with `draw_points(3)`.
+    pub fn layer(&self) -> &Layer<(), ()> {
+        &self.layer
+    }
+
+    /// Creates a binding of the source texture.
+    ///
+    /// The binding must be created again if the texture is recreated,
+    /// for example after the window is resized.
+    pub fn bind<T>(&self, cx: &Context, texture: &T) -> UniqueBinding
+    where
+        T: BindTexture,
+    {
+        let map = Map {
+            tex: BoundTexture::new(texture),
+            sam: &self.sampler,
+            exp: &self.exposure,
+        };
+
+        let mut binder = cx.make_binder(&self.shader);
+        binder.add(&map);
+        binder.into_binding()
+    }
+
+    /// Sets the exposure of the effect.
+    pub fn set_exposure(&self, cx: &Context, exposure: f32) {
+        self.exposure.update(cx, exposure);
+    }
+}
+
+trait Tonemap {
+    fn map<A>(x: Ret<A, f32>) -> impl Eval<Fs, Out = f32>
+    where
+        Ret<A, f32>: Eval<Fs, Out = f32> + Clone;
+}
+
+struct Reinhard;
+
+impl Tonemap for Reinhard {
+    fn map<A>(x: Ret<A, f32>) -> impl Eval<Fs, Out = f32>
+    where
+        Ret<A, f32>: Eval<Fs, Out = f32> + Clone,
+    {
+        x.clone() / (x + 1.)
+    }
+}
+
+struct Aces;
+
+impl Tonemap for Aces {
+    fn map<A>(x: Ret<A, f32>) -> impl Eval<Fs, Out = f32>
+    where
+        Ret<A, f32>: Eval<Fs, Out = f32> + Clone,
+    {
+        let num = x.clone() * (x.clone() * 2.51 + 0.03);
+        let den = x.clone() * (x * 2.43 + 0.59) + 0.14;
+        sl::clamp(num / den, 0., 1.)
+    }
+}
+
+/// Encodes the tonemapped color to sRGB for targets
+/// without the hardware conversion.
+struct Encode<T>(PhantomData<T>);
+
+impl<T> Tonemap for Encode<T>
+where
+    T: Tonemap,
+{
+    fn map<A>(x: Ret<A, f32>) -> impl Eval<Fs, Out = f32>
+    where
+        Ret<A, f32>: Eval<Fs, Out = f32> + Clone,
+    {
+        let x = sl::thunk(sl::max(T::map(x), 0.));
+        let lo = x.clone() * 12.92;
+        let hi = sl::pow(x.clone(), 1. / 2.4) * 1.055 - 0.055;
+        sl::mix(lo, hi, sl::step(0.003_130_8, x))
+    }
+}
+
+fn tonemap<T>(
+    idx: Index,
+    Groups(map): Groups<Map>,
+) -> Out<impl Eval<Vs, Out = types::Vec4<f32>>, impl Eval<Fs, Out = types::Vec4<f32>>>
+where
+    T: Tonemap,
+{
+    Out {
+        place: sl::fullscreen_place(idx),
+        color: {
+            let uv = sl::fragment(sl::fullscreen_uv(idx));
+            let col = sl::thunk(sl::texture_sample(map.tex, map.sam, uv));
+            let exp = sl::thunk(map.exp);
+            let r = T::map(sl::thunk(col.clone().x() * exp.clone()));
+            let g = T::map(sl::thunk(col.clone().y() * exp.clone()));
+            let b = T::map(sl::thunk(col.clone().z() * exp));
+            sl::vec4_with(sl::vec3(r, g, b), col.w())
+        },
+    }
+}
+
+/// The bloom parameters.
+#[derive(Clone, Copy)]
+pub struct BloomParams {
+    /// The luminance above which pixels start to glow.
+    pub threshold: f32,
+
+    /// The strength of the glow added to the source.
+    pub intensity: f32,
+}
+
+impl BloomParams {
+    fn value(self) -> [f32; 2] {
+        [self.threshold, self.intensity]
+    }
+}
+
+impl Default for BloomParams {
+    fn default() -> Self {
+        Self {
+            threshold: 1.,
+            intensity: 1.,
+        }
+    }
+}
+
+#[derive(Group)]
+struct Extract<'a> {
+    tex: BoundTexture<'a>,
+    sam: &'a Sampler,
+    prm: &'a Uniform<[f32; 2]>,
+}
+
+#[derive(Group)]
+struct BlurMap<'a> {
+    tex: BoundTexture<'a>,
+    sam: &'a Sampler,
+    stp: &'a Uniform<[f32; 2]>,
+    rad: &'a Uniform<f32>,
+}
+
+/// The texture rendered by post effects.
+pub type PostTexture = texture::Bind<texture::Draw<Texture2d>>;
+
+const POST_FORMAT: Format = Format::RgbAlphaHalf;
+
+fn make_post_texture(cx: &Context, size: (u32, u32)) -> PostTexture {
+    let (width, height) = size;
+    let size = (u32::max(width, 1), u32::max(height, 1));
+    let data = TextureData::empty(size, POST_FORMAT)
+        .expect("non-zero size")
+        .with_draw()
+        .with_bind();
+
+    cx.make_texture(data)
+}
+
+fn draw_pass(cx: &Context, target: &PostTexture, layer: &Layer<(), ()>, bind: &UniqueBinding) {
+    let draw = draw::draw(|mut frame| {
+        frame
+            .layer(layer, Options::default())
+            .bind(bind)
+            .draw_points(3);
+    });
+
+    cx.draw_to(target, draw);
+}
+
+/// The separable Gaussian blur.
+///
+/// Can be created using the context's [`make_blur`](crate::Context::make_blur) function.
+///
+/// The blur runs a horizontal and a vertical pass over a source texture
+/// and stores the result in a texture of the same size:
+/// ```rust,ignore
+/// let blur = cx.make_blur(2.);
+/// let bind = blur.bind(&cx, &texture);
+///
+/// // Render the blurred texture each time the source is changed
+/// blur.render(&cx, &bind);
+///
+/// // Then use it as any other texture
+/// let tex = BoundTexture::new(bind.texture());
+/// ```
+pub struct Blur {
+    shader: Shader<(), ()>,
+    layer: Layer<(), ()>,
+    sampler: Sampler,
+    radius: Uniform<f32>,
+}
+
+impl Blur {
+    pub(crate) fn new(cx: &Context, radius: f32) -> Self {
+        let shader = cx.make_shader(blur_shader);
+        let layer = cx.make_fullscreen_layer(&shader, POST_FORMAT);
+        let sampler = cx.make_sampler(Filter::Linear);
+        let radius = cx.make_uniform(radius);
+        Self {
+            shader,
+            layer,
+            sampler,
+            radius,
+        }
+    }
+
+    /// Creates a binding of the source texture.
+    ///
+    /// This allocates the intermediate and the output textures,
+    /// so the binding must be created again if the source is recreated.
+    pub fn bind<T>(&self, cx: &Context, texture: &T) -> BlurBinding
+    where
+        T: BindTexture,
+    {
+        let size = texture.bind_texture().size();
+        let temp = make_post_texture(cx, size);
+        let output = make_post_texture(cx, size);
+        let step_h = cx.make_uniform([1. / size.0 as f32, 0.]);
+        let step_v = cx.make_uniform([0., 1. / size.1 as f32]);
+        let bind = |tex, stp| {
+            let map = BlurMap {
+                tex,
+                sam: &self.sampler,
+                stp,
+                rad: &self.radius,
+            };
+
+            let mut binder = cx.make_binder(&self.shader);
+            binder.add(&map);
+            binder.into_binding()
+        };
+
+        let horizontal = bind(BoundTexture::new(texture), &step_h);
+        let vertical = bind(BoundTexture::new(&temp), &step_v);
+        BlurBinding {
+            temp,
+            output,
+            horizontal,
+            vertical,
+        }
+    }
+
+    /// Renders the blurred texture of the binding's source.
+    pub fn render(&self, cx: &Context, bind: &BlurBinding) {
+        draw_pass(cx, &bind.temp, &self.layer, &bind.horizontal);
+        draw_pass(cx, &bind.output, &self.layer, &bind.vertical);
+    }
+
+    /// Sets the blur radius in texels.
+    pub fn set_radius(&self, cx: &Context, radius: f32) {
+        self.radius.update(cx, radius);
+    }
+}
+
+/// The [blur](Blur) binding of a source texture.
+pub struct BlurBinding {
+    temp: PostTexture,
+    output: PostTexture,
+    horizontal: UniqueBinding,
+    vertical: UniqueBinding,
+}
+
+impl BlurBinding {
+    /// Returns the blurred texture.
+    pub fn texture(&self) -> &PostTexture {
+        &self.output
+    }
+}
+
+#[derive(Group)]
+struct Combine<'a> {
+    src: BoundTexture<'a>,
+    blm: BoundTexture<'a>,
+    sam: &'a Sampler,
+    prm: &'a Uniform<[f32; 2]>,
+}
+
+/// The bloom post effect.
+///
+/// Can be created using the context's [`make_bloom`](crate::Context::make_bloom) function.
+///
+/// The effect extracts bright pixels of a source texture into a half sized buffer,
+/// blurs it and then adds it back to the source:
+/// ```rust,ignore
+/// let bloom = cx.make_bloom(BloomParams::default(), view.format());
+/// let bind = bloom.bind(&cx, &hdr_texture);
+///
+/// // Render the glow before the frame is drawn
+/// bloom.render(&cx, &bind);
+///
+/// // Then in the draw stage
+/// frame.layer(bloom.layer(), opts).bind(&bind).draw_points(3);
+/// ```
+pub struct Bloom {
+    extract: Shader<(), ()>,
+    extract_layer: Layer<(), ()>,
+    blur: Blur,
+    combine: Shader<(), ()>,
+    combine_layer: Layer<(), ()>,
+    sampler: Sampler,
+    params: Uniform<[f32; 2]>,
+}
+
+impl Bloom {
+    pub(crate) fn new(cx: &Context, params: BloomParams, format: Format) -> Self {
+        let extract = cx.make_shader(extract_shader);
+        let extract_layer = cx.make_fullscreen_layer(&extract, POST_FORMAT);
+        let blur = Blur::new(cx, 1.);
+        let combine = cx.make_shader(combine_shader);
+        let combine_layer = cx.make_fullscreen_layer(&combine, format);
+        let sampler = cx.make_sampler(Filter::Linear);
+        let params = cx.make_uniform(params.value());
+        Self {
+            extract,
+            extract_layer,
+            blur,
+            combine,
+            combine_layer,
+            sampler,
+            params,
+        }
+    }
+
+    /// Returns the layer to draw the source with the glow.
+    ///
+    /// The layer has no vertex input, so draw it with `draw_points(3)`.
+    pub fn layer(&self) -> &Layer<(), ()> {
+        &self.combine_layer
+    }
+
+    /// Creates a binding of the source texture.
+    ///
+    /// This allocates the intermediate buffers, so the binding
+    /// must be created again if the texture is recreated.
+    pub fn bind<T>(&self, cx: &Context, texture: &T) -> BloomBinding
+    where
+        T: BindTexture,
+    {
+        let (width, height) = texture.bind_texture().size();
+        let bright = make_post_texture(cx, (width / 2, height / 2));
+        let extract = {
+            let map = Extract {
+                tex: BoundTexture::new(texture),
+                sam: &self.sampler,
+                prm: &self.params,
+            };
+
+            let mut binder = cx.make_binder(&self.extract);
+            binder.add(&map);
+            binder.into_binding()
+        };
+
+        let blur = self.blur.bind(cx, &bright);
+        let combine = {
+            let map = Combine {
+                src: BoundTexture::new(texture),
+                blm: BoundTexture::new(blur.texture()),
+                sam: &self.sampler,
+                prm: &self.params,
+            };
+
+            let mut binder = cx.make_binder(&self.combine);
+            binder.add(&map);
+            binder.into_binding()
+        };
+
+        BloomBinding {
+            bright,
+            blur,
+            extract,
+            combine,
+        }
+    }
+
+    /// Renders the glow of the binding's source texture.
+    ///
+    /// Call it each time the source is changed before drawing the [layer](Bloom::layer).
+    pub fn render(&self, cx: &Context, bind: &BloomBinding) {
+        draw_pass(cx, &bind.bright, &self.extract_layer, &bind.extract);
+        self.blur.render(cx, &bind.blur);
+    }
+
+    /// Sets the bloom parameters.
+    pub fn set_params(&self, cx: &Context, params: BloomParams) {
+        self.params.update(cx, params.value());
+    }
+}
+
+/// The [bloom](Bloom) binding of a source texture.
+pub struct BloomBinding {
+    bright: PostTexture,
+    blur: BlurBinding,
+    extract: UniqueBinding,
+    combine: UniqueBinding,
+}
+
+impl Binding for BloomBinding {
+    fn binding(&self) -> Bind<'_> {
+        self.combine.binding()
+    }
+}
+
+#[derive(Group)]
+struct BlitMap<'a> {
+    tex: BoundTexture<'a>,
+    sam: &'a Sampler,
+    rct: &'a Uniform<[f32; 4]>,
+}
+
+/// The texture blit.
+///
+/// Can be created using the context's [`make_blit`](crate::Context::make_blit) function.
+///
+/// Draws a texture to a region of the target without a custom shader,
+/// which is handy to show a loaded image or to debug intermediate render targets.
+/// The color is converted between linear and sRGB formats when needed:
+/// ```rust,ignore
+/// let blit = cx.make_blit(Filter::Linear, view.format());
+///
+/// // Draw the texture to the top left quarter of the target
+/// let bind = blit.bind(&cx, &texture, [0., 0., 0.5, 0.5]);
+///
+/// // Then in the draw stage
+/// blit.draw(&mut frame, &bind);
+/// ```
+pub struct Blit {
+    shader: Shader<(), ()>,
+    layer: Layer<(), ()>,
+    sampler: Sampler,
+}
+
+impl Blit {
+    pub(crate) fn new(cx: &Context, filter: Filter, format: Format) -> Self {
+        use crate::layer::{Config, Topology};
+
+        assert!(
+            !format.is_depth() && format != Format::Byte,
+            "the blit can draw only to a float color format",
+        );
+
+        let shader = cx.make_shader(blit_shader);
+        let conf = Config {
+            format,
+            topology: Topology::TriangleStrip,
+            ..Default::default()
+        };
+
+        let layer = cx.make_layer(&shader, conf);
+        let sampler = cx.make_sampler(filter);
+        Self {
+            shader,
+            layer,
+            sampler,
+        }
+    }
+
+    /// Returns the layer to draw the texture.
+    ///
+    /// The layer has no vertex input, so draw it with `draw_points(4)`.
+    pub fn layer(&self) -> &Layer<(), ()> {
+        &self.layer
+    }
+
+    /// Creates a binding of the texture.
+    ///
+    /// The `rect` is the destination region as `[x, y, width, height]`
+    /// in the `[0, 1]` range of the target size with the origin at the top left corner.
+    pub fn bind<T>(&self, cx: &Context, texture: &T, rect: [f32; 4]) -> BlitBinding
+    where
+        T: BindTexture,
+    {
+        let rect = cx.make_uniform(rect);
+        let map = BlitMap {
+            tex: BoundTexture::new(texture),
+            sam: &self.sampler,
+            rct: &rect,
+        };
+
+        let mut binder = cx.make_binder(&self.shader);
+        binder.add(&map);
+        BlitBinding {
+            rect,
+            bind: binder.into_binding(),
+        }
+    }
+
+    /// Draws the binding's texture to the frame keeping its previous content.
+    pub fn draw(&self, frame: &mut Frame, bind: &BlitBinding) {
+        frame
+            .layer(&self.layer, Options::default())
+            .bind(bind)
+            .draw_points(4);
+    }
+
+    /// Converts the texture to the blit's format.
+    ///
+    /// The texture is drawn to a new texture of the same size,
+    /// which can be [copied](crate::Frame::copy_texture) to a buffer afterwards.
+    /// For example, a screenshot drawn in the `BgrAlpha` window format
+    /// can be read back as `RgbAlpha` without swapping channels on the CPU.
+    /// Since the color is converted between linear and sRGB formats by the hardware,
+    /// the stored values stay the same only between formats of the same color space.
+    ///
+    /// Keep the blit to convert many textures, so its pipeline is compiled only once.
+    /// Use the [nearest](Filter::Nearest) filter to keep the texels as they are.
+    pub fn convert<T>(&self, cx: &Context, src: &T) -> ConvertedTexture
+    where
+        T: BindTexture,
+    {
+        let size = src.bind_texture().size();
+        let data = TextureData::empty(size, self.layer.format())
+            .expect("non-zero size")
+            .with_draw()
+            .with_copy();
+
+        let dst = cx.make_texture(data);
+        let bind = self.bind(cx, src, [0., 0., 1., 1.]);
+        cx.draw_to(&dst, draw::draw(|mut frame| self.draw(&mut frame, &bind)));
+        dst
+    }
+}
+
+/// The texture [converted](Blit::convert) to another format.
+pub type ConvertedTexture = texture::Copy<texture::Draw<Texture2d>>;
+
+/// The [blit](Blit) binding of a texture.
+pub struct BlitBinding {
+    rect: Uniform<[f32; 4]>,
+    bind: UniqueBinding,
+}
+
+impl BlitBinding {
+    /// Sets the destination region.
+    pub fn set_rect(&self, cx: &Context, rect: [f32; 4]) {
+        self.rect.update(cx, rect);
+    }
+}
+
+impl Binding for BlitBinding {
+    fn binding(&self) -> Bind<'_> {
+        self.bind.binding()
+    }
+}
+
+fn extract_shader(
+    idx: Index,
+    Groups(map): Groups<Extract>,
+) -> Out<impl Eval<Vs, Out = types::Vec4<f32>>, impl Eval<Fs, Out = types::Vec4<f32>>> {
+    Out {
+        place: sl::fullscreen_place(idx),
+        color: {
+            let uv = sl::fragment(sl::fullscreen_uv(idx));
+            let col = sl::thunk(sl::texture_sample(map.tex, map.sam, uv));
+            let luma =
+                col.clone().x() * 0.2126 + col.clone().y() * 0.7152 + col.clone().z() * 0.0722;
+            let f = sl::thunk(sl::clamp(luma - map.prm.x(), 0., 1.));
+            let r = col.clone().x() * f.clone();
+            let g = col.clone().y() * f.clone();
+            let b = col.z() * f;
+            sl::vec4_with(sl::vec3(r, g, b), 0.)
+        },
+    }
+}
+
+fn blur_shader(
+    idx: Index,
+    Groups(map): Groups<BlurMap>,
+) -> Out<impl Eval<Vs, Out = types::Vec4<f32>>, impl Eval<Fs, Out = types::Vec4<f32>>> {
+    Out {
+        place: sl::fullscreen_place(idx),
+        color: {
+            let uv = sl::thunk(sl::fragment(sl::fullscreen_uv(idx)));
+            let stp = sl::thunk(map.stp * map.rad);
+            let tex = || map.tex.clone();
+            let sam = || map.sam.clone();
+            let near = sl::thunk(stp.clone() * 1.384_615_4);
+            let far = sl::thunk(stp * 3.230_769_2);
+            sl::texture_sample(tex(), sam(), uv.clone()) * 0.227_027
+                + sl::texture_sample(tex(), sam(), uv.clone() + near.clone()) * 0.316_216_2
+                + sl::texture_sample(tex(), sam(), uv.clone() - near) * 0.316_216_2
+                + sl::texture_sample(tex(), sam(), uv.clone() + far.clone()) * 0.070_270_27
+                + sl::texture_sample(tex(), sam(), uv - far) * 0.070_270_27
+        },
+    }
+}
+
+fn combine_shader(
+    idx: Index,
+    Groups(map): Groups<Combine>,
+) -> Out<impl Eval<Vs, Out = types::Vec4<f32>>, impl Eval<Fs, Out = types::Vec4<f32>>> {
+    Out {
+        place: sl::fullscreen_place(idx),
+        color: {
+            let uv = sl::thunk(sl::fragment(sl::fullscreen_uv(idx)));
+            let sam = || map.sam.clone();
+            let src = sl::texture_sample(map.src, sam(), uv.clone());
+            let blm = sl::texture_sample(map.blm, sam(), uv);
+            src + blm * map.prm.y()
+        },
+    }
+}
+
+fn blit_shader(
+    Index(idx): Index,
+    Groups(map): Groups<BlitMap>,
+) -> Out<impl Eval<Vs, Out = types::Vec4<f32>>, impl Eval<Fs, Out = types::Vec4<f32>>> {
+    // Quad corners as a triangle strip in counter-clockwise order
+    let u = || sl::f32(idx / 2);
+    let v = || sl::f32(idx % 2);
+    let rct = sl::thunk(map.rct);
+    let x = rct.clone().x() + u() * rct.clone().z();
+    let y = rct.clone().y() + v() * rct.w();
+    Out {
+        place: sl::vec4(x * 2. - 1., 1. - y * 2., 0., 1.),
+        color: sl::texture_sample(map.tex, map.sam, sl::fragment(sl::vec2(u(), v()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::sl::IntoModule};
+
+    #[test]
+    fn tonemap_modules() {
+        // Modules are validated when built
+        let plain = tonemap::<Aces>.into_module();
+        let encoded = tonemap::<Encode<Aces>>.into_module();
+        assert!(!plain.wgsl.contains("pow("));
+        assert!(encoded.wgsl.contains("pow("));
+        _ = tonemap::<Encode<Reinhard>>.into_module();
+    }
+
+    #[test]
+    fn tonemap_shaders() {
+        let reinhard = tonemap::<Reinhard>.into_module();
+        helpers::eq_lines(&reinhard.wgsl, include_str!("../tests/post_reinhard.wgsl"));
+        let aces = tonemap::<Encode<Aces>>.into_module();
+        helpers::eq_lines(&aces.wgsl, include_str!("../tests/post_aces_encode.wgsl"));
+    }
+
+    #[test]
+    fn bloom_shaders() {
+        let extract = extract_shader.into_module();
+        helpers::eq_lines(&extract.wgsl, include_str!("../tests/post_extract.wgsl"));
+        let combine = combine_shader.into_module();
+        helpers::eq_lines(&combine.wgsl, include_str!("../tests/post_combine.wgsl"));
+    }
+
+    #[test]
+    fn blur_shader() {
+        let module = super::blur_shader.into_module();
+        helpers::eq_lines(&module.wgsl, include_str!("../tests/post_blur.wgsl"));
+    }
+
+    #[test]
+    fn blit_shader() {
+        let module = super::blit_shader.into_module();
+        helpers::eq_lines(&module.wgsl, include_str!("../tests/post_blit.wgsl"));
+    }
+}
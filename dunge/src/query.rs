@@ -0,0 +1,127 @@
+//! Query types.
+
+use {
+    crate::{state::State, texture::MapResult},
+    std::{future::IntoFuture, sync::Arc},
+    wgpu::{Buffer, CommandEncoder, QuerySet},
+};
+
+/// The set of occlusion queries.
+///
+/// Can be created using the context's [`make_occlusion_query`](crate::Context::make_occlusion_query) function.
+///
+/// Each query counts the samples passed the depth test between
+/// the layer's `begin_occlusion_query` and `end_occlusion_query` calls.
+/// The query set is attached to the following layers of a frame,
+/// then the results are resolved at the end of the frame and
+/// can be read back later, for example, at the next frame:
+/// ```rust,ignore
+/// let query = cx.make_occlusion_query(objects.len() as u32);
+///
+/// // In the draw stage
+/// frame.set_occlusion_query(Some(&query));
+/// let mut layer = frame.layer(&layer, opts);
+/// for (n, object) in iter::zip(0.., &objects) {
+///     layer.begin_occlusion_query(n);
+///     layer.bind(&object.bind).draw(&object.bounds);
+///     layer.end_occlusion_query();
+/// }
+///
+/// drop(layer);
+/// frame.resolve_occlusion_query(&query);
+///
+/// // Later
+/// let samples = cx.read_occlusion_query(&query, tx, rx).await;
+/// ```
+pub struct OcclusionQuery {
+    set: Arc<QuerySet>,
+    resolve: Buffer,
+    read: Buffer,
+    len: u32,
+}
+
+impl OcclusionQuery {
+    pub(crate) fn new(state: &State, len: u32) -> Self {
+        use wgpu::*;
+
+        assert!(len > 0, "the occlusion query set must not be empty");
+        assert!(
+            len <= QUERY_SET_MAX_QUERIES,
+            "too many queries in the occlusion query set",
+        );
+
+        let set = {
+            let desc = QuerySetDescriptor {
+                label: None,
+                ty: QueryType::Occlusion,
+                count: len,
+            };
+
+            state.device().create_query_set(&desc)
+        };
+
+        let size = BufferAddress::from(len * QUERY_SIZE);
+        let buffer = |usage| {
+            let desc = BufferDescriptor {
+                label: None,
+                size,
+                usage,
+                mapped_at_creation: false,
+            };
+
+            state.device().create_buffer(&desc)
+        };
+
+        Self {
+            set: Arc::new(set),
+            resolve: buffer(BufferUsages::QUERY_RESOLVE | BufferUsages::COPY_SRC),
+            read: buffer(BufferUsages::MAP_READ | BufferUsages::COPY_DST),
+            len,
+        }
+    }
+
+    /// Returns the number of queries in the set.
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Checks if the set has no queries.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub(crate) fn set(&self) -> Arc<QuerySet> {
+        Arc::clone(&self.set)
+    }
+
+    pub(crate) fn resolve(&self, encoder: &mut CommandEncoder) {
+        encoder.resolve_query_set(&self.set, 0..self.len, &self.resolve, 0);
+        encoder.copy_buffer_to_buffer(&self.resolve, 0, &self.read, 0, self.resolve.size());
+    }
+
+    pub(crate) async fn read<S, R>(&self, state: &State, tx: S, rx: R) -> Vec<u64>
+    where
+        S: FnOnce(MapResult) + wgpu::WasmNotSend + 'static,
+        R: IntoFuture<Output = MapResult>,
+    {
+        use wgpu::*;
+
+        let slice = self.read.slice(..);
+        let samples = {
+            let _map = state.begin_map();
+            slice.map_async(MapMode::Read, tx);
+            if !state.can_yield() {
+                state.device().poll(Maintain::Wait);
+            }
+
+            if let Err(err) = rx.await {
+                panic!("failed to read occlusion query: {err}");
+            }
+
+            bytemuck::cast_slice(&slice.get_mapped_range()).to_vec()
+        };
+
+        self.read.unmap();
+        samples
+    }
+}
@@ -18,19 +18,27 @@ use {
 pub struct Shader<V, I> {
     inner: Inner,
     wgsl: String,
+    #[cfg(feature = "spirv")]
+    nm: wgpu::naga::Module,
     ty: PhantomData<(V, I)>,
 }
 
 impl<V, I> Shader<V, I> {
-    pub(crate) fn new<M, A>(state: &State, module: M) -> Self
+    pub(crate) fn new<M, A>(state: &State, module: M, label: Option<&str>) -> Self
     where
         M: IntoModule<A, Vertex = V>,
     {
         let mut module = module.into_module();
         let wgsl = mem::take(&mut module.wgsl);
+
+        #[cfg(feature = "spirv")]
+        let nm = module.nm.clone();
+
         Self {
-            inner: Inner::new(state, module),
+            inner: Inner::new(state, module, label),
             wgsl,
+            #[cfg(feature = "spirv")]
+            nm,
             ty: PhantomData,
         }
     }
@@ -42,6 +50,17 @@ impl<V, I> Shader<V, I> {
         &self.wgsl
     }
 
+    /// Writes the shader as SPIR-V binary.
+    ///
+    /// Available with the `spirv` feature.
+    ///
+    /// # Errors
+    /// See [`SpirvError`](crate::sl::SpirvError) for details.
+    #[cfg(feature = "spirv")]
+    pub fn spirv(&self) -> Result<Vec<u32>, crate::sl::SpirvError> {
+        crate::sl::write_spirv(&self.nm)
+    }
+
     pub(crate) fn id(&self) -> usize {
         self.inner.id
     }
@@ -54,10 +73,10 @@ impl<V, I> Shader<V, I> {
         &self.inner.layout
     }
 
-    pub(crate) fn buffers(&self) -> Box<[VertexBufferLayout]> {
+    pub(crate) fn buffers(&self) -> Box<[VertexBufferLayout<'_>]> {
         use wgpu::*;
 
-        fn layout(vert: &Vertex) -> VertexBufferLayout {
+        fn layout(vert: &Vertex) -> VertexBufferLayout<'_> {
             VertexBufferLayout {
                 array_stride: vert.array_stride,
                 step_mode: vert.step_mode,
@@ -99,7 +118,7 @@ struct Inner {
 }
 
 impl Inner {
-    fn new(state: &State, Module { cx, nm, .. }: Module) -> Self {
+    fn new(state: &State, Module { cx, nm, .. }: Module, label: Option<&str>) -> Self {
         use {
             std::{borrow::Cow, iter},
             wgpu::*,
@@ -107,7 +126,7 @@ impl Inner {
 
         let module = {
             let desc = ShaderModuleDescriptor {
-                label: None,
+                label,
                 source: ShaderSource::Naga(Cow::Owned(nm)),
             };
 
@@ -139,6 +158,16 @@ impl Inner {
                             count: None,
                         }
                     }
+                    MemberType::Dynamic(_) => BindGroupLayoutEntry {
+                        binding,
+                        visibility: visibility(info.stages),
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Uniform,
+                            has_dynamic_offset: true,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
                     MemberType::Tx2df => BindGroupLayoutEntry {
                         binding,
                         visibility: visibility(info.stages),
@@ -174,6 +203,7 @@ impl Inner {
         let layout = {
             let groups: Vec<_> = groups.iter().map(TypedGroup::bind).collect();
             let desc = PipelineLayoutDescriptor {
+                label,
                 bind_group_layouts: &groups,
                 ..Default::default()
             };
@@ -218,21 +248,46 @@ impl Inner {
                 InputInfo::Vert(v) => {
                     slots.vertex = vertex.len() as u32;
 
-                    let vert = {
+                    // Attributes are packed, so any padding
+                    // in the vertex type would shift them
+                    let packed: usize = v.def.into_iter().map(VectorType::size).sum();
+                    assert_eq!(
+                        v.size, packed,
+                        "the vertex type must have no padding between fields",
+                    );
+
+                    if v.streams.is_empty() {
                         let mut attr = make_attr();
                         let mut attrs = vec![];
                         for vecty in v.def {
                             attr(ValueType::Vector(vecty), &mut attrs);
                         }
 
-                        Vertex {
+                        vertex.push(Vertex {
                             array_stride: v.size as BufferAddress,
                             step_mode: VertexStepMode::Vertex,
                             attributes: attrs.into(),
-                        }
-                    };
+                        });
 
-                    vertex.push(vert);
+                        continue;
+                    }
+
+                    // Each stream is a separate buffer with its own offsets,
+                    // but shader locations follow the order of fields
+                    let count = v.streams.into_iter().max().map_or(1, |n| n as usize + 1);
+                    let mut streams: Vec<_> = (0..count).map(|_| (make_attr(), vec![])).collect();
+                    for (vecty, stream) in iter::zip(v.def, v.streams) {
+                        let (attr, attrs) = &mut streams[stream as usize];
+                        attr(ValueType::Vector(vecty), attrs);
+                    }
+
+                    for (_, attrs) in streams {
+                        vertex.push(Vertex {
+                            array_stride: attrs.iter().map(|attr| attr.format.size()).sum(),
+                            step_mode: VertexStepMode::Vertex,
+                            attributes: attrs.into(),
+                        });
+                    }
                 }
                 InputInfo::Inst(i) => {
                     if set_instance {
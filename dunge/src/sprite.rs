@@ -0,0 +1,202 @@
+//! The sprite batch.
+
+use {
+    crate::{
+        bind::UniqueBinding,
+        color::Rgba,
+        context::Context,
+        group::BoundTexture,
+        instance::Row,
+        layer::{Blend, Config, Layer, Topology},
+        sl::{self, Eval, Fs, Groups, InInstance, Index, Out, Vs},
+        state::{Frame, Options},
+        texture::{BindTexture, Filter, Sampler},
+        types, Format, Group, Instance,
+    },
+    std::cell::{Cell, RefCell},
+};
+
+#[derive(Instance)]
+struct Sprites {
+    rect: Row<[f32; 4]>,
+    uv: Row<[f32; 4]>,
+    col: Row<[f32; 4]>,
+}
+
+#[derive(Group)]
+struct Atlas<'a> {
+    tex: BoundTexture<'a>,
+    sam: &'a Sampler,
+}
+
+/// The batch of textured quads drawn with a single draw call.
+///
+/// Can be created using the context's [`make_sprite_batch`](crate::Context::make_sprite_batch) function.
+///
+/// All sprites of the batch share one atlas texture. Each sprite is a region
+/// of the atlas drawn to a region of the target and tinted with a color,
+/// which is enough to draw text from a glyph atlas or 2D sprites:
+/// ```rust,ignore
+/// let mut batch = cx.make_sprite_batch(&atlas, Filter::Nearest, view.format());
+///
+/// // In the update stage
+/// batch.clear();
+/// batch.push([0., 0., 0.25, 0.25], [0., 0., 0.5, 0.5], Rgba::from_standard([1.; 4]));
+///
+/// // Then in the draw stage
+/// batch.draw(&cx, &mut frame);
+/// ```
+/// Rects are `[x, y, width, height]` in the `[0, 1]` range of the target size
+/// and the atlas size with the origin at the top left corner.
+/// The sprites are drawn in order with alpha blending.
+pub struct SpriteBatch {
+    layer: Layer<(), Sprites>,
+    bind: UniqueBinding,
+    rects: Vec<[f32; 4]>,
+    uvs: Vec<[f32; 4]>,
+    cols: Vec<[f32; 4]>,
+    rows: RefCell<Sprites>,
+    dirty: Cell<bool>,
+}
+
+impl SpriteBatch {
+    pub(crate) fn new<T>(cx: &Context, atlas: &T, filter: Filter, format: Format) -> Self
+    where
+        T: BindTexture,
+    {
+        let shader = cx.make_shader(sprite_shader);
+        let conf = Config {
+            format,
+            blend: Blend::Alpha,
+            topology: Topology::TriangleStrip,
+            ..Default::default()
+        };
+
+        let layer = cx.make_layer(&shader, conf);
+        let sampler = cx.make_sampler(filter);
+        let bind = {
+            let map = Atlas {
+                tex: BoundTexture::new(atlas),
+                sam: &sampler,
+            };
+
+            let mut binder = cx.make_binder(&shader);
+            binder.add(&map);
+            binder.into_binding()
+        };
+
+        let rows = Sprites {
+            rect: cx.make_row(&[]),
+            uv: cx.make_row(&[]),
+            col: cx.make_row(&[]),
+        };
+
+        Self {
+            layer,
+            bind,
+            rects: vec![],
+            uvs: vec![],
+            cols: vec![],
+            rows: RefCell::new(rows),
+            dirty: Cell::new(false),
+        }
+    }
+
+    /// Adds a sprite to the batch.
+    ///
+    /// The `rect` is the destination region, the `uv` is the region of the atlas.
+    pub fn push(&mut self, rect: [f32; 4], uv: [f32; 4], col: Rgba) {
+        self.rects.push(rect);
+        self.uvs.push(uv);
+        self.cols.push(col.0);
+        self.dirty.set(true);
+    }
+
+    /// Removes all sprites from the batch.
+    pub fn clear(&mut self) {
+        self.rects.clear();
+        self.uvs.clear();
+        self.cols.clear();
+        self.dirty.set(true);
+    }
+
+    /// Returns the number of sprites in the batch.
+    pub fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    /// Checks if the batch has no sprites.
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Draws the sprites to the frame keeping its previous content.
+    ///
+    /// If the batch was changed since the last draw, the sprites are
+    /// uploaded to the instance buffer first. The buffer grows as needed
+    /// and is reused for the following frames.
+    pub fn draw(&self, cx: &Context, frame: &mut Frame) {
+        if self.is_empty() {
+            return;
+        }
+
+        if self.dirty.replace(false) {
+            let state = cx.state();
+            let mut rows = self.rows.borrow_mut();
+            rows.rect.write(state, &self.rects);
+            rows.uv.write(state, &self.uvs);
+            rows.col.write(state, &self.cols);
+        }
+
+        let rows = self.rows.borrow();
+        frame
+            .layer(&self.layer, Options::default())
+            .bind(&self.bind)
+            .instance(&rows)
+            .draw_points(4);
+    }
+}
+
+fn sprite_shader(
+    s: InInstance<Sprites>,
+    Index(idx): Index,
+    Groups(map): Groups<Atlas>,
+) -> Out<impl Eval<Vs, Out = types::Vec4<f32>>, impl Eval<Fs, Out = types::Vec4<f32>>> {
+    // Quad corners as a triangle strip in counter-clockwise order
+    let u = || sl::f32(idx / 2);
+    let v = || sl::f32(idx % 2);
+    let rct = sl::thunk(s.rect);
+    let x = rct.clone().x() + u() * rct.clone().z();
+    let y = rct.clone().y() + v() * rct.w();
+    let uv = sl::thunk(s.uv);
+    let tu = uv.clone().x() + u() * uv.clone().z();
+    let tv = uv.clone().y() + v() * uv.w();
+    Out {
+        place: sl::vec4(x * 2. - 1., 1. - y * 2., 0., 1.),
+        color: {
+            let tex = sl::thunk(sl::texture_sample(
+                map.tex,
+                map.sam,
+                sl::fragment(sl::vec2(tu, tv)),
+            ));
+
+            let col = sl::thunk(sl::fragment(s.col));
+            let r = tex.clone().x() * col.clone().x();
+            let g = tex.clone().y() * col.clone().y();
+            let b = tex.clone().z() * col.clone().z();
+            let a = tex.w() * col.w();
+            sl::vec4(r, g, b, a)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::sl::IntoModule};
+
+    #[test]
+    fn shader() {
+        let module = sprite_shader.into_module();
+        helpers::eq_lines(&module.wgsl, include_str!("../tests/sprite.wgsl"));
+    }
+}
@@ -1,52 +1,87 @@
 use {
     crate::{
         color::Rgba,
-        context::FailedMakeContext,
+        context::{ContextOptions, FailedMakeContext},
         draw::Draw,
         format::Format,
-        layer::{Layer, SetLayer},
-        texture::{CopyBuffer, CopyTexture, DrawTexture},
+        layer::{Bundle, Layer, Pipelines, SetLayer},
+        query::OcclusionQuery,
+        texture::{CopyBuffer, CopyTexture, DrawTexture, Texture2d},
+    },
+    std::{
+        sync::{
+            atomic::{self, AtomicUsize},
+            Arc, Mutex, MutexGuard, OnceLock, TryLockError,
+        },
+        thread::{self, ThreadId},
+    },
+    wgpu::{
+        util::StagingBelt, Adapter, AdapterInfo, CommandEncoder, Device, Instance, LoadOp,
+        QuerySet, Queue, RenderPass, TextureFormatFeatureFlags, TextureView,
     },
-    std::sync::atomic::{self, AtomicUsize},
-    wgpu::{CommandEncoder, Device, Instance, Queue, TextureView},
 };
 
-#[cfg(feature = "winit")]
-use wgpu::Adapter;
-
 pub(crate) struct State {
     #[cfg(feature = "winit")]
     instance: Instance,
-    #[cfg(feature = "winit")]
     adapter: Adapter,
+    info: AdapterInfo,
     device: Device,
     queue: Queue,
     shader_ids: AtomicUsize,
+    pipelines: Pipelines,
+    maps: AtomicUsize,
+    driver: OnceLock<ThreadId>,
+    belt: Mutex<StagingBelt>,
 }
 
 impl State {
-    pub async fn new(instance: Instance) -> Result<Self, FailedMakeContext> {
-        let adapter = {
-            use wgpu::{PowerPreference, RequestAdapterOptions};
-
-            let options = RequestAdapterOptions {
-                power_preference: PowerPreference::HighPerformance,
-                ..Default::default()
-            };
-
-            instance
-                .request_adapter(&options)
-                .await
-                .ok_or(FailedMakeContext::BackendSelection)?
+    pub const BELT_CHUNK_SIZE: u64 = 1 << 12;
+
+    pub async fn new(instance: Instance, opts: &ContextOptions) -> Result<Self, FailedMakeContext> {
+        let adapter = match &opts.adapter_filter {
+            #[cfg(not(target_family = "wasm"))]
+            Some(filter) => {
+                use wgpu::{DeviceType, PowerPreference};
+
+                let preferred = match opts.power_preference {
+                    PowerPreference::LowPower => DeviceType::IntegratedGpu,
+                    _ => DeviceType::DiscreteGpu,
+                };
+
+                let mut adapters = instance.enumerate_adapters(opts.backends);
+                adapters.retain(|adapter| filter(&adapter.get_info()));
+                adapters.sort_by_key(|adapter| adapter.get_info().device_type != preferred);
+                adapters
+                    .into_iter()
+                    .next()
+                    .ok_or(FailedMakeContext::BackendSelection)?
+            }
+            #[cfg(target_family = "wasm")]
+            Some(filter) => {
+                let adapter = request_adapter(&instance, opts).await?;
+                if !filter(&adapter.get_info()) {
+                    return Err(FailedMakeContext::BackendSelection);
+                }
+
+                adapter
+            }
+            None => request_adapter(&instance, opts).await?,
         };
 
-        let backend = adapter.get_info().backend;
-        log::info!("selected backend: {backend:?}");
+        let info = adapter.get_info();
+        log::info!("selected backend: {backend:?}", backend = info.backend);
+
+        let missing = opts.features - adapter.features();
+        if !missing.is_empty() {
+            return Err(FailedMakeContext::MissingFeatures(missing));
+        }
 
         let (device, queue) = {
             use wgpu::{DeviceDescriptor, Limits};
 
             let desc = DeviceDescriptor {
+                required_features: opts.features,
                 required_limits: Limits {
                     ..if cfg!(target_arch = "wasm32") {
                         Limits::downlevel_webgl2_defaults()
@@ -58,20 +93,29 @@ impl State {
             };
 
             adapter
-                .request_device(&desc, None)
+                .request_device(&desc, opts.trace_path.as_deref())
                 .await
                 .map_err(FailedMakeContext::RequestDevice)?
         };
 
-        Ok(Self {
+        Ok(Self::from_parts(instance, adapter, device, queue))
+    }
+
+    #[cfg_attr(not(feature = "winit"), allow(unused_variables))]
+    pub fn from_parts(instance: Instance, adapter: Adapter, device: Device, queue: Queue) -> Self {
+        Self {
             #[cfg(feature = "winit")]
             instance,
-            #[cfg(feature = "winit")]
+            info: adapter.get_info(),
             adapter,
             device,
             queue,
             shader_ids: AtomicUsize::default(),
-        })
+            pipelines: Pipelines::default(),
+            maps: AtomicUsize::default(),
+            driver: OnceLock::new(),
+            belt: Mutex::new(StagingBelt::new(Self::BELT_CHUNK_SIZE)),
+        }
     }
 
     #[cfg(feature = "winit")]
@@ -84,6 +128,47 @@ impl State {
         &self.adapter
     }
 
+    /// Returns features of the texture format.
+    ///
+    /// Without the adapter specific format features
+    /// only the features guaranteed by WebGPU are available.
+    pub fn format_flags(&self, format: Format) -> TextureFormatFeatureFlags {
+        use wgpu::Features;
+
+        let specific = Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES;
+        let features = self.device.features();
+        if features.contains(specific) {
+            self.adapter
+                .get_texture_format_features(format.wgpu())
+                .flags
+        } else {
+            format.wgpu().guaranteed_format_features(features).flags
+        }
+    }
+
+    pub fn sample_counts(&self, format: Format) -> Vec<u32> {
+        use wgpu::Features;
+
+        let flags = self
+            .adapter
+            .get_texture_format_features(format.wgpu())
+            .flags;
+        let specific = Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES;
+        if self.device.features().contains(specific) {
+            flags.supported_sample_counts()
+        } else {
+            // Without the feature only the counts guaranteed by WebGPU are allowed
+            [1, 4]
+                .into_iter()
+                .filter(|&count| flags.sample_count_supported(count))
+                .collect()
+        }
+    }
+
+    pub fn info(&self) -> &AdapterInfo {
+        &self.info
+    }
+
     pub fn device(&self) -> &Device {
         &self.device
     }
@@ -92,10 +177,64 @@ impl State {
         &self.queue
     }
 
+    pub fn pipelines(&self) -> &Pipelines {
+        &self.pipelines
+    }
+
+    /// Returns the shared staging belt for uniform updates.
+    ///
+    /// Returns `None` if the belt is already used by another update scope.
+    pub fn staging_belt(&self) -> Option<MutexGuard<'_, StagingBelt>> {
+        match self.belt.try_lock() {
+            Ok(belt) => Some(belt),
+            Err(TryLockError::Poisoned(err)) => Some(err.into_inner()),
+            Err(TryLockError::WouldBlock) => None,
+        }
+    }
+
     pub fn next_shader_id(&self) -> usize {
         self.shader_ids.fetch_add(1, atomic::Ordering::Relaxed)
     }
 
+    /// Marks the device as polled by an event loop running on the current thread.
+    ///
+    /// After that, buffer mapping on other threads doesn't block
+    /// and relies on the loop to call [`poll_maps`](Self::poll_maps).
+    #[cfg(feature = "winit")]
+    pub fn set_driven(&self) {
+        _ = self.driver.set(thread::current().id());
+    }
+
+    /// Checks if a wait on the current thread can yield to the event loop.
+    ///
+    /// The loop polls the device only between its callbacks, so a wait
+    /// on the loop thread itself, for example, blocking on a mapping inside
+    /// the update, would never complete. Such a wait must poll the device.
+    pub fn can_yield(&self) -> bool {
+        self.driver
+            .get()
+            .is_some_and(|&driver| driver != thread::current().id())
+    }
+
+    pub fn begin_map(&self) -> MapGuard<'_> {
+        self.maps.fetch_add(1, atomic::Ordering::Relaxed);
+        MapGuard(&self.maps)
+    }
+
+    /// Polls the device if there are pending buffer mappings.
+    ///
+    /// Returns `true` if the device has to be polled again.
+    #[cfg(feature = "winit")]
+    pub fn poll_maps(&self) -> bool {
+        use wgpu::Maintain;
+
+        if self.maps.load(atomic::Ordering::Relaxed) == 0 {
+            return false;
+        }
+
+        !self.device.poll(Maintain::Poll).is_queue_empty()
+    }
+
     pub fn draw<D>(&self, target: Target, draw: D)
     where
         D: Draw,
@@ -111,29 +250,170 @@ impl State {
         draw.draw(Frame {
             target,
             encoder: &mut encoder,
+            occlusion: None,
         });
 
         self.queue.submit([encoder.finish()]);
     }
+
+    pub fn resolve(&self, src: &Texture2d, dst: &Texture2d) {
+        use wgpu::*;
+
+        assert!(src.samples() > 1, "the source texture must be multisampled");
+        assert_eq!(
+            dst.samples(),
+            1,
+            "the destination texture must be single sampled"
+        );
+        assert_eq!(
+            src.format(),
+            dst.format(),
+            "source and destination textures must have the same format",
+        );
+
+        assert_eq!(
+            src.size(),
+            dst.size(),
+            "source and destination textures must be the same size",
+        );
+
+        let mut encoder = {
+            let desc = CommandEncoderDescriptor::default();
+            self.device.create_command_encoder(&desc)
+        };
+
+        let color_attachment = RenderPassColorAttachment {
+            view: src.view(),
+            resolve_target: Some(dst.view()),
+            ops: Operations {
+                load: LoadOp::Load,
+                store: StoreOp::Store,
+            },
+        };
+
+        let desc = RenderPassDescriptor {
+            color_attachments: &[Some(color_attachment)],
+            ..Default::default()
+        };
+
+        drop(encoder.begin_render_pass(&desc));
+        self.queue.submit([encoder.finish()]);
+    }
+}
+
+async fn request_adapter(
+    instance: &Instance,
+    opts: &ContextOptions,
+) -> Result<Adapter, FailedMakeContext> {
+    use wgpu::RequestAdapterOptions;
+
+    let options = RequestAdapterOptions {
+        power_preference: opts.power_preference,
+        ..Default::default()
+    };
+
+    instance
+        .request_adapter(&options)
+        .await
+        .ok_or(FailedMakeContext::BackendSelection)
+}
+
+pub(crate) struct MapGuard<'a>(&'a AtomicUsize);
+
+impl Drop for MapGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, atomic::Ordering::Relaxed);
+    }
+}
+
+/// How a layer treats the previous content of a target's buffer.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Load<T> {
+    /// Keep the previous content to draw on top of it.
+    #[default]
+    Load,
+
+    /// Clear the buffer with the given value.
+    Clear(T),
+
+    /// The previous content isn't needed since the layer overwrites every pixel.
+    ///
+    /// The buffer is cleared with a default value, which is as cheap as
+    /// an undefined content on tiled GPUs, but never exposes stale data.
+    /// The color is cleared to transparent black and the depth to the
+    /// far plane, so depth tested geometry is still drawn.
+    DontCare,
+}
+
+impl<T> Load<T> {
+    fn map<U, F>(self, f: F) -> Load<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        match self {
+            Self::Load => Load::Load,
+            Self::Clear(v) => Load::Clear(f(v)),
+            Self::DontCare => Load::DontCare,
+        }
+    }
+
+    fn wgpu(self, default: T) -> LoadOp<T> {
+        match self {
+            Self::Load => LoadOp::Load,
+            Self::Clear(v) => LoadOp::Clear(v),
+            Self::DontCare => LoadOp::Clear(default),
+        }
+    }
 }
 
 /// Current layer options.
+///
+/// The options describe how a [layer](Frame::layer) starts drawing to the frame.
+/// By default, the previous content of the target is kept, so to draw
+/// a new frame, the first layer should clear it:
+/// ```rust,ignore
+/// let opts = Options::default()
+///     .clear_color(Rgba::from_standard([0., 0., 0., 1.]))
+///     .clear_depth(1.);
+///
+/// frame.layer(&layer, opts).bind_empty().draw(&mesh);
+/// ```
+///
+/// Every following layer keeps what is drawn before unless its options say
+/// otherwise. The [`color`](Options::color) and [`depth`](Options::depth) functions
+/// set the [load](Load) behavior of each buffer explicitly.
+///
+/// Buffers of the target are not the part of the options. To draw
+/// to a window with depth test or multisampling, set them up with
+/// the window's [`with_depth`](crate::window::WindowState::with_depth) and
+/// [`with_samples`](crate::window::WindowState::with_samples) methods.
+/// For an offscreen target use a [render buffer](RenderBuffer).
 #[derive(Clone, Copy, Default)]
 pub struct Options {
-    clear_color: Option<Rgba>,
-    clear_depth: Option<f32>,
+    color: Load<Rgba>,
+    depth: Load<f32>,
 }
 
 impl Options {
     /// Sets clear color for the layer.
-    pub fn clear_color(mut self, clear: Rgba) -> Self {
-        self.clear_color = Some(clear);
-        self
+    pub fn clear_color(self, clear: Rgba) -> Self {
+        self.color(Load::Clear(clear))
     }
 
     /// Sets clear depth for the layer.
-    pub fn clear_depth(mut self, clear: f32) -> Self {
-        self.clear_depth = Some(clear);
+    pub fn clear_depth(self, clear: f32) -> Self {
+        self.depth(Load::Clear(clear))
+    }
+
+    /// Sets how the layer treats the previous color of the target.
+    pub fn color(mut self, load: Load<Rgba>) -> Self {
+        self.color = load;
+        self
+    }
+
+    /// Sets how the layer treats the previous depth of the target.
+    pub fn depth(mut self, load: Load<f32>) -> Self {
+        self.depth = load;
         self
     }
 }
@@ -148,6 +428,7 @@ impl From<Rgba> for Options {
 pub struct Frame<'v, 'e> {
     target: Target<'v>,
     encoder: &'e mut CommandEncoder,
+    occlusion: Option<(Arc<QuerySet>, u32)>,
 }
 
 impl Frame<'_, '_> {
@@ -155,35 +436,91 @@ impl Frame<'_, '_> {
     where
         O: Into<Options>,
     {
-        use wgpu::*;
-
         assert_eq!(
             self.target.format,
             layer.format(),
             "layer format doesn't match frame format",
         );
 
-        assert!(
-            !layer.depth() || self.target.depthv.is_some(),
-            "the target for a layer with depth must contain a depth buffer",
+        assert_eq!(
+            self.target.samples,
+            layer.samples(),
+            "layer sample count doesn't match frame sample count",
         );
 
-        let opts = opts.into();
+        if let Some(format) = layer.depth_format() {
+            let depth = self.target.depth.map(Texture2d::format);
+            assert!(
+                depth.is_some(),
+                "the target for a layer with depth must contain a depth buffer",
+            );
+
+            assert_eq!(
+                depth,
+                Some(format),
+                "layer depth format doesn't match frame depth format",
+            );
+        }
+
+        let queries = self.occlusion.as_ref().map_or(0, |(_, len)| *len);
+        let pass = self.begin_pass(opts.into());
+        layer.set(pass, queries)
+    }
+
+    /// Executes prerecorded draw commands of the [bundle](Bundle).
+    ///
+    /// It starts drawing with the given options the same way as the
+    /// [`layer`](Frame::layer) function.
+    pub fn execute_bundle<O>(&mut self, bundle: &Bundle, opts: O)
+    where
+        O: Into<Options>,
+    {
+        assert_eq!(
+            self.target.format,
+            bundle.format(),
+            "bundle format doesn't match frame format",
+        );
+
+        assert_eq!(
+            self.target.samples,
+            bundle.samples(),
+            "bundle sample count doesn't match frame sample count",
+        );
+
+        if let Some(format) = bundle.depth_format() {
+            let depth = self.target.depth.map(Texture2d::format);
+            assert!(
+                depth.is_some(),
+                "the target for a bundle with depth must contain a depth buffer",
+            );
+
+            assert_eq!(
+                depth,
+                Some(format),
+                "bundle depth format doesn't match frame depth format",
+            );
+        }
+
+        let mut pass = self.begin_pass(opts.into());
+        pass.execute_bundles([bundle.bundle()]);
+    }
+
+    fn begin_pass(&mut self, opts: Options) -> RenderPass<'_> {
+        use wgpu::*;
+
         let color_attachment = RenderPassColorAttachment {
             view: self.target.colorv,
-            resolve_target: None,
+            resolve_target: self.target.resolvev,
             ops: Operations {
-                load: opts
-                    .clear_color
-                    .map(Rgba::wgpu)
-                    .map_or(LoadOp::Load, LoadOp::Clear),
+                load: opts.color.map(Rgba::wgpu).wgpu(wgpu::Color::TRANSPARENT),
                 store: StoreOp::Store,
             },
         };
 
         let depth_attachment = |view| {
             let ops = Operations {
-                load: opts.clear_depth.map_or(LoadOp::Load, LoadOp::Clear),
+                // Clear to the far plane, otherwise the `LessEqual` test discards everything
+                load: opts.depth.wgpu(1.),
                 store: StoreOp::Store,
             };
 
@@ -196,12 +533,52 @@ impl Frame<'_, '_> {
 
         let desc = RenderPassDescriptor {
             color_attachments: &[Some(color_attachment)],
-            depth_stencil_attachment: self.target.depthv.map(depth_attachment),
+            depth_stencil_attachment: self.target.depth.map(Texture2d::view).map(depth_attachment),
+            occlusion_query_set: self.occlusion.as_ref().map(|(set, _)| &**set),
             ..Default::default()
         };
 
-        let pass = self.encoder.begin_render_pass(&desc);
-        layer.set(pass)
+        self.encoder.begin_render_pass(&desc)
+    }
+
+    /// Attaches the [occlusion query](OcclusionQuery) set to the following layers.
+    ///
+    /// Pass `None` to detach it.
+    pub fn set_occlusion_query(&mut self, query: Option<&OcclusionQuery>) {
+        self.occlusion = query.map(|query| (query.set(), query.len()));
+    }
+
+    /// Resolves results of the [occlusion query](OcclusionQuery) set.
+    ///
+    /// Call it after the last layer using the set, the results can be read
+    /// with the context's [`read_occlusion_query`](crate::Context::read_occlusion_query)
+    /// function once the frame is submitted.
+    pub fn resolve_occlusion_query(&mut self, query: &OcclusionQuery) {
+        query.resolve(self.encoder);
+    }
+
+    /// Opens a debug group for the following layers.
+    ///
+    /// Graphics debuggers show the commands between this call and the matching
+    /// [`pop_debug_group`](Frame::pop_debug_group) as a named group,
+    /// so a frame capture can be organized by stages:
+    /// ```rust,ignore
+    /// frame.push_debug_group("shadows");
+    /// frame.layer(&shadow_layer, opts).bind(&bind).draw(&mesh);
+    /// frame.pop_debug_group();
+    /// ```
+    pub fn push_debug_group(&mut self, label: &str) {
+        self.encoder.push_debug_group(label);
+    }
+
+    /// Closes the last opened debug group.
+    pub fn pop_debug_group(&mut self) {
+        self.encoder.pop_debug_group();
+    }
+
+    /// Inserts a debug marker between layers.
+    pub fn insert_debug_marker(&mut self, label: &str) {
+        self.encoder.insert_debug_marker(label);
     }
 
     pub fn copy_texture<T>(&mut self, buffer: &CopyBuffer, texture: &T)
@@ -216,32 +593,65 @@ impl Frame<'_, '_> {
 #[derive(Clone, Copy)]
 pub struct Target<'v> {
     format: Format,
+    samples: u32,
     colorv: &'v TextureView,
-    depthv: Option<&'v TextureView>,
+    resolvev: Option<&'v TextureView>,
+    depth: Option<&'v Texture2d>,
 }
 
 impl<'v> Target<'v> {
     pub(crate) fn new(format: Format, colorv: &'v TextureView) -> Self {
         Self {
             format,
+            samples: 1,
             colorv,
-            depthv: None,
+            resolvev: None,
+            depth: None,
+        }
+    }
+
+    pub(crate) fn with_samples(self, samples: u32) -> Self {
+        Self { samples, ..self }
+    }
+
+    #[cfg(feature = "winit")]
+    pub(crate) fn with_resolve(self, resolvev: &'v TextureView) -> Self {
+        Self {
+            resolvev: Some(resolvev),
+            ..self
+        }
+    }
+
+    pub(crate) fn with_depth(self, depth: &'v Texture2d) -> Self {
+        Self {
+            depth: Some(depth),
+            ..self
         }
     }
 }
 
 /// Something that contains a [target](Target).
+///
+/// Any texture created with the [`with_draw`](crate::texture::TextureData::with_draw)
+/// usage is a valid target, for example `TextureData::empty(size, format)?.with_draw().with_bind()`.
+/// Such textures can be drawn to by reference, so they can be stored as is
+/// without any interior mutability. A pair of a color texture and
+/// a depth texture forms a target as a [render buffer](RenderBuffer).
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be used as a render target",
+    note = "create the texture with the `with_draw` usage to draw to it"
+)]
 pub trait AsTarget {
-    fn as_target(&self) -> Target;
+    fn as_target(&self) -> Target<'_>;
 }
 
 impl<T> AsTarget for T
 where
     T: DrawTexture,
 {
-    fn as_target(&self) -> Target {
+    fn as_target(&self) -> Target<'_> {
         let texture = self.draw_texture();
-        Target::new(texture.format(), texture.view())
+        Target::new(texture.format(), texture.view()).with_samples(texture.samples())
     }
 }
 
@@ -250,10 +660,9 @@ where
     T: DrawTexture,
     D: DrawTexture,
 {
-    fn as_target(&self) -> Target {
-        let mut target = self.color.as_target();
-        target.depthv = Some(self.depth.draw_texture().view());
-        target
+    fn as_target(&self) -> Target<'_> {
+        let target = self.color.as_target();
+        target.with_depth(self.depth.draw_texture())
     }
 }
 
@@ -272,10 +681,9 @@ impl<T, D> RenderBuffer<T, D> {
     {
         let color_texture = color.draw_texture();
         let depth_texture = depth.draw_texture();
-        assert_eq!(
-            depth_texture.format(),
-            Format::Depth,
-            "the depth texture must have the depth format",
+        assert!(
+            depth_texture.format().is_depth(),
+            "the depth texture must have a depth format",
         );
 
         assert_eq!(
@@ -284,6 +692,12 @@ impl<T, D> RenderBuffer<T, D> {
             "color and depth textures must be the same size",
         );
 
+        assert_eq!(
+            color_texture.samples(),
+            depth_texture.samples(),
+            "color and depth textures must have the same sample count",
+        );
+
         Self { color, depth }
     }
 
@@ -2,7 +2,7 @@
 
 use {
     crate::{format::Format, state::State},
-    std::{error, fmt, future::IntoFuture},
+    std::{error, fmt, future::IntoFuture, iter, ops::Range},
     wgpu::{
         Buffer, BufferAsyncError, BufferSlice, BufferView, CommandEncoder, FilterMode,
         TextureUsages, TextureView, WasmNotSend,
@@ -14,6 +14,7 @@ pub struct TextureData<'a> {
     data: &'a [u8],
     size: (u32, u32),
     format: Format,
+    samples: u32,
 }
 
 impl<'a> TextureData<'a> {
@@ -27,6 +28,7 @@ impl<'a> TextureData<'a> {
             data: &[],
             size,
             format,
+            samples: 1,
         })
     }
 
@@ -47,6 +49,26 @@ impl<'a> TextureData<'a> {
         Ok(Self { data, ..empty })
     }
 
+    /// Sets the number of samples per texel to make a multisampled texture.
+    ///
+    /// A multisampled texture can only be used as a render attachment
+    /// of a [layer](crate::layer::Layer) with the same sample count,
+    /// so it has no data and only the [`with_draw`](Multisampled::with_draw) usage.
+    /// To use the rendered image in a shader, [resolve](crate::Context::resolve) it
+    /// into a single sampled texture created with the `with_draw` and `with_bind` usages.
+    ///
+    /// The count of 4 is supported by all devices.
+    ///
+    /// # Errors
+    /// Returns [`Error::MultisampledData`] if the texture data isn't [empty](TextureData::empty).
+    pub const fn with_samples(self, samples: u32) -> Result<Multisampled<'a>, Error> {
+        if !self.data.is_empty() {
+            return Err(Error::MultisampledData);
+        }
+
+        Ok(Multisampled(Self { samples, ..self }))
+    }
+
     /// Allow to use a texture in the shader.
     pub fn with_bind(self) -> Bind<Self> {
         Bind(self)
@@ -71,6 +93,9 @@ pub enum Error {
 
     /// The texture data length doesn't match with size and format.
     InvalidLen,
+
+    /// The multisampled texture has initial data.
+    MultisampledData,
 }
 
 impl fmt::Display for Error {
@@ -78,6 +103,7 @@ impl fmt::Display for Error {
         match self {
             Self::ZeroSized => write!(f, "zero sized data"),
             Self::InvalidLen => write!(f, "invalid data length"),
+            Self::MultisampledData => write!(f, "multisampled texture can't have data"),
         }
     }
 }
@@ -102,7 +128,13 @@ pub struct Texture2d {
 }
 
 impl Texture2d {
-    fn new(state: &State, mut usage: TextureUsages, data: TextureData) -> Self {
+    fn new(
+        state: &State,
+        mut usage: TextureUsages,
+        label: Option<&str>,
+        staging: Option<&Buffer>,
+        data: TextureData,
+    ) -> Self {
         use wgpu::*;
 
         let (width, height) = data.size;
@@ -116,10 +148,10 @@ impl Texture2d {
         let inner = {
             usage.set(TextureUsages::COPY_DST, copy_data);
             let desc = TextureDescriptor {
-                label: None,
+                label,
                 size,
                 mip_level_count: 1,
-                sample_count: 1,
+                sample_count: data.samples,
                 dimension: TextureDimension::D2,
                 format: data.format.wgpu(),
                 usage,
@@ -129,7 +161,32 @@ impl Texture2d {
             state.device().create_texture(&desc)
         };
 
-        if copy_data {
+        if let Some(buffer) = staging.filter(|_| copy_data) {
+            let mut encoder = {
+                let desc = CommandEncoderDescriptor::default();
+                state.device().create_command_encoder(&desc)
+            };
+
+            encoder.copy_buffer_to_texture(
+                ImageCopyBuffer {
+                    buffer,
+                    layout: ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(staged_row(width, data.format)),
+                        rows_per_image: Some(height),
+                    },
+                },
+                ImageCopyTexture {
+                    texture: &inner,
+                    mip_level: 0,
+                    origin: Origin3d::ZERO,
+                    aspect: TextureAspect::All,
+                },
+                size,
+            );
+
+            state.queue().submit([encoder.finish()]);
+        } else if copy_data {
             state.queue().write_texture(
                 ImageCopyTexture {
                     texture: &inner,
@@ -163,21 +220,93 @@ impl Texture2d {
         Format::from_wgpu(self.inner.format())
     }
 
+    pub fn samples(&self) -> u32 {
+        self.inner.sample_count()
+    }
+
     pub(crate) fn view(&self) -> &TextureView {
         &self.view
     }
 }
 
-pub(crate) fn make<M>(state: &State, data: M) -> M::Out
+pub(crate) fn make<M>(state: &State, data: M, label: Option<&str>) -> M::Out
 where
     M: Make,
 {
     data.make(Maker {
         state,
         usage: TextureUsages::empty(),
+        label,
+        staging: None,
     })
 }
 
+/// Makes a texture uploading its data through a staging buffer.
+///
+/// The buffer is mapped asynchronously, so the caller waits for the mapping
+/// the same way as for a [readback](CopyBufferView::map). Then the data is
+/// written into it and the copy to the texture is submitted.
+pub(crate) async fn make_staged<M, S, R>(state: &State, data: M, tx: S, rx: R) -> M::Out
+where
+    M: Make,
+    S: FnOnce(MapResult) + WasmNotSend + 'static,
+    R: IntoFuture<Output = MapResult>,
+{
+    use wgpu::*;
+
+    let texture_data = data.texture_data();
+    if texture_data.data.is_empty() {
+        return make(state, data, None);
+    }
+
+    let (width, height) = texture_data.size;
+    let row = staged_row(width, texture_data.format);
+    let buf = {
+        let desc = BufferDescriptor {
+            label: None,
+            size: BufferAddress::from(row) * BufferAddress::from(height),
+            usage: BufferUsages::MAP_WRITE | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        };
+
+        state.device().create_buffer(&desc)
+    };
+
+    let slice = buf.slice(..);
+    {
+        let _map = state.begin_map();
+        slice.map_async(MapMode::Write, tx);
+        if !state.can_yield() {
+            state.device().poll(Maintain::Wait);
+        }
+
+        if let Err(err) = rx.await {
+            panic!("failed to upload texture: {err}");
+        }
+    }
+
+    {
+        let mut view = slice.get_mapped_range_mut();
+        let len = (width * texture_data.format.bytes()) as usize;
+        for (dst, src) in iter::zip(view.chunks_mut(row as usize), texture_data.data.chunks(len)) {
+            dst[..len].copy_from_slice(src);
+        }
+    }
+
+    buf.unmap();
+    data.make(Maker {
+        state,
+        usage: TextureUsages::empty(),
+        label: None,
+        staging: Some(&buf),
+    })
+}
+
+/// Returns the size of a texture row in a staging buffer, it's aligned for copying.
+fn staged_row(width: u32, format: Format) -> u32 {
+    (width * format.bytes()).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+}
+
 #[derive(Clone, Copy)]
 pub enum Filter {
     Nearest,
@@ -196,14 +325,17 @@ impl Filter {
 pub struct Sampler(wgpu::Sampler);
 
 impl Sampler {
-    pub(crate) fn new(state: &State, filter: Filter) -> Self {
+    pub(crate) fn new(state: &State, filter: Filter, (lod_min, lod_max): (f32, f32)) -> Self {
         use wgpu::*;
 
+        check_lod(lod_min, lod_max);
         let inner = {
             let filter = filter.wgpu();
             let desc = SamplerDescriptor {
                 mag_filter: filter,
                 min_filter: filter,
+                lod_min_clamp: lod_min,
+                lod_max_clamp: lod_max,
                 ..Default::default()
             };
 
@@ -218,6 +350,13 @@ impl Sampler {
     }
 }
 
+fn check_lod(min: f32, max: f32) {
+    assert!(
+        0. <= min && min <= max,
+        "the lod range must be non-negative and ordered, got {min}..{max}",
+    );
+}
+
 pub struct CopyBuffer {
     buf: Buffer,
     size: (u32, u32),
@@ -283,8 +422,61 @@ impl CopyBuffer {
         );
     }
 
-    pub fn view(&self) -> CopyBufferView {
-        CopyBufferView(self.buf.slice(..))
+    pub fn view(&self) -> CopyBufferView<'_> {
+        CopyBufferView {
+            buf: &self.buf,
+            slice: self.buf.slice(..),
+        }
+    }
+
+    /// Returns a view of the given rows of the buffer.
+    ///
+    /// A large buffer can be read in chunks of rows, so only one chunk
+    /// is mapped at a time. The buffer is unmapped when the [mapped](Mapped)
+    /// data is dropped, so the next chunk can be mapped after that.
+    /// See the [`chunks`](CopyBuffer::chunks) function to split the whole buffer.
+    ///
+    /// # Panics
+    /// Panics if the range is empty or exceeds the buffer height.
+    pub fn rows(&self, rows: Range<u32>) -> CopyBufferView<'_> {
+        use wgpu::BufferAddress;
+
+        let (width, height) = self.size;
+        assert!(
+            rows.start < rows.end && rows.end <= height,
+            "the rows are out of the buffer",
+        );
+
+        let row = BufferAddress::from(width * self.pixel_size);
+        let start = BufferAddress::from(rows.start) * row;
+        let end = BufferAddress::from(rows.end) * row;
+        CopyBufferView {
+            buf: &self.buf,
+            slice: self.buf.slice(start..end),
+        }
+    }
+
+    /// Returns views of consecutive chunks of the given number of rows.
+    ///
+    /// The last chunk can be shorter. Each view is mapped on its own,
+    /// so a large readback doesn't stay mapped all at once:
+    /// ```rust,ignore
+    /// for chunk in buffer.chunks(64) {
+    ///     let (tx, rx) = oneshot();
+    ///     let mapped = cx.map_view(chunk, tx, rx).await;
+    ///     process(mapped.data());
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the number of rows is zero.
+    pub fn chunks(&self, rows: u32) -> impl Iterator<Item = CopyBufferView<'_>> {
+        assert!(rows != 0, "the chunk must have at least one row");
+
+        let (_, height) = self.size;
+        (0..height)
+            .step_by(rows as usize)
+            .map(move |start| self.rows(start..u32::min(start + rows, height)))
     }
 
     pub fn size(&self) -> (u32, u32) {
@@ -294,7 +486,7 @@ impl CopyBuffer {
 
 impl Drop for CopyBuffer {
     fn drop(&mut self) {
-        self.buf.unmap();
+        // The buffer is already unmapped, since the mapped data borrows it
         self.buf.destroy();
     }
 }
@@ -302,7 +494,10 @@ impl Drop for CopyBuffer {
 pub type MapResult = Result<(), BufferAsyncError>;
 
 #[derive(Clone, Copy)]
-pub struct CopyBufferView<'a>(BufferSlice<'a>);
+pub struct CopyBufferView<'a> {
+    buf: &'a Buffer,
+    slice: BufferSlice<'a>,
+}
 
 impl<'a> CopyBufferView<'a> {
     pub(crate) async fn map<S, R>(self, state: &State, tx: S, rx: R) -> Mapped<'a>
@@ -312,21 +507,43 @@ impl<'a> CopyBufferView<'a> {
     {
         use wgpu::*;
 
-        self.0.map_async(MapMode::Read, tx);
-        state.device().poll(Maintain::Wait);
+        let _map = state.begin_map();
+        self.slice.map_async(MapMode::Read, tx);
+        if !state.can_yield() {
+            state.device().poll(Maintain::Wait);
+        }
+
         if let Err(err) = rx.await {
             panic!("failed to copy texture: {err}");
         }
 
-        Mapped(self.0.get_mapped_range())
+        Mapped {
+            view: Some(self.slice.get_mapped_range()),
+            buf: self.buf,
+        }
     }
 }
 
-pub struct Mapped<'a>(BufferView<'a>);
+/// The mapped data of a [copy buffer](CopyBuffer).
+///
+/// The buffer is unmapped when the data is dropped.
+pub struct Mapped<'a> {
+    view: Option<BufferView<'a>>,
+    buf: &'a Buffer,
+}
 
 impl Mapped<'_> {
     pub fn data(&self) -> &[[u8; 4]] {
-        bytemuck::cast_slice(&self.0)
+        let view = self.view.as_deref().expect("mapped view");
+        bytemuck::cast_slice(view)
+    }
+}
+
+impl Drop for Mapped<'_> {
+    fn drop(&mut self) {
+        // The view must be released before unmapping
+        self.view = None;
+        self.buf.unmap();
     }
 }
 
@@ -340,6 +557,13 @@ impl Get for Texture2d {
     }
 }
 
+/// A texture that can be bound in a shader.
+///
+/// Implemented for textures created with the [`with_bind`](TextureData::with_bind) usage.
+#[diagnostic::on_unimplemented(
+    message = "the texture `{Self}` can't be bound in a shader",
+    note = "create the texture with the `with_bind` usage"
+)]
 pub trait BindTexture: private::Sealed {
     fn bind_texture(&self) -> &Texture2d;
 }
@@ -371,6 +595,13 @@ where
     }
 }
 
+/// A texture that can be drawn to.
+///
+/// Implemented for textures created with the [`with_draw`](TextureData::with_draw) usage.
+#[diagnostic::on_unimplemented(
+    message = "the texture `{Self}` can't be drawn to",
+    note = "create the texture with the `with_draw` usage"
+)]
 pub trait DrawTexture: private::Sealed {
     fn draw_texture(&self) -> &Texture2d;
 }
@@ -413,6 +644,13 @@ where
     }
 }
 
+/// A texture that can be copied from.
+///
+/// Implemented for textures created with the [`with_copy`](TextureData::with_copy) usage.
+#[diagnostic::on_unimplemented(
+    message = "the texture `{Self}` can't be copied from",
+    note = "create the texture with the `with_copy` usage"
+)]
 pub trait CopyTexture: private::Sealed {
     fn copy_texture(&self) -> &Texture2d;
 }
@@ -447,20 +685,65 @@ where
 pub struct Maker<'a> {
     state: &'a State,
     usage: TextureUsages,
+    label: Option<&'a str>,
+    staging: Option<&'a Buffer>,
 }
 
-pub trait Make: private::Sealed {
+pub trait Make: private::Sealed + private::Data {
     type Out;
     fn make(self, maker: Maker) -> Self::Out;
 }
 
 impl private::Sealed for TextureData<'_> {}
 
+impl private::Data for TextureData<'_> {
+    fn texture_data(&self) -> TextureData<'_> {
+        *self
+    }
+}
+
 impl Make for TextureData<'_> {
     type Out = Texture2d;
 
-    fn make(self, Maker { state, usage }: Maker) -> Self::Out {
-        Texture2d::new(state, usage, self)
+    fn make(
+        self,
+        Maker {
+            state,
+            usage,
+            label,
+            staging,
+        }: Maker,
+    ) -> Self::Out {
+        Texture2d::new(state, usage, label, staging, self)
+    }
+}
+
+/// The [texture data](TextureData) of a multisampled texture.
+///
+/// Created with the [`with_samples`](TextureData::with_samples) function.
+#[derive(Clone, Copy)]
+pub struct Multisampled<'a>(TextureData<'a>);
+
+impl<'a> Multisampled<'a> {
+    /// Allow to use a texture as render attachment.
+    pub fn with_draw(self) -> Draw<Self> {
+        Draw(self)
+    }
+}
+
+impl private::Sealed for Multisampled<'_> {}
+
+impl private::Data for Multisampled<'_> {
+    fn texture_data(&self) -> TextureData<'_> {
+        self.0
+    }
+}
+
+impl Make for Multisampled<'_> {
+    type Out = Texture2d;
+
+    fn make(self, maker: Maker) -> Self::Out {
+        self.0.make(maker)
     }
 }
 
@@ -487,6 +770,15 @@ where
 
 impl<M> private::Sealed for Bind<M> {}
 
+impl<M> private::Data for Bind<M>
+where
+    M: private::Data,
+{
+    fn texture_data(&self) -> TextureData<'_> {
+        self.0.texture_data()
+    }
+}
+
 impl<M> Make for Bind<M>
 where
     M: Make,
@@ -502,11 +794,17 @@ where
 pub struct Draw<M>(M);
 
 impl<M> Draw<M> {
-    pub fn with_bind(self) -> Bind<Self> {
+    pub fn with_bind(self) -> Bind<Self>
+    where
+        M: private::Single,
+    {
         Bind(self)
     }
 
-    pub fn with_copy(self) -> Copy<Self> {
+    pub fn with_copy(self) -> Copy<Self>
+    where
+        M: private::Single,
+    {
         Copy(self)
     }
 }
@@ -522,6 +820,15 @@ where
 
 impl<M> private::Sealed for Draw<M> {}
 
+impl<M> private::Data for Draw<M>
+where
+    M: private::Data,
+{
+    fn texture_data(&self) -> TextureData<'_> {
+        self.0.texture_data()
+    }
+}
+
 impl<M> Make for Draw<M>
 where
     M: Make,
@@ -557,6 +864,15 @@ where
 
 impl<M> private::Sealed for Copy<M> {}
 
+impl<M> private::Data for Copy<M>
+where
+    M: private::Data,
+{
+    fn texture_data(&self) -> TextureData<'_> {
+        self.0.texture_data()
+    }
+}
+
 impl<M> Make for Copy<M>
 where
     M: Make,
@@ -570,5 +886,68 @@ where
 }
 
 mod private {
+    use super::{Bind, Copy, Draw, TextureData};
+
     pub trait Sealed {}
+
+    pub trait Data {
+        fn texture_data(&self) -> TextureData<'_>;
+    }
+
+    /// The texture data with one sample per texel.
+    #[diagnostic::on_unimplemented(
+        message = "the multisampled texture can't be bound or copied",
+        note = "resolve it into a texture with one sample"
+    )]
+    pub trait Single {}
+
+    impl Single for TextureData<'_> {}
+    impl<M> Single for Bind<M> {}
+    impl<M> Single for Copy<M> {}
+    impl<M> Single for Draw<M> where M: Single {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_samples() {
+        let empty = TextureData::empty((2, 2), Format::RgbAlpha).expect("non-zero size");
+        let msaa = empty.with_samples(4).expect("no data");
+        assert_eq!(msaa.0.samples, 4);
+
+        let data = TextureData::new(&[0; 16], (2, 2), Format::RgbAlpha).expect("valid data");
+        assert!(matches!(data.with_samples(4), Err(Error::MultisampledData),));
+    }
+
+    #[test]
+    fn lod() {
+        check_lod(0., 0.);
+        check_lod(1., 4.);
+    }
+
+    #[test]
+    #[should_panic(expected = "the lod range must be non-negative and ordered, got 4..1")]
+    fn lod_unordered() {
+        check_lod(4., 1.);
+    }
+
+    #[test]
+    #[should_panic(expected = "the lod range must be non-negative and ordered, got -1..1")]
+    fn lod_negative() {
+        check_lod(-1., 1.);
+    }
+
+    #[test]
+    #[should_panic(expected = "the lod range must be non-negative and ordered, got NaN..1")]
+    fn lod_nan_min() {
+        check_lod(f32::NAN, 1.);
+    }
+
+    #[test]
+    #[should_panic(expected = "the lod range must be non-negative and ordered, got 0..NaN")]
+    fn lod_nan_max() {
+        check_lod(0., f32::NAN);
+    }
 }
@@ -1,4 +1,7 @@
-use {instant::Instant, std::time::Duration};
+use {
+    instant::Instant,
+    std::{fmt, time::Duration},
+};
 
 pub(crate) struct Time {
     last: Instant,
@@ -26,6 +29,15 @@ impl Time {
     }
 }
 
+/// Returns the exponential moving average of the delta time.
+pub(crate) fn smooth(prev: Duration, next: Duration, factor: f32) -> Duration {
+    if prev.is_zero() {
+        return next;
+    }
+
+    prev.mul_f32(factor) + next.mul_f32(1. - factor)
+}
+
 #[derive(Default)]
 pub(crate) struct Fps {
     timer: Duration,
@@ -46,3 +58,138 @@ impl Fps {
         }
     }
 }
+
+/// The state of a frame benchmark.
+#[derive(Default)]
+pub(crate) enum Bench {
+    #[default]
+    Idle,
+    Running {
+        frames: usize,
+        frame_times: Vec<Duration>,
+        cpu_times: Vec<Duration>,
+    },
+    Done(BenchReport),
+}
+
+impl Bench {
+    pub fn start(frames: usize) -> Self {
+        Self::Running {
+            frames,
+            frame_times: Vec::with_capacity(frames),
+            cpu_times: Vec::with_capacity(frames),
+        }
+    }
+
+    pub fn record(&mut self, frame_time: Duration, cpu_time: Duration) {
+        let Self::Running {
+            frames,
+            frame_times,
+            cpu_times,
+        } = self
+        else {
+            return;
+        };
+
+        frame_times.push(frame_time);
+        cpu_times.push(cpu_time);
+        if frame_times.len() >= *frames {
+            *self = Self::Done(BenchReport {
+                frames: frame_times.len(),
+                frame_time: Percentiles::new(frame_times),
+                cpu_time: Percentiles::new(cpu_times),
+            });
+        }
+    }
+}
+
+/// The result of a frame benchmark.
+///
+/// Can be obtained with the control's
+/// [`benchmark_report`](crate::Control::benchmark_report) function.
+/// The report implements [`Display`](fmt::Display) to be printed as a table.
+#[derive(Clone, Copy, Debug)]
+pub struct BenchReport {
+    /// The number of measured frames.
+    pub frames: usize,
+
+    /// The time between frames.
+    pub frame_time: Percentiles,
+
+    /// The time the CPU spends to update, draw and present a frame.
+    pub cpu_time: Percentiles,
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "frames: {}", self.frames)?;
+        writeln!(f, "           p50       p95       p99       max")?;
+        writeln!(f, "frame {}", self.frame_time)?;
+        write!(f, "cpu   {}", self.cpu_time)
+    }
+}
+
+/// Percentiles of measured durations.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Percentiles {
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl Percentiles {
+    fn new(times: &mut [Duration]) -> Self {
+        times.sort_unstable();
+        let at = |p: f32| {
+            let last = times.len().saturating_sub(1);
+            let idx = (last as f32 * p).round() as usize;
+            times.get(idx).copied().unwrap_or_default()
+        };
+
+        Self {
+            p50: at(0.5),
+            p95: at(0.95),
+            p99: at(0.99),
+            max: at(1.),
+        }
+    }
+}
+
+impl fmt::Display for Percentiles {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.;
+        write!(
+            f,
+            "{:7.3}ms {:7.3}ms {:7.3}ms {:7.3}ms",
+            ms(self.p50),
+            ms(self.p95),
+            ms(self.p99),
+            ms(self.max),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bench() {
+        let mut bench = Bench::start(100);
+        for n in (1..=100).rev() {
+            let time = Duration::from_millis(n);
+            bench.record(time, time / 2);
+        }
+
+        let Bench::Done(report) = bench else {
+            panic!("the benchmark should be done");
+        };
+
+        assert_eq!(report.frames, 100);
+        assert_eq!(report.frame_time.p50, Duration::from_millis(51));
+        assert_eq!(report.frame_time.p99, Duration::from_millis(99));
+        assert_eq!(report.frame_time.max, Duration::from_millis(100));
+        assert_eq!(report.cpu_time.max, Duration::from_millis(50));
+    }
+}
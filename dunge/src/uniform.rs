@@ -6,28 +6,44 @@ use {
         state::State,
         types::{self, MatrixType, ScalarType, ValueType, VectorType},
     },
-    std::marker::PhantomData,
-    wgpu::Buffer,
+    std::{
+        marker::PhantomData,
+        ops::{Deref, DerefMut},
+        sync::{
+            atomic::{self, AtomicU32},
+            Arc, MutexGuard,
+        },
+    },
+    wgpu::{util::StagingBelt, Buffer, BufferBinding, BufferSize, CommandEncoder},
 };
 
 /// Uniform shader data.
 ///
 /// Can be created using the context's [`make_uniform`](crate::Context::make_uniform) function.
+///
+/// The value type is a plain array, such as `[f32; 3]` or `[[f32; 4]; 4]`.
+/// The `glam` vectors and matrices are [converted](IntoValue) to them,
+/// so a uniform made from `glam::Vec3` is a `Uniform<[f32; 3]>` and
+/// the group member type is the same for both.
 pub struct Uniform<U> {
     buf: Buffer,
     ty: PhantomData<U>,
 }
 
 impl<U> Uniform<U> {
-    pub(crate) fn new(state: &State, contents: &[u8]) -> Self {
+    pub(crate) fn new(state: &State, contents: &[u8], label: Option<&str>) -> Self
+    where
+        U: Value,
+    {
         use wgpu::{
             util::{BufferInitDescriptor, DeviceExt},
             BufferUsages,
         };
 
+        const { check_layout::<U>() };
         let buf = {
             let desc = BufferInitDescriptor {
-                label: None,
+                label,
                 contents,
                 usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
             };
@@ -57,6 +73,202 @@ impl<U> Uniform<U> {
     }
 }
 
+/// Uniform shader data for frequently changing values.
+///
+/// Can be created using the context's [`make_dynamic_uniform`](crate::Context::make_dynamic_uniform) function.
+///
+/// The uniform allocates a ring of slots in one buffer and binds it with a dynamic offset.
+/// Each [update](DynamicUniform::update) writes the next slot and rotates the offset,
+/// so a new value doesn't overwrite the slot that previous frames are still reading.
+///
+/// This holds only while there are fewer than `len` updates before the GPU
+/// finishes those frames. After `len` updates the ring wraps to the first slot,
+/// which may still be in use. So choose `len` of at least the number of frames
+/// in flight times the number of updates per frame, for example, `3` for a value
+/// updated once per frame with triple buffering.
+pub struct DynamicUniform<U> {
+    buf: Buffer,
+    stride: u32,
+    len: u32,
+    offset: Arc<AtomicU32>,
+    ty: PhantomData<U>,
+}
+
+impl<U> DynamicUniform<U> {
+    pub(crate) fn new(state: &State, contents: &[u8], len: u32) -> Self
+    where
+        U: Value,
+    {
+        use wgpu::{BufferDescriptor, BufferUsages};
+
+        const { check_layout::<U>() };
+        assert!(len != 0, "the dynamic uniform must have at least one slot");
+
+        let align = state.device().limits().min_uniform_buffer_offset_alignment;
+        let stride = (contents.len() as u32).next_multiple_of(align);
+        let buf = {
+            let desc = BufferDescriptor {
+                label: None,
+                size: u64::from(stride) * u64::from(len),
+                usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            };
+
+            state.device().create_buffer(&desc)
+        };
+
+        state.queue().write_buffer(&buf, 0, contents);
+        Self {
+            buf,
+            stride,
+            len,
+            offset: Arc::default(),
+            ty: PhantomData,
+        }
+    }
+
+    /// Updates the uniform data.
+    ///
+    /// The value is written into the next slot of the ring,
+    /// and all bindings of the uniform switch to it.
+    pub fn update<V>(&self, cx: &Context, val: V)
+    where
+        V: IntoValue<Value = U>,
+        U: Value,
+    {
+        let queue = cx.state().queue();
+        let val = val.into_value();
+        let slot = (self.offset.load(atomic::Ordering::Relaxed) / self.stride + 1) % self.len;
+        let offset = slot * self.stride;
+        queue.write_buffer(&self.buf, u64::from(offset), val.value().as_ref());
+        self.offset.store(offset, atomic::Ordering::Relaxed);
+    }
+
+    /// Returns the number of slots in the ring.
+    pub fn slots(&self) -> u32 {
+        self.len
+    }
+
+    pub(crate) fn binding(&self) -> BufferBinding<'_> {
+        BufferBinding {
+            buffer: &self.buf,
+            offset: 0,
+            size: BufferSize::new(u64::from(self.stride)),
+        }
+    }
+
+    pub(crate) fn offset(&self) -> Arc<AtomicU32> {
+        Arc::clone(&self.offset)
+    }
+}
+
+/// A scope that coalesces uniform updates.
+///
+/// Can be created using the context's [`begin_updates`](crate::Context::begin_updates) function.
+///
+/// Every update is written into a staging memory shared by the scopes of the context
+/// and the copies are recorded into a single command buffer. The buffer is submitted
+/// when the scope is [submitted](Updates::submit) or dropped.
+/// Since the submission happens before any following
+/// [`draw_to`](crate::Context::draw_to) call, all updates are visible
+/// to the next draw. If the same uniform is updated several times
+/// within one scope, the last value wins.
+pub struct Updates<'a> {
+    state: &'a State,
+    encoder: Option<CommandEncoder>,
+    belt: Belt<'a>,
+}
+
+impl<'a> Updates<'a> {
+    pub(crate) fn new(state: &'a State) -> Self {
+        // Another scope may use the shared belt at the same time,
+        // then this one gets its own
+        let belt = match state.staging_belt() {
+            Some(belt) => Belt::Shared(belt),
+            None => Belt::Own(StagingBelt::new(State::BELT_CHUNK_SIZE)),
+        };
+
+        Self {
+            state,
+            encoder: None,
+            belt,
+        }
+    }
+
+    /// Schedules the uniform data update.
+    pub fn update<U, V>(&mut self, uni: &Uniform<U>, val: V)
+    where
+        V: IntoValue<Value = U>,
+        U: Value,
+    {
+        use wgpu::CommandEncoderDescriptor;
+
+        let val = val.into_value();
+        let data = val.value();
+        let data = data.as_ref();
+        let Some(size) = BufferSize::new(data.len() as u64) else {
+            return;
+        };
+
+        let device = self.state.device();
+        let encoder = self.encoder.get_or_insert_with(|| {
+            let desc = CommandEncoderDescriptor::default();
+            device.create_command_encoder(&desc)
+        });
+
+        self.belt
+            .write_buffer(encoder, &uni.buf, 0, size, device)
+            .copy_from_slice(data);
+    }
+
+    /// Submits all scheduled updates.
+    pub fn submit(mut self) {
+        self.flush();
+    }
+
+    fn flush(&mut self) {
+        let Some(encoder) = self.encoder.take() else {
+            return;
+        };
+
+        // The staging memory is recalled after the submission to reuse it in next scopes
+        self.belt.finish();
+        self.state.queue().submit([encoder.finish()]);
+        self.belt.recall();
+    }
+}
+
+impl Drop for Updates<'_> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+enum Belt<'a> {
+    Shared(MutexGuard<'a, StagingBelt>),
+    Own(StagingBelt),
+}
+
+impl Deref for Belt<'_> {
+    type Target = StagingBelt;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Shared(belt) => belt,
+            Self::Own(belt) => belt,
+        }
+    }
+}
+
+impl DerefMut for Belt<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::Shared(belt) => belt,
+            Self::Own(belt) => belt,
+        }
+    }
+}
+
 /// Uniform value.
 pub trait Value: private::Sealed {
     const TYPE: ValueType;
@@ -65,6 +277,21 @@ pub trait Value: private::Sealed {
     fn value(self) -> Self::Data;
 }
 
+/// Checks the data of the value matches its layout in a shader.
+///
+/// The data holds the value in the uniform layout padded to 16 bytes,
+/// otherwise the shader would read the fields at wrong offsets.
+const fn check_layout<U>()
+where
+    U: Value,
+{
+    let size = U::TYPE.uniform_size().next_multiple_of(16);
+    assert!(
+        size_of::<U::Data>() == size,
+        "the uniform data doesn't match the shader layout",
+    );
+}
+
 /// Uniform binary data.
 pub struct Data<const N: usize = 4>([f32; N]);
 
@@ -141,10 +368,12 @@ impl private::Sealed for [[f32; 3]; 3] {}
 impl Value for [[f32; 3]; 3] {
     const TYPE: ValueType = ValueType::Matrix(MatrixType::Mat3);
     type Type = types::Mat3;
-    type Data = Data<9>;
+    type Data = Data<12>;
 
     fn value(self) -> Self::Data {
-        Data(bytemuck::cast(self))
+        // Each column is aligned to 16 bytes
+        let [[a, b, c], [d, e, f], [g, h, i]] = self;
+        Data([a, b, c, 0., d, e, f, 0., g, h, i, 0.])
     }
 }
 
@@ -228,3 +457,31 @@ impl IntoValue for glam::Mat4 {
 mod private {
     pub trait Sealed: bytemuck::NoUninit {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout() {
+        const {
+            check_layout::<f32>();
+            check_layout::<[f32; 2]>();
+            check_layout::<[f32; 3]>();
+            check_layout::<[f32; 4]>();
+            check_layout::<[[f32; 2]; 2]>();
+            check_layout::<[[f32; 3]; 3]>();
+            check_layout::<[[f32; 4]; 4]>();
+        }
+    }
+
+    #[test]
+    fn mat3_columns() {
+        let data = [[1., 2., 3.], [4., 5., 6.], [7., 8., 9.]].value();
+        let stride = MatrixType::Mat3.column_stride() / size_of::<f32>();
+        for (n, col) in data.0.chunks(stride).enumerate() {
+            let first = (n * 3 + 1) as f32;
+            assert_eq!(col[..3], [first, first + 1., first + 2.]);
+        }
+    }
+}
@@ -91,6 +91,18 @@ pub trait Update: Draw {
     type Event: 'static;
     fn update(&mut self, ctrl: &Control) -> Self::Flow;
     fn event(&mut self, _: Self::Event) {}
+
+    /// Called when the application is resumed after a suspension.
+    ///
+    /// On suspend the window surface is dropped, since some platforms
+    /// like Android destroy the native window, and it is recreated on resume.
+    /// Only the surface and the window's multisample and depth buffers
+    /// are recreated. Everything created with the [context](Context)
+    /// like meshes, textures, uniforms and layers survives the suspension,
+    /// so there is nothing to recreate by default. The window size may
+    /// change, it is reported with the [`resized`](Control::resized)
+    /// function in the following update.
+    fn resume(&mut self, _: &Context, _: &View) {}
 }
 
 /// Helper function to create a [`Update`]
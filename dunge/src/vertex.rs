@@ -1,11 +1,38 @@
 //! The vertex module.
 
-use crate::{
-    sl::{ReadVertex, Ret},
-    types::{self, VectorType},
+use {
+    crate::{
+        sl::{ReadVertex, Ret},
+        types::{self, VectorType},
+        Vertex,
+    },
+    std::{borrow::Cow, iter},
 };
 
-pub use dunge_shader::vertex::{verts_as_bytes, Projection};
+pub use dunge_shader::vertex::{stream_count, verts_as_bytes, Projection};
+
+/// Splits vertex bytes into separate buffers of the vertex streams.
+pub(crate) fn split_streams<V>(bytes: &[u8]) -> Vec<Cow<'_, [u8]>>
+where
+    V: Vertex,
+{
+    let count = stream_count::<V>();
+    if count == 1 {
+        return vec![Cow::Borrowed(bytes)];
+    }
+
+    let mut streams = vec![vec![]; count];
+    for vert in bytes.chunks_exact(size_of::<V>()) {
+        let mut offset = 0;
+        for (vecty, stream) in iter::zip(V::DEF, V::STREAMS) {
+            let field = &vert[offset..offset + vecty.size()];
+            streams[stream as usize].extend_from_slice(field);
+            offset += vecty.size();
+        }
+    }
+
+    streams.into_iter().map(Cow::Owned).collect()
+}
 
 /// Describes an input type projection.
 ///
@@ -49,6 +76,39 @@ impl InputProjection for [f32; 4] {
     }
 }
 
+impl private::Sealed for glam::Vec2 {}
+
+impl InputProjection for glam::Vec2 {
+    const TYPE: VectorType = VectorType::Vec2f;
+    type Field = Ret<ReadVertex, types::Vec2<f32>>;
+
+    fn input_projection(id: u32, index: u32) -> Self::Field {
+        ReadVertex::new(id, index)
+    }
+}
+
+impl private::Sealed for glam::Vec3 {}
+
+impl InputProjection for glam::Vec3 {
+    const TYPE: VectorType = VectorType::Vec3f;
+    type Field = Ret<ReadVertex, types::Vec3<f32>>;
+
+    fn input_projection(id: u32, index: u32) -> Self::Field {
+        ReadVertex::new(id, index)
+    }
+}
+
+impl private::Sealed for glam::Vec4 {}
+
+impl InputProjection for glam::Vec4 {
+    const TYPE: VectorType = VectorType::Vec4f;
+    type Field = Ret<ReadVertex, types::Vec4<f32>>;
+
+    fn input_projection(id: u32, index: u32) -> Self::Field {
+        ReadVertex::new(id, index)
+    }
+}
+
 mod private {
     pub trait Sealed {}
 }
@@ -7,9 +7,13 @@ use {
         element::Element,
         format::Format,
         state::{State, Target},
+        texture::{self, DrawTexture, Texture2d, TextureData},
         update::IntoUpdate,
     },
-    std::{error, fmt, sync::Arc},
+    std::{
+        error, fmt,
+        sync::{mpsc, Arc, Mutex},
+    },
     wgpu::{
         CreateSurfaceError, Surface, SurfaceConfiguration, SurfaceError, SurfaceTexture,
         TextureView,
@@ -17,11 +21,24 @@ use {
     winit::{
         error::{EventLoopError, OsError},
         event_loop::{ActiveEventLoop, EventLoop, EventLoopClosed, EventLoopProxy},
-        window::{self, WindowAttributes, WindowId},
+        window::{self, Icon, WindowAttributes, WindowId},
     },
 };
 
-pub struct Notifier<V>(EventLoopProxy<V>)
+pub use winit::window::BadIcon;
+
+/// The message sent to the main loop by a [notifier](Notifier).
+pub(crate) enum Message<V> {
+    Wake,
+    Event(V),
+}
+
+/// The handle to reach the main loop from another thread.
+///
+/// Can be created using the window state's [`notifier`](WindowState::notifier) function.
+/// The loop requests a redraw when it receives an event or a wake-up,
+/// so the update stage runs as soon as the frame rate allows.
+pub struct Notifier<V>(EventLoopProxy<Message<V>>)
 where
     V: 'static;
 
@@ -31,7 +48,107 @@ impl<V> Notifier<V> {
     /// # Errors
     /// If the main loop was stopped, the event will return back.
     pub fn notify(&self, ev: V) -> Result<(), V> {
-        self.0.send_event(ev).map_err(|EventLoopClosed(ev)| ev)
+        self.0
+            .send_event(Message::Event(ev))
+            .map_err(|EventLoopClosed(mes)| match mes {
+                Message::Event(ev) => ev,
+                Message::Wake => unreachable!(),
+            })
+    }
+
+    /// Wakes the main loop up to redraw without sending an event.
+    ///
+    /// Returns `false` if the main loop was stopped.
+    pub fn wake(&self) -> bool {
+        self.0.send_event(Message::Wake).is_ok()
+    }
+}
+
+impl<V> Clone for Notifier<V> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<V> Notifier<V>
+where
+    V: Send,
+{
+    /// Creates a typed channel to the main loop.
+    ///
+    /// Unlike the [`notify`](Notifier::notify) function, the channel doesn't depend
+    /// on the event type of the loop, so a loop can have several channels for
+    /// different messages, for example, a decoder sending new frames and a watcher
+    /// requesting to reload a config. Each sent message wakes the loop up,
+    /// and the receiver can be drained in the update stage:
+    /// ```rust,ignore
+    /// let (tx, rx) = ws.notifier().channel();
+    /// thread::spawn(move || _ = tx.send(decode()));
+    ///
+    /// // In the update stage
+    /// for frame in rx.try_iter() {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn channel<T>(&self) -> (Sender<T>, Receiver<T>) {
+        let (tx, rx) = mpsc::channel();
+        let wake = Arc::new(Mutex::new(self.clone()));
+        (Sender { tx, wake }, Receiver(rx))
+    }
+}
+
+trait Wake: Send + Sync {
+    fn wake(&self) -> bool;
+}
+
+impl<V> Wake for Mutex<Notifier<V>>
+where
+    V: Send,
+{
+    fn wake(&self) -> bool {
+        self.lock().expect("lock notifier").wake()
+    }
+}
+
+/// The sending half of a [channel](Notifier::channel).
+pub struct Sender<T> {
+    tx: mpsc::Sender<T>,
+    wake: Arc<dyn Wake>,
+}
+
+impl<T> Sender<T> {
+    /// Sends a message and wakes the main loop up.
+    ///
+    /// # Errors
+    /// If the receiver was dropped, the message will return back.
+    pub fn send(&self, mes: T) -> Result<(), T> {
+        self.tx.send(mes).map_err(|mpsc::SendError(mes)| mes)?;
+        self.wake.wake();
+        Ok(())
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            wake: Arc::clone(&self.wake),
+        }
+    }
+}
+
+/// The receiving half of a [channel](Notifier::channel).
+pub struct Receiver<T>(mpsc::Receiver<T>);
+
+impl<T> Receiver<T> {
+    /// Returns a received message if there is one.
+    pub fn try_recv(&self) -> Option<T> {
+        self.0.try_recv().ok()
+    }
+
+    /// Returns an iterator over all received messages without blocking.
+    pub fn try_iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.0.try_iter()
     }
 }
 
@@ -40,8 +157,11 @@ where
     V: 'static,
 {
     attrs: WindowAttributes,
+    formats: Vec<Format>,
+    buffers: Buffers,
+    present: Present,
     el: Element,
-    lu: EventLoop<V>,
+    lu: EventLoop<Message<V>>,
 }
 
 impl<V> WindowState<V> {
@@ -67,6 +187,53 @@ impl<V> WindowState<V> {
         }
     }
 
+    /// Set the minimum window size.
+    pub fn with_min_size(self, (width, height): (u32, u32)) -> Self {
+        use winit::dpi::PhysicalSize;
+
+        let size = PhysicalSize::new(width, height);
+        Self {
+            attrs: self.attrs.with_min_inner_size(size),
+            ..self
+        }
+    }
+
+    /// Set the maximum window size.
+    pub fn with_max_size(self, (width, height): (u32, u32)) -> Self {
+        use winit::dpi::PhysicalSize;
+
+        let size = PhysicalSize::new(width, height);
+        Self {
+            attrs: self.attrs.with_max_inner_size(size),
+            ..self
+        }
+    }
+
+    /// Set whether the window is resizable by the user.
+    ///
+    /// To get a window of fixed size, disable resizing and
+    /// set the size with the [`with_size`](WindowState::with_size) method.
+    pub fn with_resizable(self, resizable: bool) -> Self {
+        Self {
+            attrs: self.attrs.with_resizable(resizable),
+            ..self
+        }
+    }
+
+    /// Set the window icon from RGBA pixel data.
+    ///
+    /// The icon is ignored on platforms that don't support it, such as the web.
+    ///
+    /// # Errors
+    /// Returns an error if the data length doesn't match the size.
+    pub fn with_icon(self, rgba: &[u8], (width, height): (u32, u32)) -> Result<Self, BadIcon> {
+        let icon = Icon::from_rgba(rgba.to_vec(), width, height)?;
+        Ok(Self {
+            attrs: self.attrs.with_window_icon(Some(icon)),
+            ..self
+        })
+    }
+
     /// Enables fullscreen for the window.
     pub fn with_fullscreen(self) -> Self {
         use winit::window::Fullscreen;
@@ -78,6 +245,106 @@ impl<V> WindowState<V> {
         }
     }
 
+    /// Set preferred surface formats.
+    ///
+    /// The first format supported by the surface will be selected,
+    /// so the list should be ordered by priority. For example, pass
+    /// [`Format::RgbAlphaHalf`] first to get a float surface for wide-gamut
+    /// displays with a fallback to an 8-bit format. The color space is
+    /// chosen by the platform for the selected format.
+    /// The selected format is available via the [`format`](View::format) method.
+    ///
+    /// By default, sRGB formats are preferred over linear ones.
+    pub fn with_formats(self, formats: &[Format]) -> Self {
+        Self {
+            formats: formats.to_vec(),
+            ..self
+        }
+    }
+
+    /// Attaches a depth buffer to the window target.
+    ///
+    /// The buffer is resized with the window, so layers with
+    /// [depth](crate::layer::Config::depth) can be drawn directly to the window.
+    /// Clear it with the [`clear_depth`](crate::Options::clear_depth) option.
+    pub fn with_depth(self) -> Self {
+        self.with_depth_format(Format::Depth)
+    }
+
+    /// Attaches a depth buffer of the given format to the window target.
+    ///
+    /// Layers drawn to the window must have the same
+    /// [depth format](crate::layer::Config::depth_format).
+    ///
+    /// # Panics
+    /// Panics if the format isn't a depth format.
+    pub fn with_depth_format(self, format: Format) -> Self {
+        assert!(format.is_depth(), "the format must be a depth format");
+        Self {
+            buffers: Buffers {
+                depth: Some(format),
+                ..self.buffers
+            },
+            ..self
+        }
+    }
+
+    /// Sets the number of samples per pixel of the window target.
+    ///
+    /// With more than one sample, the window is drawn to a multisampled buffer
+    /// which is resolved to the surface after each layer. Layers drawn to the window
+    /// must have the same [sample count](crate::layer::Config::samples),
+    /// which is available via the [`samples`](View::samples) method.
+    /// The count of 4 is supported by all devices.
+    pub fn with_samples(self, samples: u32) -> Self {
+        Self {
+            buffers: Buffers {
+                samples,
+                ..self.buffers
+            },
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of frames queued for presentation.
+    ///
+    /// With more frames in flight the CPU can prepare the next frame while
+    /// the GPU still draws the previous ones, which improves throughput and
+    /// smooths frame pacing, but every queued frame adds to the input latency.
+    /// A latency of 1 makes the CPU wait for the GPU on each frame and
+    /// reduces the input lag, which is useful for fast-paced games on slow devices.
+    /// The value is a hint, the backend may not support it exactly.
+    ///
+    /// The default latency is 2. The latency of 0 is treated as 1.
+    pub fn with_frame_latency(self, latency: u32) -> Self {
+        Self {
+            present: Present {
+                latency: latency.max(1),
+                ..self.present
+            },
+            ..self
+        }
+    }
+
+    /// Sets whether presentation waits for the vertical sync.
+    ///
+    /// Without the vsync, frames are presented as soon as they are drawn,
+    /// which can cause tearing but doesn't limit the frame rate by the display.
+    /// It's useful to measure the performance with a
+    /// [benchmark](crate::Control::start_benchmark). If the surface
+    /// doesn't support presenting without vsync, the setting is ignored.
+    ///
+    /// Enabled by default.
+    pub fn with_vsync(self, vsync: bool) -> Self {
+        Self {
+            present: Present {
+                vsync,
+                ..self.present
+            },
+            ..self
+        }
+    }
+
     /// Creates a new [notifier](Notifier).
     pub fn notifier(&self) -> Notifier<V> {
         Notifier(self.lu.create_proxy())
@@ -100,9 +367,12 @@ impl<V> WindowState<V> {
         el::run_local(self, cx, upd)
     }
 
-    pub(crate) fn into_view_and_loop(self) -> (View, EventLoop<V>) {
+    pub(crate) fn into_view_and_loop(self) -> (View, EventLoop<Message<V>>) {
         let view = View {
             init: Init::Empty(Box::new(self.attrs)),
+            formats: self.formats,
+            buffers: self.buffers,
+            present: self.present,
             id: WindowId::from(u64::MAX),
             el: self.el,
             format: Format::default(),
@@ -138,16 +408,48 @@ pub fn from_element(id: &str) -> WindowState {
 
 fn state<V>(el: Element) -> WindowState<V> {
     let attrs = WindowAttributes::default();
+    let formats = vec![
+        Format::SrgbAlpha,
+        Format::SbgrAlpha,
+        Format::RgbAlpha,
+        Format::BgrAlpha,
+    ];
+
     let Ok(lu) = EventLoop::with_user_event().build() else {
         panic!("attempt to recreate the event loop");
     };
 
-    WindowState { attrs, el, lu }
+    WindowState {
+        attrs,
+        formats,
+        buffers: Buffers {
+            depth: None,
+            samples: 1,
+        },
+        present: Present {
+            latency: 2,
+            vsync: true,
+        },
+        el,
+        lu,
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Present {
+    latency: u32,
+    vsync: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Buffers {
+    depth: Option<Format>,
+    samples: u32,
 }
 
 enum Init {
     Empty(Box<WindowAttributes>),
-    Active(Inner),
+    Active(Box<Inner>),
 }
 
 impl Init {
@@ -168,6 +470,9 @@ impl Init {
 
 pub struct View {
     init: Init,
+    formats: Vec<Format>,
+    buffers: Buffers,
+    present: Present,
     id: WindowId,
     el: Element,
     format: Format,
@@ -184,10 +489,10 @@ impl View {
                 self.el.set_canvas(&window);
                 self.el.set_window_size(&window);
 
-                let inner = Inner::new(state, window)?;
+                let inner = Inner::new(state, window, &self.formats, self.buffers, self.present)?;
                 self.format = inner.format();
                 self.size = inner.size();
-                self.init = Init::Active(inner);
+                self.init = Init::Active(Box::new(inner));
                 Ok(())
             }
             Init::Active(_) => Ok(()),
@@ -198,6 +503,11 @@ impl View {
         &self.init.get().window
     }
 
+    /// Changes the window title.
+    pub fn set_title(&self, title: &str) {
+        self.window().set_title(title);
+    }
+
     pub fn format(&self) -> Format {
         self.format
     }
@@ -206,6 +516,31 @@ impl View {
         self.size
     }
 
+    /// Returns `true` if the window target has a [depth buffer](WindowState::with_depth).
+    pub fn depth(&self) -> bool {
+        self.buffers.depth.is_some()
+    }
+
+    /// Returns the depth format of the window target if it has a depth buffer.
+    pub fn depth_format(&self) -> Option<Format> {
+        self.buffers.depth
+    }
+
+    /// Returns the [sample count](WindowState::with_samples) of the window target.
+    pub fn samples(&self) -> u32 {
+        self.buffers.samples
+    }
+
+    /// Returns the [frame latency](WindowState::with_frame_latency) of the window surface.
+    pub fn frame_latency(&self) -> u32 {
+        self.present.latency
+    }
+
+    /// Returns `true` if the window [vsync](WindowState::with_vsync) is enabled.
+    pub fn vsync(&self) -> bool {
+        self.present.vsync
+    }
+
     pub(crate) fn id(&self) -> WindowId {
         self.id
     }
@@ -214,12 +549,13 @@ impl View {
         self.init.get().window.request_redraw();
     }
 
-    pub(crate) fn output(&self) -> Result<Output, SurfaceError> {
+    pub(crate) fn output(&self) -> Result<Output<'_>, SurfaceError> {
         use wgpu::TextureViewDescriptor;
 
         let inner = self.init.get();
         let format = inner.format();
-        let output = inner.surface.get_current_texture()?;
+        let surface = inner.surface.as_ref().ok_or(SurfaceError::Lost)?;
+        let output = surface.get_current_texture()?;
         let view = {
             let desc = TextureViewDescriptor::default();
             output.texture.create_view(&desc)
@@ -229,6 +565,8 @@ impl View {
             view,
             format,
             output,
+            msaa: inner.msaa.as_ref().map(|msaa| msaa.draw_texture()),
+            depth: inner.depth.as_ref().map(|depth| depth.draw_texture()),
         })
     }
 
@@ -240,46 +578,83 @@ impl View {
     pub(crate) fn resize(&mut self, state: &State) {
         let inner = self.init.get_mut();
         let size = inner.window.inner_size();
-        if size.width > 0 && size.height > 0 {
+        if let (Some(surface), true) = (&inner.surface, size.width > 0 && size.height > 0) {
             inner.conf.width = size.width;
             inner.conf.height = size.height;
-            inner.surface.configure(state.device(), &inner.conf);
+            surface.configure(state.device(), &inner.conf);
+            inner.make_buffers(state, self.buffers);
             self.size = inner.size();
         }
     }
+
+    /// Drops the window surface when the application is suspended.
+    pub(crate) fn suspend(&mut self) {
+        if let Init::Active(inner) = &mut self.init {
+            inner.surface = None;
+        }
+    }
+
+    /// Recreates the surface dropped on suspend.
+    ///
+    /// Returns `true` if the surface was recreated.
+    pub(crate) fn resume(&mut self, state: &State) -> Result<bool, Error> {
+        let Init::Active(inner) = &mut self.init else {
+            return Ok(false);
+        };
+
+        if inner.surface.is_some() {
+            return Ok(false);
+        }
+
+        let surface = state.instance().create_surface(Arc::clone(&inner.window))?;
+        inner.surface = Some(surface);
+        self.resize(state);
+        Ok(true)
+    }
 }
 
 struct Inner {
     conf: SurfaceConfiguration,
-    surface: Surface<'static>,
+
+    /// The surface is dropped while the application is suspended.
+    surface: Option<Surface<'static>>,
     window: Arc<window::Window>,
+    msaa: Option<texture::Draw<Texture2d>>,
+    depth: Option<texture::Draw<Texture2d>>,
 }
 
 impl Inner {
-    fn new(state: &State, window: window::Window) -> Result<Self, Error> {
+    fn new(
+        state: &State,
+        window: window::Window,
+        formats: &[Format],
+        buffers: Buffers,
+        present: Present,
+    ) -> Result<Self, Error> {
         use wgpu::*;
 
-        let supported_formats = const {
-            [
-                Format::SrgbAlpha,
-                Format::SbgrAlpha,
-                Format::RgbAlpha,
-                Format::BgrAlpha,
-            ]
-        };
-
         let window = Arc::new(window);
         let surface = state.instance().create_surface(Arc::clone(&window))?;
         let conf = {
             let caps = surface.get_capabilities(state.adapter());
-            let format = supported_formats.into_iter().find_map(|format| {
+            let preferred = formats.iter().find_map(|format| {
                 let format = format.wgpu();
                 caps.formats.contains(&format).then_some(format)
             });
 
-            let Some(format) = format else {
+            // Fall back to any color format the surface supports
+            let fallback = || {
+                let format = caps.formats.iter().copied().find(|&format| {
+                    Format::try_from_wgpu(format).is_some_and(|format| !format.is_depth())
+                })?;
+
+                log::warn!("no preferred surface format, fall back to {format:?}");
+                Some(format)
+            };
+
+            let Some(format) = preferred.or_else(fallback) else {
                 log::error!("surface formats: {formats:?}", formats = &caps.formats);
-                return Err(ErrorKind::UnsupportedSurface.into());
+                return Err(ErrorKind::UnsupportedSurface(caps.formats).into());
             };
 
             let size = window.inner_size();
@@ -288,19 +663,44 @@ impl Inner {
                 format,
                 width: size.width.max(1),
                 height: size.height.max(1),
-                present_mode: PresentMode::default(),
-                desired_maximum_frame_latency: 2,
+                present_mode: if present.vsync {
+                    PresentMode::Fifo
+                } else {
+                    PresentMode::AutoNoVsync
+                },
+                desired_maximum_frame_latency: present.latency,
                 alpha_mode: CompositeAlphaMode::default(),
                 view_formats: vec![],
             }
         };
 
         surface.configure(state.device(), &conf);
-        Ok(Self {
+        let mut inner = Self {
             conf,
-            surface,
+            surface: Some(surface),
             window,
-        })
+            msaa: None,
+            depth: None,
+        };
+
+        inner.make_buffers(state, buffers);
+        Ok(inner)
+    }
+
+    fn make_buffers(&mut self, state: &State, buffers: Buffers) {
+        let size = self.size();
+        let make = |format| {
+            let data = TextureData::empty(size, format)
+                .expect("non-zero size")
+                .with_samples(buffers.samples)
+                .expect("no data")
+                .with_draw();
+
+            texture::make(state, data, None)
+        };
+
+        self.msaa = (buffers.samples > 1).then(|| make(self.format()));
+        self.depth = buffers.depth.map(make);
     }
 
     fn format(&self) -> Format {
@@ -312,15 +712,28 @@ impl Inner {
     }
 }
 
-pub(crate) struct Output {
+pub(crate) struct Output<'a> {
     view: TextureView,
     format: Format,
     output: SurfaceTexture,
+    msaa: Option<&'a Texture2d>,
+    depth: Option<&'a Texture2d>,
 }
 
-impl Output {
-    pub fn target(&self) -> Target {
-        Target::new(self.format, &self.view)
+impl Output<'_> {
+    pub fn target(&self) -> Target<'_> {
+        let mut target = match self.msaa {
+            Some(msaa) => Target::new(self.format, msaa.view())
+                .with_samples(msaa.samples())
+                .with_resolve(&self.view),
+            None => Target::new(self.format, &self.view),
+        };
+
+        if let Some(depth) = self.depth {
+            target = target.with_depth(depth);
+        }
+
+        target
     }
 
     pub fn present(self) {
@@ -364,7 +777,9 @@ impl From<FailedMakeContext> for Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self.0 {
-            ErrorKind::UnsupportedSurface => write!(f, "unsupported surface"),
+            ErrorKind::UnsupportedSurface(formats) => {
+                write!(f, "unsupported surface formats: {formats:?}")
+            }
             ErrorKind::EventLoop(err) => err.fmt(f),
             ErrorKind::Os(err) => err.fmt(f),
             ErrorKind::Surface(err) => err.fmt(f),
@@ -376,7 +791,7 @@ impl fmt::Display for Error {
 impl error::Error for Error {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match &self.0 {
-            ErrorKind::UnsupportedSurface => None,
+            ErrorKind::UnsupportedSurface(_) => None,
             ErrorKind::EventLoop(err) => Some(err),
             ErrorKind::Os(err) => Some(err),
             ErrorKind::Surface(err) => Some(err),
@@ -387,7 +802,7 @@ impl error::Error for Error {
 
 #[derive(Debug)]
 enum ErrorKind {
-    UnsupportedSurface,
+    UnsupportedSurface(Vec<wgpu::TextureFormat>),
     EventLoop(EventLoopError),
     Os(OsError),
     Surface(CreateSurfaceError),
@@ -0,0 +1,32 @@
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+    @location(0) member_1: vec2<f32>,
+    @location(1) member_2: vec4<f32>,
+}
+
+@group(0) @binding(0) 
+var global: texture_2d<f32>;
+@group(0) @binding(1) 
+var global_1: sampler;
+@group(0) @binding(2) 
+var<uniform> global_2: vec2<f32>;
+
+@vertex 
+fn vs(@location(0) param: vec4<f32>, @location(1) param_1: vec4<f32>, @location(2) param_2: vec4<f32>, @location(3) param_3: vec4<f32>, @builtin(vertex_index) param_4: u32) -> VertexOutput {
+    let _e6: f32 = f32((param_4 / 2u));
+    let _e7: f32 = (1f - _e6);
+    let _e9: f32 = f32((param_4 % 2u));
+    let _e10: f32 = (1f - _e9);
+    let _e11: f32 = (_e7 * _e10);
+    let _e14: f32 = (_e7 * _e9);
+    let _e19: f32 = (_e6 * _e10);
+    let _e23: f32 = (_e6 * _e9);
+    let _e27: vec2<f32> = global_2;
+    return VertexOutput(vec4<f32>((((((((param.x * _e11) + (param.z * _e14)) + (param_1.x * _e19)) + (param_1.z * _e23)) / _e27.x) * 2f) - 1f), (1f - ((((((param.y * _e11) + (param.w * _e14)) + (param_1.y * _e19)) + (param_1.w * _e23)) / _e27.y) * 2f)), 0f, 1f), vec2<f32>((param_2.x + (_e6 * param_2.z)), (param_2.y + (_e9 * param_2.w))), param_3);
+}
+
+@fragment 
+fn fs(param_5: VertexOutput) -> @location(0) vec4<f32> {
+    let _e4: vec4<f32> = textureSample(global, global_1, param_5.member_1);
+    return vec4<f32>((_e4.x * param_5.member_2.x), (_e4.y * param_5.member_2.y), (_e4.z * param_5.member_2.z), (_e4.w * param_5.member_2.w));
+}
@@ -0,0 +1,1092 @@
+#![cfg(not(target_family = "wasm"))]
+
+type Error = Box<dyn std::error::Error>;
+
+#[test]
+fn layer_pipeline_cache() -> Result<(), Error> {
+    use dunge::{
+        layer::{Blend, Config},
+        prelude::*,
+        sl::{self, Index, Out},
+    };
+
+    let compute = |Index(_): Index| Out {
+        place: sl::splat_vec4(1.),
+        color: sl::splat_vec4(1.),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    let a = cx.make_layer(&shader, Format::RgbAlpha);
+    let b = cx.make_layer(&shader, Format::RgbAlpha);
+    let stats = cx.pipeline_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.len), (1, 1, 1));
+
+    let conf = Config {
+        format: Format::RgbAlpha,
+        blend: Blend::Alpha,
+        ..Default::default()
+    };
+
+    let c = cx.make_layer(&shader, conf);
+    let stats = cx.pipeline_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.len), (1, 2, 2));
+
+    drop((a, b, c));
+    assert_eq!(cx.pipeline_cache_stats().len, 0);
+    Ok(())
+}
+
+#[test]
+fn layer_make_many() -> Result<(), Error> {
+    use dunge::{
+        layer::{Blend, Config},
+        prelude::*,
+        sl::{self, Index, Out},
+    };
+
+    let compute = |Index(_): Index| Out {
+        place: sl::splat_vec4(1.),
+        color: sl::splat_vec4(1.),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    let formats = [Format::RgbAlpha, Format::BgrAlpha, Format::SrgbAlpha];
+    let layers: Vec<_> = formats
+        .into_iter()
+        .map(|format| {
+            let conf = Config {
+                format,
+                blend: Blend::Alpha,
+                ..Default::default()
+            };
+
+            (&shader, conf)
+        })
+        .collect();
+
+    let layers = cx.make_layers(&layers);
+    let made: Vec<_> = layers.iter().map(|layer| layer.format()).collect();
+    assert_eq!(made, formats);
+    assert_eq!(cx.pipeline_cache_stats().len, formats.len());
+    Ok(())
+}
+
+#[test]
+fn layer_make_many_same() -> Result<(), Error> {
+    use dunge::{
+        prelude::*,
+        sl::{self, Index, Out},
+    };
+
+    let compute = |Index(_): Index| Out {
+        place: sl::splat_vec4(1.),
+        color: sl::splat_vec4(1.),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    let layers = vec![(&shader, Format::RgbAlpha.into()); 32];
+    let layers = cx.make_layers(&layers);
+    assert_eq!(layers.len(), 32);
+
+    // Concurrent requests of the same pipeline compile it once
+    let stats = cx.pipeline_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.len), (31, 1, 1));
+    Ok(())
+}
+
+#[test]
+fn layer_bundle() -> Result<(), Error> {
+    use {
+        dunge::{
+            color::Rgba,
+            prelude::*,
+            sl::{self, InVertex, Out},
+        },
+        glam::Vec2,
+    };
+
+    #[repr(C)]
+    #[derive(Vertex)]
+    struct Vert([f32; 2], [f32; 3]);
+
+    let triangle = |vert: InVertex<Vert>| Out {
+        place: sl::vec4_concat(vert.0, Vec2::new(0., 1.)),
+        color: sl::vec4_with(sl::fragment(vert.1), 1.),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(triangle);
+    let size = const { (300, 300) };
+    let layer = cx.make_layer(&shader, Format::SrgbAlpha);
+    let view = {
+        let data = TextureData::empty(size, Format::SrgbAlpha)?
+            .with_draw()
+            .with_copy();
+
+        cx.make_texture(data)
+    };
+
+    let mesh = {
+        let data = const {
+            MeshData::from_verts(&[
+                Vert([0., -0.75], [1., 0., 0.]),
+                Vert([0.866, 0.75], [0., 1., 0.]),
+                Vert([-0.866, 0.75], [0., 0., 1.]),
+            ])
+        };
+
+        cx.make_mesh(&data)
+    };
+
+    let bundle = cx.make_bundle(&layer, |rec| rec.bind_empty().draw(&mesh));
+    let buffer = cx.make_copy_buffer(size);
+    let opts = Rgba::from_standard([0., 0., 0., 1.]);
+    let render = |bundled| {
+        let draw = dunge::draw(|mut frame| {
+            if bundled {
+                frame.execute_bundle(&bundle, opts);
+            } else {
+                frame.layer(&layer, opts).bind_empty().draw(&mesh);
+            }
+
+            frame.copy_texture(&buffer, &view);
+        });
+
+        cx.draw_to(&view, draw);
+        let mapped = helpers::block_on({
+            let (tx, rx) = helpers::oneshot();
+            cx.map_view(buffer.view(), tx, rx)
+        });
+
+        mapped.data().to_vec()
+    };
+
+    assert_eq!(render(true), render(false));
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "a bundle can't use dynamic uniforms")]
+fn layer_bundle_dynamic_uniform() {
+    use dunge::{
+        prelude::*,
+        sl::{self, Groups, Index, Out},
+        uniform::DynamicUniform,
+    };
+
+    #[derive(Group)]
+    struct Map<'a> {
+        col: &'a DynamicUniform<[f32; 4]>,
+    }
+
+    let compute = |idx: Index, Groups(map): Groups<Map>| Out {
+        place: sl::fullscreen_place(idx),
+        color: map.col,
+    };
+
+    let cx = helpers::block_on(dunge::context()).expect("create context");
+    let shader = cx.make_shader(compute);
+    let layer = cx.make_layer(&shader, Format::RgbAlpha);
+    let col = cx.make_dynamic_uniform([1., 0., 0., 1.], 2);
+    let bind = {
+        let mut binder = cx.make_binder(&shader);
+        binder.add(&Map { col: &col });
+        binder.into_binding()
+    };
+
+    _ = cx.make_bundle(&layer, |rec| rec.bind(&bind).draw_points(3));
+}
+
+#[test]
+fn layer_depth_format() -> Result<(), Error> {
+    use dunge::{
+        color::Rgba,
+        layer::Config,
+        prelude::*,
+        sl::{self, Index, Out},
+        RenderBuffer,
+    };
+
+    let compute = |Index(_): Index| Out {
+        place: sl::splat_vec4(1.),
+        color: sl::splat_vec4(1.),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    let conf = Config {
+        format: Format::RgbAlpha,
+        depth: true,
+        depth_format: Format::Depth16,
+        ..Default::default()
+    };
+
+    let layer = cx.make_layer(&shader, conf);
+    assert_eq!(layer.depth_format(), Some(Format::Depth16));
+
+    let size = const { (4, 4) };
+    let color = cx.make_texture(TextureData::empty(size, Format::RgbAlpha)?.with_draw());
+    let depth = cx.make_texture(TextureData::empty(size, Format::Depth16)?.with_draw());
+    let buffer = RenderBuffer::new(color, depth);
+    let opts = Options::from(Rgba::from_standard([0., 0., 0., 1.])).clear_depth(1.);
+    let draw = dunge::draw(|mut frame| {
+        frame.layer(&layer, opts).bind_empty().draw_points(3);
+    });
+
+    cx.draw_to(&buffer, draw);
+    Ok(())
+}
+
+#[test]
+fn layer_convert_texture() -> Result<(), Error> {
+    use dunge::{prelude::*, texture::Filter};
+
+    let cx = helpers::block_on(dunge::context())?;
+    let size = (64, 1);
+    let bgra: Vec<_> = (0..64u8).flat_map(|n| [n, n * 2, n * 3, 255]).collect();
+    let src = {
+        let data = TextureData::new(&bgra, size, Format::BgrAlpha)?.with_bind();
+        cx.make_texture(data)
+    };
+
+    let blit = cx.make_blit(Filter::Nearest, Format::RgbAlpha);
+    let dst = blit.convert(&cx, &src);
+    let buffer = cx.make_copy_buffer(size);
+    cx.draw_to(
+        &dst,
+        dunge::draw(|mut frame| frame.copy_texture(&buffer, &dst)),
+    );
+
+    let mapped = helpers::block_on({
+        let (tx, rx) = helpers::oneshot();
+        cx.map_view(buffer.view(), tx, rx)
+    });
+
+    for (n, &[r, g, b, a]) in (0..64u8).zip(mapped.data()) {
+        assert_eq!([b, g, r, a], [n, n * 2, n * 3, 255]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn layer_mesh_arena() -> Result<(), Error> {
+    use dunge::prelude::*;
+
+    #[repr(C)]
+    #[derive(Clone, Vertex)]
+    struct Vert([f32; 2]);
+
+    let cx = helpers::block_on(dunge::context())?;
+    let verts = vec![Vert([0., 0.]); 1000];
+    let data = MeshData::from_verts(&verts);
+    let mut arena = cx.make_mesh_arena();
+    for _ in 0..4 {
+        arena.alloc(&cx, &data);
+    }
+
+    assert_eq!(arena.allocated(), 4 * 8000);
+    arena.reset();
+    assert_eq!(arena.allocated(), 0);
+
+    let tri = MeshData::new(&verts[..3], &[[0, 1, 2]])?;
+    arena.alloc(&cx, &tri);
+    assert_eq!(arena.allocated(), 24 + 8, "indices are padded to 4 bytes");
+    Ok(())
+}
+
+#[test]
+fn layer_mesh_arena_draw() -> Result<(), Error> {
+    use dunge::{
+        color::Rgba,
+        layer::Config,
+        prelude::*,
+        sl::{self, InVertex, Out},
+    };
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Vertex)]
+    struct Vert([f32; 2], [f32; 3]);
+
+    let compute = |vert: InVertex<Vert>| Out {
+        place: sl::vec4_concat(vert.0, dunge::glam::Vec2::new(0., 1.)),
+        color: sl::vec4_with(sl::fragment(vert.1), 1.),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    let layer = {
+        let conf = Config {
+            format: Format::RgbAlpha,
+            ..Default::default()
+        };
+
+        cx.make_layer(&shader, conf)
+    };
+
+    let size = (4, 4);
+    let view = {
+        let data = TextureData::empty(size, Format::RgbAlpha)?
+            .with_draw()
+            .with_copy();
+
+        cx.make_texture(data)
+    };
+
+    let (red, green) = ([1., 0., 0.], [0., 1., 0.]);
+    let mut arena = cx.make_mesh_arena();
+
+    // Shift the next meshes away from the start of the buffer
+    let skip = [Vert([0., 0.], [0.; 3]); 3];
+    arena.alloc(&cx, &MeshData::new(&skip, &[[0, 1, 2]])?);
+
+    // The left half of the target as a plain mesh
+    let left = arena.alloc(
+        &cx,
+        &MeshData::from_verts(&[
+            Vert([-1., -1.], red),
+            Vert([0., -1.], red),
+            Vert([0., 1.], red),
+            Vert([-1., -1.], red),
+            Vert([0., 1.], red),
+            Vert([-1., 1.], red),
+        ]),
+    );
+
+    // The right half of the target as an indexed mesh
+    let right = arena.alloc(
+        &cx,
+        &MeshData::new(
+            &[
+                Vert([0., -1.], green),
+                Vert([1., -1.], green),
+                Vert([1., 1.], green),
+                Vert([0., 1.], green),
+            ],
+            &[[0, 1, 2], [0, 2, 3]],
+        )?,
+    );
+
+    let buffer = cx.make_copy_buffer(size);
+    let opts = Rgba::from_standard([0., 0., 0., 1.]);
+    let draw = dunge::draw(|mut frame| {
+        let mut layer = frame.layer(&layer, opts);
+        let mut set = layer.bind_empty();
+        set.draw(&left);
+        set.draw(&right);
+        frame.copy_texture(&buffer, &view);
+    });
+
+    cx.draw_to(&view, draw);
+    let mapped = helpers::block_on({
+        let (tx, rx) = helpers::oneshot();
+        cx.map_view(buffer.view(), tx, rx)
+    });
+
+    let (width, height) = size;
+    let (padded, _) = buffer.size();
+    for y in 0..height {
+        for x in 0..width {
+            let expected = if x < width / 2 {
+                [255, 0, 0, 255]
+            } else {
+                [0, 255, 0, 255]
+            };
+
+            assert_eq!(mapped.data()[(x + y * padded) as usize], expected);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn layer_canvas() -> Result<(), Error> {
+    use dunge::{color::Rgba, prelude::*};
+
+    let cx = helpers::block_on(dunge::context())?;
+    let size = (4, 4);
+
+    // The canvas keeps the previous content, so the texture is filled with black
+    let black = [[0, 0, 0, 255]; 16];
+    let view = {
+        let data = TextureData::new(black.as_flattened(), size, Format::RgbAlpha)?
+            .with_draw()
+            .with_copy();
+
+        cx.make_texture(data)
+    };
+
+    // The rect covers pixels from 1 to 2 by both axes
+    let mut canvas = cx.make_2d_layer(size, Format::RgbAlpha);
+    canvas.rect([1., 1., 2., 2.], Rgba::from_bytes([255, 0, 0, 255]));
+
+    let buffer = cx.make_copy_buffer(size);
+    let draw = dunge::draw(|mut frame| {
+        canvas.draw(&cx, &mut frame);
+        frame.copy_texture(&buffer, &view);
+    });
+
+    cx.draw_to(&view, draw);
+    let mapped = helpers::block_on({
+        let (tx, rx) = helpers::oneshot();
+        cx.map_view(buffer.view(), tx, rx)
+    });
+
+    let (width, height) = size;
+    let (padded, _) = buffer.size();
+    for y in 0..height {
+        for x in 0..width {
+            let inside = (1..3).contains(&x) && (1..3).contains(&y);
+            let expected = if inside {
+                [255, 0, 0, 255]
+            } else {
+                [0, 0, 0, 255]
+            };
+
+            assert_eq!(
+                mapped.data()[(x + y * padded) as usize],
+                expected,
+                "({x}, {y})"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn layer_occlusion_query() -> Result<(), Error> {
+    use dunge::{
+        color::Rgba,
+        layer::Config,
+        prelude::*,
+        sl::{self, InVertex, Out},
+        RenderBuffer,
+    };
+
+    #[repr(C)]
+    #[derive(Vertex)]
+    struct Vert([f32; 3]);
+
+    let compute = |vert: InVertex<Vert>| Out {
+        place: sl::vec4_with(vert.0, 1.),
+        color: sl::splat_vec4(1.),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    let conf = Config {
+        format: Format::RgbAlpha,
+        depth: true,
+        ..Default::default()
+    };
+
+    let layer = cx.make_layer(&shader, conf);
+    let size = (4, 4);
+    let color = cx.make_texture(TextureData::empty(size, Format::RgbAlpha)?.with_draw());
+    let depth = cx.make_texture(TextureData::empty(size, Format::Depth)?.with_draw());
+    let target = RenderBuffer::new(color, depth);
+
+    // Full-screen triangles at the given depth
+    let triangle = |z| {
+        let verts = [Vert([-1., -1., z]), Vert([3., -1., z]), Vert([-1., 3., z])];
+
+        cx.make_mesh(&MeshData::from_verts(&verts))
+    };
+
+    let occluder = triangle(0.5);
+    let behind = triangle(0.75);
+    let front = triangle(0.25);
+    let query = cx.make_occlusion_query(2);
+    let opts = Options::from(Rgba::from_standard([0., 0., 0., 1.])).clear_depth(1.);
+    let draw = dunge::draw(|mut frame| {
+        frame.set_occlusion_query(Some(&query));
+        let mut layer = frame.layer(&layer, opts);
+        layer.bind_empty().draw(&occluder);
+        layer.begin_occlusion_query(0);
+        layer.bind_empty().draw(&behind);
+        layer.end_occlusion_query();
+        layer.begin_occlusion_query(1);
+        layer.bind_empty().draw(&front);
+        layer.end_occlusion_query();
+        drop(layer);
+        frame.resolve_occlusion_query(&query);
+    });
+
+    cx.draw_to(&target, draw);
+    let samples = helpers::block_on({
+        let (tx, rx) = helpers::oneshot();
+        cx.read_occlusion_query(&query, tx, rx)
+    });
+
+    // Backends may count samples approximately, only a zero is exact
+    let [behind, front] = samples[..] else {
+        panic!("two queries are expected");
+    };
+
+    assert_eq!(behind, 0, "the triangle behind is occluded");
+    assert_ne!(front, 0, "the triangle in front is visible");
+    Ok(())
+}
+
+#[test]
+fn layer_dynamic_uniform() -> Result<(), Error> {
+    use dunge::{
+        color::Rgba,
+        layer::Config,
+        prelude::*,
+        sl::{self, Groups, Index, Out},
+        uniform::DynamicUniform,
+    };
+
+    #[derive(Group)]
+    struct Map<'a> {
+        col: &'a DynamicUniform<[f32; 4]>,
+    }
+
+    let compute = |idx: Index, Groups(map): Groups<Map>| Out {
+        place: sl::fullscreen_place(idx),
+        color: map.col,
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    let layer = {
+        let conf = Config {
+            format: Format::RgbAlpha,
+            ..Default::default()
+        };
+
+        cx.make_layer(&shader, conf)
+    };
+
+    let red = [1., 0., 0., 1.];
+    let green = [0., 1., 0., 1.];
+    let col = cx.make_dynamic_uniform(red, 2);
+    let bind = {
+        let mut binder = cx.make_binder(&shader);
+        binder.add(&Map { col: &col });
+        binder.into_binding()
+    };
+
+    let size = (1, 1);
+    let view = {
+        let data = TextureData::empty(size, Format::RgbAlpha)?
+            .with_draw()
+            .with_copy();
+
+        cx.make_texture(data)
+    };
+
+    // Draws with the current slot of the uniform
+    let draw = || {
+        let buffer = cx.make_copy_buffer(size);
+        let opts = Rgba::from_standard([0., 0., 0., 1.]);
+        let draw = dunge::draw(|mut frame| {
+            frame.layer(&layer, opts).bind(&bind).draw_points(3);
+            frame.copy_texture(&buffer, &view);
+        });
+
+        cx.draw_to(&view, draw);
+        let mapped = helpers::block_on({
+            let (tx, rx) = helpers::oneshot();
+            cx.map_view(buffer.view(), tx, rx)
+        });
+
+        mapped.data()[0]
+    };
+
+    assert_eq!(draw(), [255, 0, 0, 255], "the first slot");
+    col.update(&cx, green);
+    assert_eq!(draw(), [0, 255, 0, 255], "the second slot");
+    col.update(&cx, red);
+    assert_eq!(draw(), [255, 0, 0, 255], "the first slot again");
+    Ok(())
+}
+
+#[test]
+fn layer_updates() -> Result<(), Error> {
+    use dunge::{
+        color::Rgba,
+        layer::Config,
+        prelude::*,
+        sl::{self, Groups, Index, Out},
+        uniform::Uniform,
+    };
+
+    #[derive(Group)]
+    struct Map<'a> {
+        col: &'a Uniform<[f32; 4]>,
+    }
+
+    let compute = |idx: Index, Groups(map): Groups<Map>| Out {
+        place: sl::fullscreen_place(idx),
+        color: map.col,
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    let layer = {
+        let conf = Config {
+            format: Format::RgbAlpha,
+            ..Default::default()
+        };
+
+        cx.make_layer(&shader, conf)
+    };
+
+    let a = cx.make_uniform([0.; 4]);
+    let b = cx.make_uniform([0.; 4]);
+    let bind = |col| {
+        let mut binder = cx.make_binder(&shader);
+        binder.add(&Map { col });
+        binder.into_binding()
+    };
+
+    let (bind_a, bind_b) = (bind(&a), bind(&b));
+    let size = (1, 1);
+    let view = {
+        let data = TextureData::empty(size, Format::RgbAlpha)?
+            .with_draw()
+            .with_copy();
+
+        cx.make_texture(data)
+    };
+
+    let read = |bind| {
+        let buffer = cx.make_copy_buffer(size);
+        let opts = Rgba::from_standard([0., 0., 0., 1.]);
+        let draw = dunge::draw(|mut frame| {
+            frame.layer(&layer, opts).bind(bind).draw_points(3);
+            frame.copy_texture(&buffer, &view);
+        });
+
+        cx.draw_to(&view, draw);
+        let mapped = helpers::block_on({
+            let (tx, rx) = helpers::oneshot();
+            cx.map_view(buffer.view(), tx, rx)
+        });
+
+        mapped.data()[0]
+    };
+
+    // The scopes are used in turn, the second one is nested
+    // while the first one holds the shared staging memory
+    for (col, expected) in [
+        ([1., 0., 0., 1.], [255, 0, 0, 255]),
+        ([0., 1., 0., 1.], [0, 255, 0, 255]),
+    ] {
+        let mut outer = cx.begin_updates();
+        outer.update(&a, col);
+        let mut inner = cx.begin_updates();
+        inner.update(&b, col);
+        inner.submit();
+        outer.submit();
+        assert_eq!(read(&bind_a), expected);
+        assert_eq!(read(&bind_b), expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn layer_depth_dont_care() -> Result<(), Error> {
+    use dunge::{
+        color::Rgba,
+        layer::Config,
+        prelude::*,
+        sl::{self, Index, Out},
+        Load, RenderBuffer,
+    };
+
+    // A full-screen triangle in the middle of the depth range
+    let compute = |Index(index): Index| {
+        let index = sl::thunk(index);
+        Out {
+            place: sl::vec4(
+                sl::f32(index.clone() % 2) * 4. - 1.,
+                sl::f32(index / 2) * 4. - 1.,
+                0.5,
+                1.,
+            ),
+            color: sl::splat_vec4(1.),
+        }
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    let conf = Config {
+        format: Format::RgbAlpha,
+        depth: true,
+        ..Default::default()
+    };
+
+    let layer = cx.make_layer(&shader, conf);
+    let size = (4, 4);
+    let color = {
+        let data = TextureData::empty(size, Format::RgbAlpha)?
+            .with_draw()
+            .with_copy();
+
+        cx.make_texture(data)
+    };
+
+    let depth = cx.make_texture(TextureData::empty(size, Format::Depth)?.with_draw());
+    let target = RenderBuffer::new(color, depth);
+    let buffer = cx.make_copy_buffer(size);
+    let opts = Options::from(Rgba::from_standard([0., 0., 0., 1.])).depth(Load::DontCare);
+    let draw = dunge::draw(|mut frame| {
+        frame.layer(&layer, opts).bind_empty().draw_points(3);
+        frame.copy_texture(&buffer, target.color());
+    });
+
+    cx.draw_to(&target, draw);
+    let mapped = helpers::block_on({
+        let (tx, rx) = helpers::oneshot();
+        cx.map_view(buffer.view(), tx, rx)
+    });
+
+    assert_eq!(
+        mapped.data()[0],
+        [255; 4],
+        "the triangle should pass the depth test"
+    );
+    Ok(())
+}
+
+#[test]
+fn layer_draw_indirect() -> Result<(), Error> {
+    use dunge::{
+        color::Rgba,
+        indirect::DrawIndirectArgs,
+        prelude::*,
+        sl::{self, Index, Out},
+        wgpu,
+    };
+
+    let compute = |idx: Index| Out {
+        place: sl::fullscreen_place(idx),
+        color: sl::splat_vec4(1.),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    let layer = cx.make_layer(&shader, Format::RgbAlpha);
+    let size = (4, 4);
+    let view = {
+        let data = TextureData::empty(size, Format::RgbAlpha)?
+            .with_draw()
+            .with_copy();
+
+        cx.make_texture(data)
+    };
+
+    let buffer = cx.make_copy_buffer(size);
+    let read = |args: &_| {
+        let draw = dunge::draw(|mut frame| {
+            frame
+                .layer(&layer, Rgba::from_standard([0., 0., 0., 1.]))
+                .bind_empty()
+                .draw_indirect(args);
+
+            frame.copy_texture(&buffer, &view);
+        });
+
+        cx.draw_to(&view, draw);
+        let mapped = helpers::block_on({
+            let (tx, rx) = helpers::oneshot();
+            cx.map_view(buffer.view(), tx, rx)
+        });
+
+        mapped.data()[0]
+    };
+
+    let args = DrawIndirectArgs {
+        vertex_count: 3,
+        instance_count: 1,
+        ..Default::default()
+    };
+
+    let args = cx.make_indirect_buffer(&[args]);
+    assert_eq!(read(&args), [255; 4]);
+
+    // Fill the arguments from a compute pass
+    args.update(&cx, &[DrawIndirectArgs::default()]);
+    assert_eq!(read(&args), [0, 0, 0, 255]);
+
+    let device = cx.device();
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(
+            "@group(0) @binding(0) var<storage, read_write> args: array<u32>;
+            @compute @workgroup_size(1)
+            fn main() { args[0] = 3u; args[1] = 1u; }"
+                .into(),
+        ),
+    });
+
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: None,
+        layout: None,
+        module: &module,
+        entry_point: "main",
+        compilation_options: wgpu::PipelineCompilationOptions::default(),
+        cache: None,
+    });
+
+    let bind = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: args.buffer().as_entire_binding(),
+        }],
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    {
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, &bind, &[]);
+        pass.dispatch_workgroups(1, 1, 1);
+    }
+
+    cx.queue().submit([encoder.finish()]);
+    assert_eq!(read(&args), [255; 4]);
+    Ok(())
+}
+
+#[test]
+fn layer_update_range() -> Result<(), Error> {
+    use dunge::{
+        color::Rgba,
+        instance::Row,
+        layer::{Config, Topology},
+        prelude::*,
+        sl::{self, InInstance, Index, Out},
+        Instance,
+    };
+
+    #[derive(Instance)]
+    struct Cols {
+        pos: Row<[f32; 2]>,
+        col: Row<[f32; 4]>,
+    }
+
+    // Each instance covers a full height column of the target
+    let compute = |cols: InInstance<Cols>, Index(idx): Index| {
+        let pos = sl::thunk(cols.pos);
+        let x = pos.clone().x() + sl::f32(idx / 2) * pos.y();
+        let y = sl::f32(idx % 2) * 2. - 1.;
+        Out {
+            place: sl::vec4(x, y, 0., 1.),
+            color: sl::fragment(cols.col),
+        }
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    let layer = {
+        let conf = Config {
+            format: Format::RgbAlpha,
+            topology: Topology::TriangleStrip,
+            ..Default::default()
+        };
+
+        cx.make_layer(&shader, conf)
+    };
+
+    let red = [1., 0., 0., 1.];
+    let green = [0., 1., 0., 1.];
+    let cols = Cols {
+        pos: cx.make_row(&[[-1., 0.5], [-0.5, 0.5], [0., 0.5], [0.5, 0.5]]),
+        col: cx.make_row(&[red; 4]),
+    };
+
+    assert!(cols.col.update_range(&cx, 3, &[green; 2]).is_err());
+    assert!(cols.col.update_range(&cx, 5, &[]).is_err());
+    assert!(cols.col.update_range(&cx, u32::MAX, &[green]).is_err());
+    assert!(cols.col.update_range(&cx, 4, &[]).is_ok());
+
+    let size = (4, 1);
+    let view = {
+        let data = TextureData::empty(size, Format::RgbAlpha)?
+            .with_draw()
+            .with_copy();
+
+        cx.make_texture(data)
+    };
+
+    let draw = || {
+        let buffer = cx.make_copy_buffer(size);
+        let opts = Rgba::from_standard([0., 0., 0., 1.]);
+        let draw = dunge::draw(|mut frame| {
+            frame
+                .layer(&layer, opts)
+                .bind_empty()
+                .instance(&cols)
+                .draw_points(4);
+
+            frame.copy_texture(&buffer, &view);
+        });
+
+        cx.draw_to(&view, draw);
+        let mapped = helpers::block_on({
+            let (tx, rx) = helpers::oneshot();
+            cx.map_view(buffer.view(), tx, rx)
+        });
+
+        mapped.data()[..4].to_vec()
+    };
+
+    let r = [255, 0, 0, 255];
+    let g = [0, 255, 0, 255];
+    assert_eq!(draw(), [r; 4], "the failed updates change nothing");
+    cols.col.update_range(&cx, 1, &[green; 2])?;
+    assert_eq!(draw(), [r, g, g, r], "only the middle instances change");
+    Ok(())
+}
+
+#[test]
+fn layer_depth_write() -> Result<(), Error> {
+    use dunge::{
+        color::Rgba,
+        layer::Config,
+        prelude::*,
+        sl::{self, Index, Out},
+        RenderBuffer,
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+
+    // Makes a layer drawing a full-screen triangle of the color at the depth
+    let make = |depth: f32, [r, g, b]: [f32; 3], depth_write: bool| {
+        let compute = move |Index(index): Index| {
+            let index = sl::thunk(index);
+            Out {
+                place: sl::vec4(
+                    sl::f32(index.clone() % 2) * 4. - 1.,
+                    sl::f32(index / 2) * 4. - 1.,
+                    depth,
+                    1.,
+                ),
+                color: sl::vec4(r, g, b, 1.),
+            }
+        };
+
+        let shader = cx.make_shader(compute);
+        let conf = Config {
+            format: Format::RgbAlpha,
+            depth: true,
+            depth_write,
+            ..Default::default()
+        };
+
+        cx.make_layer(&shader, conf)
+    };
+
+    let back = make(0.9, [0., 0., 1.], true);
+    let front = make(0.5, [1., 0., 0.], false);
+    let middle = make(0.7, [0., 1., 0.], true);
+    let size = (4, 4);
+    let color = {
+        let data = TextureData::empty(size, Format::RgbAlpha)?
+            .with_draw()
+            .with_copy();
+
+        cx.make_texture(data)
+    };
+
+    let depth = cx.make_texture(TextureData::empty(size, Format::Depth)?.with_draw());
+    let target = RenderBuffer::new(color, depth);
+    let buffer = cx.make_copy_buffer(size);
+    let opts = Options::from(Rgba::from_standard([0., 0., 0., 1.])).clear_depth(1.);
+    let draw = dunge::draw(|mut frame| {
+        frame.layer(&back, opts).bind_empty().draw_points(3);
+        frame
+            .layer(&front, Options::default())
+            .bind_empty()
+            .draw_points(3);
+
+        frame
+            .layer(&middle, Options::default())
+            .bind_empty()
+            .draw_points(3);
+
+        frame.copy_texture(&buffer, target.color());
+    });
+
+    cx.draw_to(&target, draw);
+    let mapped = helpers::block_on({
+        let (tx, rx) = helpers::oneshot();
+        cx.map_view(buffer.view(), tx, rx)
+    });
+
+    assert_eq!(
+        mapped.data()[0],
+        [0, 255, 0, 255],
+        "the front triangle shouldn't write its depth to occlude the middle one",
+    );
+    Ok(())
+}
+
+#[test]
+fn layer_write_mask() -> Result<(), Error> {
+    use dunge::{
+        color::Rgba,
+        layer::Config,
+        prelude::*,
+        sl::{self, Index, Out},
+        wgpu,
+    };
+
+    let compute = |idx: Index| Out {
+        place: sl::fullscreen_place(idx),
+        color: sl::vec4(1., 1., 1., 0.),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    let layer = {
+        let conf = Config {
+            format: Format::RgbAlpha,
+            write_mask: wgpu::ColorWrites::RED | wgpu::ColorWrites::GREEN,
+            ..Default::default()
+        };
+
+        cx.make_layer(&shader, conf)
+    };
+
+    let size = (1, 1);
+    let view = {
+        let data = TextureData::empty(size, Format::RgbAlpha)?
+            .with_draw()
+            .with_copy();
+
+        cx.make_texture(data)
+    };
+
+    let buffer = cx.make_copy_buffer(size);
+    let opts = Rgba::from_standard([0., 0., 0., 1.]);
+    let draw = dunge::draw(|mut frame| {
+        frame.layer(&layer, opts).bind_empty().draw_points(3);
+        frame.copy_texture(&buffer, &view);
+    });
+
+    cx.draw_to(&view, draw);
+    let mapped = helpers::block_on({
+        let (tx, rx) = helpers::oneshot();
+        cx.map_view(buffer.view(), tx, rx)
+    });
+
+    assert_eq!(
+        mapped.data()[0],
+        [255, 255, 0, 255],
+        "blue and alpha channels should keep the clear color",
+    );
+    Ok(())
+}
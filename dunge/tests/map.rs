@@ -0,0 +1,190 @@
+#![cfg(not(target_family = "wasm"))]
+
+type Error = Box<dyn std::error::Error>;
+
+#[test]
+fn map_many() -> Result<(), Error> {
+    use dunge::{
+        color::Rgba,
+        layer::Config,
+        prelude::*,
+        sl::{self, Index, Out},
+    };
+
+    use std::{
+        future::{self, Future},
+        iter,
+        task::Poll,
+    };
+
+    let compute = |Index(_): Index| Out {
+        place: sl::splat_vec4(0.),
+        color: sl::splat_vec4(0.),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    let layer = {
+        let conf = Config {
+            format: Format::RgbAlpha,
+            ..Default::default()
+        };
+
+        cx.make_layer(&shader, conf)
+    };
+
+    let size = (1, 1);
+    let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+    let buffers = colors.map(|col| {
+        let view = {
+            let data = TextureData::empty(size, Format::RgbAlpha)
+                .expect("non-zero size")
+                .with_draw()
+                .with_copy();
+
+            cx.make_texture(data)
+        };
+
+        let buffer = cx.make_copy_buffer(size);
+        let draw = dunge::draw(|mut frame| {
+            // Starting the layer clears the target
+            frame.layer(&layer, Rgba::from_bytes(col));
+            frame.copy_texture(&buffer, &view);
+        });
+
+        cx.draw_to(&view, draw);
+        buffer
+    });
+
+    // All mappings are requested together and alive at the same time
+    let mapped = helpers::block_on(async {
+        let mut futures = buffers.each_ref().map(|buffer| {
+            let (tx, rx) = helpers::oneshot();
+            Box::pin(cx.map_view(buffer.view(), tx, rx))
+        });
+
+        let mut mapped = [(); 3].map(|_| None);
+        future::poll_fn(|task| {
+            let mut ready = true;
+            for (fut, mapped) in iter::zip(&mut futures, &mut mapped) {
+                if mapped.is_none() {
+                    match fut.as_mut().poll(task) {
+                        Poll::Ready(view) => *mapped = Some(view),
+                        Poll::Pending => ready = false,
+                    }
+                }
+            }
+
+            if ready {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+
+        mapped.map(|view| view.expect("the mapping is ready"))
+    });
+
+    for (mapped, col) in mapped.iter().zip(colors) {
+        assert_eq!(mapped.data()[0], col);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn map_rows() -> Result<(), Error> {
+    use dunge::prelude::*;
+
+    let cx = helpers::block_on(dunge::context())?;
+    let size = (1, 3);
+    let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+    let view = {
+        let data = TextureData::new(colors.as_flattened(), size, Format::RgbAlpha)?
+            .with_draw()
+            .with_copy();
+
+        cx.make_texture(data)
+    };
+
+    let buffer = cx.make_copy_buffer(size);
+    cx.draw_to(
+        &view,
+        dunge::draw(|mut frame| frame.copy_texture(&buffer, &view)),
+    );
+
+    // Each range is mapped after the previous one is unmapped
+    for (rows, col) in [(0..1, colors[0]), (1..3, colors[1])] {
+        let mapped = helpers::block_on({
+            let (tx, rx) = helpers::oneshot();
+            cx.map_view(buffer.rows(rows), tx, rx)
+        });
+
+        assert_eq!(mapped.data()[0], col);
+    }
+
+    let (width, _) = buffer.size();
+    let mut rows = vec![];
+    for chunk in buffer.chunks(2) {
+        let mapped = helpers::block_on({
+            let (tx, rx) = helpers::oneshot();
+            cx.map_view(chunk, tx, rx)
+        });
+
+        rows.extend(mapped.data().chunks(width as usize).map(|row| row[0]));
+    }
+
+    assert_eq!(rows, colors);
+    Ok(())
+}
+
+#[test]
+fn map_texture_async() -> Result<(), Error> {
+    use dunge::prelude::*;
+
+    let cx = helpers::block_on(dunge::context())?;
+    let size = (3, 2);
+    let colors = [
+        [255, 0, 0, 255],
+        [0, 255, 0, 255],
+        [0, 0, 255, 255],
+        [255, 255, 0, 255],
+        [0, 255, 255, 255],
+        [255, 0, 255, 255],
+    ];
+
+    // The width isn't aligned, so the staging rows are padded
+    let view = helpers::block_on({
+        let data = TextureData::new(colors.as_flattened(), size, Format::RgbAlpha)?
+            .with_draw()
+            .with_copy();
+
+        let (tx, rx) = helpers::oneshot();
+        cx.make_texture_async(data, tx, rx)
+    });
+
+    let buffer = cx.make_copy_buffer(size);
+    cx.draw_to(
+        &view,
+        dunge::draw(|mut frame| frame.copy_texture(&buffer, &view)),
+    );
+
+    let mapped = helpers::block_on({
+        let (tx, rx) = helpers::oneshot();
+        cx.map_view(buffer.view(), tx, rx)
+    });
+
+    let (width, height) = size;
+    let (padded, _) = buffer.size();
+    let data: Vec<_> = mapped
+        .data()
+        .chunks(padded as usize)
+        .take(height as usize)
+        .flat_map(|row| &row[..width as usize])
+        .copied()
+        .collect();
+
+    assert_eq!(data, colors);
+    Ok(())
+}
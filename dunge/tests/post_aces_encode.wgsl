@@ -0,0 +1,29 @@
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+    @location(0) member_1: vec2<f32>,
+}
+
+@group(0) @binding(0) 
+var global: texture_2d<f32>;
+@group(0) @binding(1) 
+var global_1: sampler;
+@group(0) @binding(2) 
+var<uniform> global_2: f32;
+
+@vertex 
+fn vs(@builtin(vertex_index) param: u32) -> VertexOutput {
+    return VertexOutput(vec4<f32>(((f32((param % 2u)) * 4f) - 1f), ((f32((param / 2u)) * 4f) - 1f), 0f, 1f), vec2<f32>((f32((param % 2u)) * 2f), (1f - (f32((param / 2u)) * 2f))));
+}
+
+@fragment 
+fn fs(param_1: VertexOutput) -> @location(0) vec4<f32> {
+    let _e4: vec4<f32> = textureSample(global, global_1, param_1.member_1);
+    let _e7: f32 = global_2;
+    let _e8: f32 = (_e4.x * _e7);
+    let _e25: f32 = max(clamp(((_e8 * ((_e8 * 2.51f) + 0.03f)) / ((_e8 * ((_e8 * 2.43f) + 0.59f)) + 0.14f)), 0f, 1f), 0f);
+    let _e38: f32 = (_e4.y * _e7);
+    let _e48: f32 = max(clamp(((_e38 * ((_e38 * 2.51f) + 0.03f)) / ((_e38 * ((_e38 * 2.43f) + 0.59f)) + 0.14f)), 0f, 1f), 0f);
+    let _e56: f32 = (_e4.z * _e7);
+    let _e66: f32 = max(clamp(((_e56 * ((_e56 * 2.51f) + 0.03f)) / ((_e56 * ((_e56 * 2.43f) + 0.59f)) + 0.14f)), 0f, 1f), 0f);
+    return vec4<f32>(vec3<f32>(mix((_e25 * 12.92f), ((pow(_e25, 0.41666666f) * 1.055f) - 0.055f), step(0.0031308f, _e25)), mix((_e48 * 12.92f), ((pow(_e48, 0.41666666f) * 1.055f) - 0.055f), step(0.0031308f, _e48)), mix((_e66 * 12.92f), ((pow(_e66, 0.41666666f) * 1.055f) - 0.055f), step(0.0031308f, _e66))), _e4.w);
+}
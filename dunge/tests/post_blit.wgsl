@@ -0,0 +1,23 @@
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+    @location(0) member_1: vec2<f32>,
+}
+
+@group(0) @binding(0) 
+var global: texture_2d<f32>;
+@group(0) @binding(1) 
+var global_1: sampler;
+@group(0) @binding(2) 
+var<uniform> global_2: vec4<f32>;
+
+@vertex 
+fn vs(@builtin(vertex_index) param: u32) -> VertexOutput {
+    let _e1: vec4<f32> = global_2;
+    return VertexOutput(vec4<f32>((((_e1.x + (f32((param / 2u)) * _e1.z)) * 2f) - 1f), (1f - ((_e1.y + (f32((param % 2u)) * _e1.w)) * 2f)), 0f, 1f), vec2<f32>(f32((param / 2u)), f32((param % 2u))));
+}
+
+@fragment 
+fn fs(param_1: VertexOutput) -> @location(0) vec4<f32> {
+    let _e4: vec4<f32> = textureSample(global, global_1, param_1.member_1);
+    return _e4;
+}
@@ -0,0 +1,33 @@
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+    @location(0) member_1: vec2<f32>,
+}
+
+@group(0) @binding(0) 
+var global: texture_2d<f32>;
+@group(0) @binding(1) 
+var global_1: sampler;
+@group(0) @binding(2) 
+var<uniform> global_2: vec2<f32>;
+@group(0) @binding(3) 
+var<uniform> global_3: f32;
+
+@vertex 
+fn vs(@builtin(vertex_index) param: u32) -> VertexOutput {
+    return VertexOutput(vec4<f32>(((f32((param % 2u)) * 4f) - 1f), ((f32((param / 2u)) * 4f) - 1f), 0f, 1f), vec2<f32>((f32((param % 2u)) * 2f), (1f - (f32((param / 2u)) * 2f))));
+}
+
+@fragment 
+fn fs(param_1: VertexOutput) -> @location(0) vec4<f32> {
+    let _e4: vec4<f32> = textureSample(global, global_1, param_1.member_1);
+    let _e8: vec2<f32> = global_2;
+    let _e10: f32 = global_3;
+    let _e11: vec2<f32> = (_e8 * _e10);
+    let _e13: vec2<f32> = (_e11 * 1.3846154f);
+    let _e15: vec4<f32> = textureSample(global, global_1, (param_1.member_1 + _e13));
+    let _e20: vec4<f32> = textureSample(global, global_1, (param_1.member_1 - _e13));
+    let _e24: vec2<f32> = (_e11 * 3.2307692f);
+    let _e26: vec4<f32> = textureSample(global, global_1, (param_1.member_1 + _e24));
+    let _e31: vec4<f32> = textureSample(global, global_1, (param_1.member_1 - _e24));
+    return (((((_e4 * 0.227027f) + (_e15 * 0.3162162f)) + (_e20 * 0.3162162f)) + (_e26 * 0.07027027f)) + (_e31 * 0.07027027f));
+}
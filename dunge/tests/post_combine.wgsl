@@ -0,0 +1,26 @@
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+    @location(0) member_1: vec2<f32>,
+}
+
+@group(0) @binding(0) 
+var global: texture_2d<f32>;
+@group(0) @binding(1) 
+var global_1: texture_2d<f32>;
+@group(0) @binding(2) 
+var global_2: sampler;
+@group(0) @binding(3) 
+var<uniform> global_3: vec2<f32>;
+
+@vertex 
+fn vs(@builtin(vertex_index) param: u32) -> VertexOutput {
+    return VertexOutput(vec4<f32>(((f32((param % 2u)) * 4f) - 1f), ((f32((param / 2u)) * 4f) - 1f), 0f, 1f), vec2<f32>((f32((param % 2u)) * 2f), (1f - (f32((param / 2u)) * 2f))));
+}
+
+@fragment 
+fn fs(param_1: VertexOutput) -> @location(0) vec4<f32> {
+    let _e4: vec4<f32> = textureSample(global, global_2, param_1.member_1);
+    let _e6: vec4<f32> = textureSample(global_1, global_2, param_1.member_1);
+    let _e8: vec2<f32> = global_3;
+    return (_e4 + (_e6 * _e8.y));
+}
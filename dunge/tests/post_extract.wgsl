@@ -0,0 +1,24 @@
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+    @location(0) member_1: vec2<f32>,
+}
+
+@group(0) @binding(0) 
+var global: texture_2d<f32>;
+@group(0) @binding(1) 
+var global_1: sampler;
+@group(0) @binding(2) 
+var<uniform> global_2: vec2<f32>;
+
+@vertex 
+fn vs(@builtin(vertex_index) param: u32) -> VertexOutput {
+    return VertexOutput(vec4<f32>(((f32((param % 2u)) * 4f) - 1f), ((f32((param / 2u)) * 4f) - 1f), 0f, 1f), vec2<f32>((f32((param % 2u)) * 2f), (1f - (f32((param / 2u)) * 2f))));
+}
+
+@fragment 
+fn fs(param_1: VertexOutput) -> @location(0) vec4<f32> {
+    let _e4: vec4<f32> = textureSample(global, global_1, param_1.member_1);
+    let _e18: vec2<f32> = global_2;
+    let _e23: f32 = clamp(((((_e4.x * 0.2126f) + (_e4.y * 0.7152f)) + (_e4.z * 0.0722f)) - _e18.x), 0f, 1f);
+    return vec4<f32>(vec3<f32>((_e4.x * _e23), (_e4.y * _e23), (_e4.z * _e23)), 0f);
+}
@@ -27,6 +27,31 @@ fn shader_calc() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn shader_math() -> Result<(), Error> {
+    use dunge::{
+        glam::Vec3,
+        sl::{self, Out},
+    };
+
+    let compute = || {
+        let v = Vec3::new(1., 2., 3.);
+        let n = sl::normalize(v);
+        let a = sl::mix(sl::exp(1.), sl::log(2.), sl::fract(0.5));
+        let b = sl::max(sl::min(sl::sign(-1.), sl::step(0.5, 1.)), sl::length(v));
+        let c = sl::reflect(v, n) + sl::refract(v, sl::normalize(v), 0.5);
+        Out {
+            place: sl::vec4_with(c, a + b),
+            color: sl::splat_vec4(sl::clamp(1., 0., 2.)),
+        }
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    helpers::eq_lines(shader.debug_wgsl(), include_str!("shader_math.wgsl"));
+    Ok(())
+}
+
 #[test]
 fn shader_if() -> Result<(), Error> {
     use dunge::{
@@ -137,6 +162,359 @@ fn shader_zero() -> Result<(), Error> {
     Ok(())
 }
 
+#[test]
+fn shader_texture_load() -> Result<(), Error> {
+    use dunge::{
+        group::BoundTexture,
+        prelude::*,
+        sl::{self, Groups, Out},
+    };
+
+    #[derive(Group)]
+    struct Map<'a> {
+        tex: BoundTexture<'a>,
+    }
+
+    let compute = |Groups(map): Groups<Map>| Out {
+        place: sl::splat_vec4(1.),
+        color: sl::texture_load(map.tex, sl::vec2(0u32, 1u32), 0u32),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    helpers::eq_lines(
+        shader.debug_wgsl(),
+        include_str!("shader_texture_load.wgsl"),
+    );
+    Ok(())
+}
+
+#[test]
+fn shader_texture_sample_level() -> Result<(), Error> {
+    use dunge::{
+        group::BoundTexture,
+        prelude::*,
+        sl::{self, Groups, Out},
+        texture::Sampler,
+    };
+
+    #[derive(Group)]
+    struct Map<'a> {
+        tex: BoundTexture<'a>,
+        sam: &'a Sampler,
+    }
+
+    let compute = |Groups(map): Groups<Map>| Out {
+        place: sl::texture_sample_level(map.tex.clone(), map.sam.clone(), sl::splat_vec2(0.), 1.),
+        color: sl::texture_sample_bias(map.tex, map.sam, sl::splat_vec2(1.), -0.5),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    helpers::eq_lines(
+        shader.debug_wgsl(),
+        include_str!("shader_texture_sample_level.wgsl"),
+    );
+    Ok(())
+}
+
+#[test]
+fn shader_fullscreen() -> Result<(), Error> {
+    use dunge::{
+        glam::Vec2,
+        sl::{self, Index, Out},
+    };
+
+    let compute = |idx: Index| Out {
+        place: sl::fullscreen_place(idx),
+        color: sl::vec4_concat(sl::fragment(sl::fullscreen_uv(idx)), Vec2::new(0., 1.)),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    helpers::eq_lines(shader.debug_wgsl(), include_str!("shader_fullscreen.wgsl"));
+    Ok(())
+}
+
+#[test]
+fn shader_sdf() -> Result<(), Error> {
+    use dunge::{
+        group::BoundTexture,
+        prelude::*,
+        sl::{self, Groups, Out},
+        texture::Sampler,
+    };
+
+    #[derive(Group)]
+    struct Atlas<'a> {
+        tex: BoundTexture<'a>,
+        sam: &'a Sampler,
+    }
+
+    let compute = |Groups(atlas): Groups<Atlas>| {
+        let dist = sl::texture_sample(atlas.tex, atlas.sam, sl::splat_vec2(0.5)).w();
+        Out {
+            place: sl::splat_vec4(1.),
+            color: sl::vec4_with(sl::splat_vec3(1.), sl::sdf_alpha(dist, 0.1)),
+        }
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    helpers::eq_lines(shader.debug_wgsl(), include_str!("shader_sdf.wgsl"));
+    Ok(())
+}
+
+#[test]
+fn shader_normal() -> Result<(), Error> {
+    use dunge::{
+        prelude::*,
+        sl::{self, Groups, InVertex, Out},
+        uniform::Uniform,
+    };
+
+    #[repr(C)]
+    #[derive(Vertex)]
+    struct Vert {
+        pos: [f32; 3],
+        nrm: [f32; 3],
+    }
+
+    #[derive(Group)]
+    struct Model<'a>(&'a Uniform<[[f32; 4]; 4]>);
+
+    let compute = |vert: InVertex<Vert>, Groups(m): Groups<Model>| {
+        let normal = sl::normalize(sl::normal_matrix(m.0.clone()) * vert.nrm);
+        let light = sl::clamp(sl::dot(sl::fragment(normal), sl::vec3(0., 1., 0.)), 0., 1.);
+        Out {
+            place: m.0 * sl::vec4_with(vert.pos, 1.),
+            color: sl::vec4_with(sl::splat_vec3(light), 1.),
+        }
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    helpers::eq_lines(shader.debug_wgsl(), include_str!("shader_normal.wgsl"));
+    Ok(())
+}
+
+#[test]
+fn shader_flat() -> Result<(), Error> {
+    use dunge::{
+        prelude::*,
+        sl::{self, InVertex, Out},
+    };
+
+    #[repr(C)]
+    #[derive(Vertex)]
+    struct Vert {
+        pos: [f32; 2],
+        col: [f32; 3],
+    }
+
+    let compute = |vert: InVertex<Vert>| Out {
+        place: sl::vec4_concat(vert.pos, sl::vec2(0., 1.)),
+        color: sl::vec4_with(sl::flat(vert.col), 1.),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    helpers::eq_lines(shader.debug_wgsl(), include_str!("shader_flat.wgsl"));
+    Ok(())
+}
+
+#[test]
+fn shader_derivative() -> Result<(), Error> {
+    use dunge::{
+        prelude::*,
+        sl::{self, InVertex, Out},
+    };
+
+    #[repr(C)]
+    #[derive(Vertex)]
+    struct Vert([f32; 2]);
+
+    let compute = |vert: InVertex<Vert>| {
+        let uv = sl::thunk(sl::fragment(vert.0));
+        Out {
+            place: sl::vec4_concat(vert.0, sl::vec2(0., 1.)),
+            color: sl::vec4_concat(sl::fwidth(uv.clone()), sl::dpdx(uv.clone()) + sl::dpdy(uv)),
+        }
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    helpers::eq_lines(shader.debug_wgsl(), include_str!("shader_derivative.wgsl"));
+    Ok(())
+}
+
+#[test]
+fn shader_inverse() -> Result<(), Error> {
+    use dunge::{
+        prelude::*,
+        sl::{self, Groups, Out},
+        uniform::Uniform,
+    };
+
+    #[derive(Group)]
+    struct Map<'a> {
+        m2: &'a Uniform<[[f32; 2]; 2]>,
+        m4: &'a Uniform<[[f32; 4]; 4]>,
+    }
+
+    let compute = |Groups(map): Groups<Map>| Out {
+        place: sl::inverse(map.m4) * sl::splat_vec4(1.),
+        color: sl::vec4_concat(
+            sl::transpose(map.m2.clone()) * sl::splat_vec2(1.),
+            sl::inverse(map.m2) * sl::splat_vec2(1.),
+        ),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    helpers::eq_lines(shader.debug_wgsl(), include_str!("shader_inverse.wgsl"));
+    Ok(())
+}
+
+#[test]
+fn shader_swizzle() -> Result<(), Error> {
+    use dunge::{
+        prelude::*,
+        sl::{self, InVertex, Out},
+    };
+
+    #[repr(C)]
+    #[derive(Vertex)]
+    struct Vert([f32; 4]);
+
+    let compute = |vert: InVertex<Vert>| Out {
+        place: sl::vec4_with(vert.0.xyz(), 1.),
+        color: sl::vec4_concat(sl::fragment(vert.0.xz()), sl::fragment(vert.0.yzw().xy())),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    helpers::eq_lines(shader.debug_wgsl(), include_str!("shader_swizzle.wgsl"));
+    Ok(())
+}
+
+#[test]
+fn shader_vector_parts() -> Result<(), Error> {
+    use dunge::{
+        prelude::*,
+        sl::{self, InVertex, Out},
+    };
+
+    #[repr(C)]
+    #[derive(Vertex)]
+    struct Vert([f32; 4]);
+
+    let compute = |vert: InVertex<Vert>| Out {
+        place: sl::vec4_parts((0., vert.0.xy(), 1.)),
+        color: sl::vec4_parts((sl::vec3_parts((sl::fragment(vert.0.zw()), 0.5)), 1.)),
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    helpers::eq_lines(
+        shader.debug_wgsl(),
+        include_str!("shader_vector_parts.wgsl"),
+    );
+    Ok(())
+}
+
+#[test]
+fn shader_glam_parity() {
+    use dunge::{
+        glam::{Vec2, Vec3},
+        sl::{self, Groups, InVertex, IntoModule, Out},
+        uniform::Uniform,
+        Group, Vertex,
+    };
+
+    #[repr(C)]
+    #[derive(Vertex)]
+    struct Plain {
+        pos: [f32; 2],
+        col: [f32; 3],
+    }
+
+    #[repr(C)]
+    #[derive(Vertex)]
+    struct Glam {
+        pos: Vec2,
+        col: Vec3,
+    }
+
+    #[derive(Group)]
+    struct Tint<'a> {
+        col: &'a Uniform<[f32; 3]>,
+    }
+
+    let plain = |vert: InVertex<Plain>, Groups(tint): Groups<Tint>| Out {
+        place: sl::vec4_concat(vert.pos, Vec2::new(0., 1.)),
+        color: sl::vec4_with(sl::fragment(vert.col) + tint.col, 1.),
+    };
+
+    let glam = |vert: InVertex<Glam>, Groups(tint): Groups<Tint>| Out {
+        place: sl::vec4_concat(vert.pos, Vec2::new(0., 1.)),
+        color: sl::vec4_with(sl::fragment(vert.col) + tint.col, 1.),
+    };
+
+    assert_eq!(size_of::<Plain>(), size_of::<Glam>());
+    assert_eq!(plain.into_module().wgsl, glam.into_module().wgsl);
+}
+
+#[test]
+fn shader_literal_dedup() {
+    use dunge::{
+        sl::{self, IntoModule, Out},
+        wgpu::naga::Expression,
+    };
+
+    // The vertex shader uses the `1.0` literal ten times
+    let one = || sl::splat_vec4(1.) * 1.;
+    let compute = || Out {
+        place: one() + one() + one() + one() + one(),
+        color: sl::splat_vec4(1.),
+    };
+
+    let module = compute.into_module();
+    for entry in &module.nm.entry_points {
+        let literals = entry
+            .function
+            .expressions
+            .iter()
+            .filter(|(_, ex)| matches!(ex, Expression::Literal(_)))
+            .count();
+
+        assert_eq!(literals, 1, "literals should be deduplicated");
+    }
+}
+
+#[test]
+fn shader_noise() -> Result<(), Error> {
+    use dunge::sl::{self, Index, Out};
+
+    let compute = |Index(index): Index| {
+        let uv = sl::thunk(sl::vec2(sl::f32(index), 0.5));
+        Out {
+            place: sl::vec4(
+                sl::hash(uv.clone()),
+                sl::value_noise(uv.clone()),
+                sl::perlin(uv),
+                1.,
+            ),
+            color: sl::splat_vec4(1.),
+        }
+    };
+
+    let cx = helpers::block_on(dunge::context())?;
+    let shader = cx.make_shader(compute);
+    helpers::eq_lines(shader.debug_wgsl(), include_str!("shader_noise.wgsl"));
+    Ok(())
+}
+
 #[test]
 #[should_panic(expected = "thunk cannot be created outside of a shader function")]
 fn shader_thunk_outside() {
@@ -19,8 +19,8 @@ fn vs() -> VertexOutput {
             }
         }
     }
-    let _e12: vec4<f32> = local;
-    return VertexOutput(_e12);
+    let _e11: vec4<f32> = local;
+    return VertexOutput(_e11);
 }
 
 @fragment 
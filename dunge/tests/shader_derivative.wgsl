@@ -0,0 +1,21 @@
+struct type_1 {
+    @location(0) member: vec2<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+    @location(0) member_1: vec2<f32>,
+}
+
+@vertex 
+fn vs(param: type_1) -> VertexOutput {
+    return VertexOutput(vec4<f32>(param.member, vec2<f32>(0f, 1f)), param.member);
+}
+
+@fragment 
+fn fs(param_1: VertexOutput) -> @location(0) vec4<f32> {
+    let _e2: vec2<f32> = fwidth(param_1.member_1);
+    let _e3: vec2<f32> = dpdx(param_1.member_1);
+    let _e4: vec2<f32> = dpdy(param_1.member_1);
+    return vec4<f32>(_e2, (_e3 + _e4));
+}
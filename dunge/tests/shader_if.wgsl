@@ -11,8 +11,8 @@ fn vs() -> VertexOutput {
     } else {
         local = (vec4<f32>(2f, 2f, 2f, 2f) * 2f);
     }
-    let _e11: vec4<f32> = local;
-    return VertexOutput(_e11);
+    let _e7: vec4<f32> = local;
+    return VertexOutput(_e7);
 }
 
 @fragment 
@@ -0,0 +1,43 @@
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+}
+
+@group(0) @binding(0) 
+var<uniform> global: mat2x2<f32>;
+@group(0) @binding(1) 
+var<uniform> global_1: mat4x4<f32>;
+
+@vertex 
+fn vs() -> VertexOutput {
+    let _e1: mat4x4<f32> = global_1;
+    let _e25: f32 = ((_e1[2].z * _e1[3].w) - (_e1[3].z * _e1[2].w));
+    let _e29: f32 = ((_e1[1].z * _e1[3].w) - (_e1[3].z * _e1[1].w));
+    let _e34: f32 = ((_e1[1].z * _e1[2].w) - (_e1[2].z * _e1[1].w));
+    let _e36: f32 = (((_e1[1].y * _e25) - (_e1[2].y * _e29)) + (_e1[3].y * _e34));
+    let _e41: f32 = ((_e1[0].z * _e1[3].w) - (_e1[3].z * _e1[0].w));
+    let _e46: f32 = ((_e1[0].z * _e1[2].w) - (_e1[2].z * _e1[0].w));
+    let _e48: f32 = (((_e1[0].y * _e25) - (_e1[2].y * _e41)) + (_e1[3].y * _e46));
+    let _e56: f32 = ((_e1[0].z * _e1[1].w) - (_e1[1].z * _e1[0].w));
+    let _e58: f32 = (((_e1[0].y * _e29) - (_e1[1].y * _e41)) + (_e1[3].y * _e56));
+    let _e65: f32 = (((_e1[0].y * _e34) - (_e1[1].y * _e46)) + (_e1[2].y * _e56));
+    let _e97: f32 = ((_e1[2].y * _e1[3].w) - (_e1[3].y * _e1[2].w));
+    let _e101: f32 = ((_e1[1].y * _e1[3].w) - (_e1[3].y * _e1[1].w));
+    let _e106: f32 = ((_e1[1].y * _e1[2].w) - (_e1[2].y * _e1[1].w));
+    let _e112: f32 = ((_e1[0].y * _e1[3].w) - (_e1[3].y * _e1[0].w));
+    let _e117: f32 = ((_e1[0].y * _e1[2].w) - (_e1[2].y * _e1[0].w));
+    let _e126: f32 = ((_e1[0].y * _e1[1].w) - (_e1[1].y * _e1[0].w));
+    let _e138: f32 = ((_e1[2].y * _e1[3].z) - (_e1[3].y * _e1[2].z));
+    let _e142: f32 = ((_e1[1].y * _e1[3].z) - (_e1[3].y * _e1[1].z));
+    let _e147: f32 = ((_e1[1].y * _e1[2].z) - (_e1[2].y * _e1[1].z));
+    let _e154: f32 = ((_e1[0].y * _e1[3].z) - (_e1[3].y * _e1[0].z));
+    let _e159: f32 = ((_e1[0].y * _e1[2].z) - (_e1[2].y * _e1[0].z));
+    let _e167: f32 = ((_e1[0].y * _e1[1].z) - (_e1[1].y * _e1[0].z));
+    return VertexOutput(((mat4x4<f32>(vec4<f32>(_e36, -(_e48), _e58, -(_e65)), vec4<f32>(-((((_e1[1].x * _e25) - (_e1[2].x * _e29)) + (_e1[3].x * _e34))), (((_e1[0].x * _e25) - (_e1[2].x * _e41)) + (_e1[3].x * _e46)), -((((_e1[0].x * _e29) - (_e1[1].x * _e41)) + (_e1[3].x * _e56))), (((_e1[0].x * _e34) - (_e1[1].x * _e46)) + (_e1[2].x * _e56))), vec4<f32>((((_e1[1].x * _e97) - (_e1[2].x * _e101)) + (_e1[3].x * _e106)), -((((_e1[0].x * _e97) - (_e1[2].x * _e112)) + (_e1[3].x * _e117))), (((_e1[0].x * _e101) - (_e1[1].x * _e112)) + (_e1[3].x * _e126)), -((((_e1[0].x * _e106) - (_e1[1].x * _e117)) + (_e1[2].x * _e126)))), vec4<f32>(-((((_e1[1].x * _e138) - (_e1[2].x * _e142)) + (_e1[3].x * _e147))), (((_e1[0].x * _e138) - (_e1[2].x * _e154)) + (_e1[3].x * _e159)), -((((_e1[0].x * _e142) - (_e1[1].x * _e154)) + (_e1[3].x * _e167))), (((_e1[0].x * _e147) - (_e1[1].x * _e159)) + (_e1[2].x * _e167)))) * (1f / ((((_e1[0].x * _e36) - (_e1[1].x * _e48)) + (_e1[2].x * _e58)) - (_e1[3].x * _e65)))) * vec4<f32>(1f, 1f, 1f, 1f)));
+}
+
+@fragment 
+fn fs(param: VertexOutput) -> @location(0) vec4<f32> {
+    let _e1: mat2x2<f32> = global;
+    let _e6: mat2x2<f32> = global;
+    return vec4<f32>((transpose(_e1) * vec2<f32>(1f, 1f)), ((mat2x2<f32>(vec2<f32>(_e6[1].y, -(_e6[0].y)), vec2<f32>(-(_e6[1].x), _e6[0].x)) * (1f / ((_e6[0].x * _e6[1].y) - (_e6[1].x * _e6[0].y)))) * vec2<f32>(1f, 1f)));
+}
@@ -0,0 +1,14 @@
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+}
+
+@vertex 
+fn vs() -> VertexOutput {
+    return VertexOutput(vec4<f32>((reflect(vec3<f32>(1f, 2f, 3f), normalize(vec3<f32>(1f, 2f, 3f))) + refract(vec3<f32>(1f, 2f, 3f), normalize(vec3<f32>(1f, 2f, 3f)), 0.5f)), (mix(exp(1f), log(2f), fract(0.5f)) + max(min(sign(-1f), step(0.5f, 1f)), length(vec3<f32>(1f, 2f, 3f))))));
+}
+
+@fragment 
+fn fs(param: VertexOutput) -> @location(0) vec4<f32> {
+    let _e3: f32 = clamp(1f, 0f, 2f);
+    return vec4<f32>(_e3, _e3, _e3, _e3);
+}
@@ -0,0 +1,67 @@
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+}
+
+@vertex 
+fn vs(@builtin(vertex_index) param: u32) -> VertexOutput {
+    var local: array<vec2<f32>, 8>;
+
+    let _e3: vec2<f32> = vec2<f32>(f32(param), 0.5f);
+    let _e8: f32 = fract((_e3.x * 0.1031f));
+    let _e10: f32 = fract((_e3.y * 0.1031f));
+    let _e19: f32 = (((_e8 * (_e10 + 33.33f)) + (_e10 * (_e8 + 33.33f))) + (_e8 * (_e8 + 33.33f)));
+    let _e20: f32 = (_e8 + _e19);
+    let _e27: f32 = floor(_e3.x);
+    let _e28: f32 = floor(_e3.y);
+    let _e29: f32 = (_e3.x - _e27);
+    let _e30: f32 = (_e3.y - _e28);
+    let _e32: f32 = fract((_e27 * 0.1031f));
+    let _e34: f32 = fract((_e28 * 0.1031f));
+    let _e42: f32 = (((_e32 * (_e34 + 33.33f)) + (_e34 * (_e32 + 33.33f))) + (_e32 * (_e32 + 33.33f)));
+    let _e43: f32 = (_e32 + _e42);
+    let _e52: f32 = fract(((_e27 + 1f) * 0.1031f));
+    let _e54: f32 = fract((_e28 * 0.1031f));
+    let _e62: f32 = (((_e52 * (_e54 + 33.33f)) + (_e54 * (_e52 + 33.33f))) + (_e52 * (_e52 + 33.33f)));
+    let _e63: f32 = (_e52 + _e62);
+    let _e71: f32 = fract((_e27 * 0.1031f));
+    let _e73: f32 = fract(((_e28 + 1f) * 0.1031f));
+    let _e81: f32 = (((_e71 * (_e73 + 33.33f)) + (_e73 * (_e71 + 33.33f))) + (_e71 * (_e71 + 33.33f)));
+    let _e82: f32 = (_e71 + _e81);
+    let _e92: f32 = fract(((_e27 + 1f) * 0.1031f));
+    let _e94: f32 = fract(((_e28 + 1f) * 0.1031f));
+    let _e102: f32 = (((_e92 * (_e94 + 33.33f)) + (_e94 * (_e92 + 33.33f))) + (_e92 * (_e92 + 33.33f)));
+    let _e103: f32 = (_e92 + _e102);
+    let _e113: f32 = ((_e29 * _e29) * (3f - (_e29 * 2f)));
+    let _e123: f32 = floor(_e3.x);
+    let _e124: f32 = floor(_e3.y);
+    let _e125: f32 = (_e3.x - _e123);
+    let _e126: f32 = (_e3.y - _e124);
+    local = array<vec2<f32>, 8>(vec2<f32>(1f, 0f), vec2<f32>(-1f, 0f), vec2<f32>(0f, 1f), vec2<f32>(0f, -1f), vec2<f32>(0.70710677f, 0.70710677f), vec2<f32>(-0.70710677f, 0.70710677f), vec2<f32>(0.70710677f, -0.70710677f), vec2<f32>(-0.70710677f, -0.70710677f));
+    let _e142: f32 = fract((_e123 * 0.1031f));
+    let _e144: f32 = fract((_e124 * 0.1031f));
+    let _e152: f32 = (((_e142 * (_e144 + 33.33f)) + (_e144 * (_e142 + 33.33f))) + (_e142 * (_e142 + 33.33f)));
+    let _e153: f32 = (_e142 + _e152);
+    let _e164: vec2<f32> = local[(u32((fract(((_e153 + (_e144 + _e152)) * _e153)) * 256f)) % 8u)];
+    let _e173: f32 = fract(((_e123 + 1f) * 0.1031f));
+    let _e175: f32 = fract((_e124 * 0.1031f));
+    let _e183: f32 = (((_e173 * (_e175 + 33.33f)) + (_e175 * (_e173 + 33.33f))) + (_e173 * (_e173 + 33.33f)));
+    let _e184: f32 = (_e173 + _e183);
+    let _e193: vec2<f32> = local[(u32((fract(((_e184 + (_e175 + _e183)) * _e184)) * 256f)) % 8u)];
+    let _e202: f32 = fract((_e123 * 0.1031f));
+    let _e204: f32 = fract(((_e124 + 1f) * 0.1031f));
+    let _e212: f32 = (((_e202 * (_e204 + 33.33f)) + (_e204 * (_e202 + 33.33f))) + (_e202 * (_e202 + 33.33f)));
+    let _e213: f32 = (_e202 + _e212);
+    let _e222: vec2<f32> = local[(u32((fract(((_e213 + (_e204 + _e212)) * _e213)) * 256f)) % 8u)];
+    let _e233: f32 = fract(((_e123 + 1f) * 0.1031f));
+    let _e235: f32 = fract(((_e124 + 1f) * 0.1031f));
+    let _e243: f32 = (((_e233 * (_e235 + 33.33f)) + (_e235 * (_e233 + 33.33f))) + (_e233 * (_e233 + 33.33f)));
+    let _e244: f32 = (_e233 + _e243);
+    let _e253: vec2<f32> = local[(u32((fract(((_e244 + (_e235 + _e243)) * _e244)) * 256f)) % 8u)];
+    let _e268: f32 = (((_e125 * _e125) * _e125) * ((_e125 * ((_e125 * 6f) - 15f)) + 10f));
+    return VertexOutput(vec4<f32>(fract(((_e20 + (_e10 + _e19)) * _e20)), mix(mix(fract(((_e43 + (_e34 + _e42)) * _e43)), fract(((_e63 + (_e54 + _e62)) * _e63)), _e113), mix(fract(((_e82 + (_e73 + _e81)) * _e82)), fract(((_e103 + (_e94 + _e102)) * _e103)), _e113), ((_e30 * _e30) * (3f - (_e30 * 2f)))), (mix(mix(((_e164.x * _e125) + (_e164.y * _e126)), ((_e193.x * (_e125 - 1f)) + (_e193.y * _e126)), _e268), mix(((_e222.x * _e125) + (_e222.y * (_e126 - 1f))), ((_e253.x * (_e125 - 1f)) + (_e253.y * (_e126 - 1f))), _e268), (((_e126 * _e126) * _e126) * ((_e126 * ((_e126 * 6f) - 15f)) + 10f))) * 1.4142135f), 1f));
+}
+
+@fragment 
+fn fs(param_1: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(1f, 1f, 1f, 1f);
+}
@@ -0,0 +1,29 @@
+struct type_1 {
+    @location(0) member: vec3<f32>,
+    @location(1) member_1: vec3<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+    @location(0) member_1: vec3<f32>,
+}
+
+@group(0) @binding(0) 
+var<uniform> global: mat4x4<f32>;
+
+@vertex 
+fn vs(param: type_1) -> VertexOutput {
+    let _e1: mat4x4<f32> = global;
+    let _e7: mat4x4<f32> = global;
+    let _e12: vec3<f32> = vec3<f32>(_e7[0].x, _e7[0].y, _e7[0].z);
+    let _e17: vec3<f32> = vec3<f32>(_e7[1].x, _e7[1].y, _e7[1].z);
+    let _e22: vec3<f32> = vec3<f32>(_e7[2].x, _e7[2].y, _e7[2].z);
+    let _e23: vec3<f32> = cross(_e17, _e22);
+    return VertexOutput((_e1 * vec4<f32>(param.member, 1f)), normalize((((1f / dot(_e12, _e23)) * mat3x3<f32>(_e23, cross(_e22, _e12), cross(_e12, _e17))) * param.member_1)));
+}
+
+@fragment 
+fn fs(param_1: VertexOutput) -> @location(0) vec4<f32> {
+    let _e6: f32 = clamp(dot(param_1.member_1, vec3<f32>(0f, 1f, 0f)), 0f, 1f);
+    return vec4<f32>(vec3<f32>(_e6, _e6, _e6), 1f);
+}
@@ -0,0 +1,19 @@
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+}
+
+@group(0) @binding(0) 
+var global: texture_2d<f32>;
+@group(0) @binding(1) 
+var global_1: sampler;
+
+@vertex 
+fn vs() -> VertexOutput {
+    return VertexOutput(vec4<f32>(1f, 1f, 1f, 1f));
+}
+
+@fragment 
+fn fs(param: VertexOutput) -> @location(0) vec4<f32> {
+    let _e8: vec4<f32> = textureSample(global, global_1, vec2<f32>(0.5f, 0.5f));
+    return vec4<f32>(vec3<f32>(1f, 1f, 1f), smoothstep(0.4f, 0.6f, _e8.w));
+}
@@ -0,0 +1,19 @@
+struct type_1 {
+    @location(0) member: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+    @location(0) member_1: vec2<f32>,
+    @location(1) member_2: vec2<f32>,
+}
+
+@vertex 
+fn vs(param: type_1) -> VertexOutput {
+    return VertexOutput(vec4<f32>(param.member.xyz, 1f), param.member.xz, param.member.yzw.xy);
+}
+
+@fragment 
+fn fs(param_1: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(param_1.member_1, param_1.member_2);
+}
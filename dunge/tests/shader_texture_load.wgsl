@@ -0,0 +1,17 @@
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+}
+
+@group(0) @binding(0) 
+var global: texture_2d<f32>;
+
+@vertex 
+fn vs() -> VertexOutput {
+    return VertexOutput(vec4<f32>(1f, 1f, 1f, 1f));
+}
+
+@fragment 
+fn fs(param: VertexOutput) -> @location(0) vec4<f32> {
+    let _e5: vec4<f32> = textureLoad(global, vec2<u32>(0u, 1u), i32(0u));
+    return _e5;
+}
@@ -0,0 +1,20 @@
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+}
+
+@group(0) @binding(0) 
+var global: texture_2d<f32>;
+@group(0) @binding(1) 
+var global_1: sampler;
+
+@vertex 
+fn vs() -> VertexOutput {
+    let _e5: vec4<f32> = textureSampleLevel(global, global_1, vec2<f32>(0f, 0f), 1f);
+    return VertexOutput(_e5);
+}
+
+@fragment 
+fn fs(param: VertexOutput) -> @location(0) vec4<f32> {
+    let _e5: vec4<f32> = textureSampleBias(global, global_1, vec2<f32>(1f, 1f), -0.5f);
+    return _e5;
+}
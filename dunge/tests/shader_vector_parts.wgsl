@@ -0,0 +1,18 @@
+struct type_1 {
+    @location(0) member: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+    @location(0) member_1: vec2<f32>,
+}
+
+@vertex 
+fn vs(param: type_1) -> VertexOutput {
+    return VertexOutput(vec4<f32>(0f, param.member.xy, 1f), param.member.zw);
+}
+
+@fragment 
+fn fs(param_1: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(vec3<f32>(param_1.member_1, 0.5f), 1f);
+}
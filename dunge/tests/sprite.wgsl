@@ -0,0 +1,21 @@
+struct VertexOutput {
+    @builtin(position) member: vec4<f32>,
+    @location(0) member_1: vec2<f32>,
+    @location(1) member_2: vec4<f32>,
+}
+
+@group(0) @binding(0) 
+var global: texture_2d<f32>;
+@group(0) @binding(1) 
+var global_1: sampler;
+
+@vertex 
+fn vs(@location(0) param: vec4<f32>, @location(1) param_1: vec4<f32>, @location(2) param_2: vec4<f32>, @builtin(vertex_index) param_3: u32) -> VertexOutput {
+    return VertexOutput(vec4<f32>((((param.x + (f32((param_3 / 2u)) * param.z)) * 2f) - 1f), (1f - ((param.y + (f32((param_3 % 2u)) * param.w)) * 2f)), 0f, 1f), vec2<f32>((param_1.x + (f32((param_3 / 2u)) * param_1.z)), (param_1.y + (f32((param_3 % 2u)) * param_1.w))), param_2);
+}
+
+@fragment 
+fn fs(param_4: VertexOutput) -> @location(0) vec4<f32> {
+    let _e4: vec4<f32> = textureSample(global, global_1, param_4.member_1);
+    return vec4<f32>((_e4.x * param_4.member_2.x), (_e4.y * param_4.member_2.y), (_e4.z * param_4.member_2.z), (_e4.w * param_4.member_2.w));
+}
@@ -77,7 +77,7 @@ pub(crate) fn derive(input: DeriveInput) -> TokenStream {
     let n_members = fields.len();
     let group_visit_members = iter::zip(0.., &fields).map(|(index, field)| {
         let ident = member::make(index, field.ident.clone());
-        quote::quote! { ::dunge::bind::VisitMember::visit_member(self.#ident, visitor) }
+        quote::quote! { ::dunge::bind::VisitMember::visit_member(&self.#ident, visitor) }
     });
 
     let group_fields = iter::zip(0.., &fields).map(|(index, field)| {
@@ -164,8 +164,8 @@ mod tests {
             impl ::dunge::bind::Visit for Map<'_> {
                 const N_MEMBERS: ::core::primitive::usize = 2usize;
                 fn visit<'a>(&'a self, visitor: &mut ::dunge::bind::Visitor<'a>) {
-                    ::dunge::bind::VisitMember::visit_member(self.tex, visitor);
-                    ::dunge::bind::VisitMember::visit_member(self.sam, visitor);
+                    ::dunge::bind::VisitMember::visit_member(&self.tex, visitor);
+                    ::dunge::bind::VisitMember::visit_member(&self.sam, visitor);
                 }
             }
 
@@ -207,8 +207,8 @@ mod tests {
             impl ::dunge::bind::Visit for Map<'_> {
                 const N_MEMBERS: ::core::primitive::usize = 2usize;
                 fn visit<'a>(&'a self, visitor: &mut ::dunge::bind::Visitor<'a>) {
-                    ::dunge::bind::VisitMember::visit_member(self.0, visitor);
-                    ::dunge::bind::VisitMember::visit_member(self.1, visitor);
+                    ::dunge::bind::VisitMember::visit_member(&self.0, visitor);
+                    ::dunge::bind::VisitMember::visit_member(&self.1, visitor);
                 }
             }
 
@@ -20,7 +20,7 @@ pub fn derive_instance(input: TokenStream) -> TokenStream {
 }
 
 /// Derive implementation for the vector type.
-#[proc_macro_derive(Vertex)]
+#[proc_macro_derive(Vertex, attributes(stream))]
 pub fn derive_vertex(input: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(input);
     vertex::derive(input).into()
@@ -3,6 +3,7 @@ use {
     proc_macro2::TokenStream,
     syn::{
         meta::ParseNestedMeta, spanned::Spanned, Attribute, Data, DataStruct, DeriveInput, Fields,
+        LitInt,
     },
 };
 
@@ -43,6 +44,29 @@ pub(crate) fn derive(input: DeriveInput) -> TokenStream {
         };
     }
 
+    let mut streams = vec![];
+    for field in &fields {
+        match stream(&field.attrs) {
+            Ok(n) => streams.push(n),
+            Err(err) => return err.into_compile_error(),
+        }
+    }
+
+    let max = streams.iter().copied().max().unwrap_or_default();
+    if (0..max).any(|n| !streams.contains(&n)) {
+        return quote::quote_spanned! { fields.span() =>
+            ::std::compile_error!("vertex streams must be numbered sequentially from zero");
+        };
+    }
+
+    let streams = (max > 0).then(|| {
+        quote::quote! {
+            const STREAMS: ::dunge::sl::Define<::core::primitive::u32> = ::dunge::sl::Define::new(&[
+                #(#streams),*,
+            ]);
+        }
+    });
+
     let name = input.ident;
     let projection_name = quote::format_ident!("{name}Projection");
     let vector_types = fields.iter().map(|field| {
@@ -86,6 +110,7 @@ pub(crate) fn derive(input: DeriveInput) -> TokenStream {
             const DEF: ::dunge::sl::Define<::dunge::types::VectorType> = ::dunge::sl::Define::new(&[
                 #(#vector_types),*,
             ]);
+            #streams
         }
 
         #projection
@@ -100,6 +125,18 @@ pub(crate) fn derive(input: DeriveInput) -> TokenStream {
     }
 }
 
+fn stream(attrs: &[Attribute]) -> syn::Result<u32> {
+    let mut stream = 0;
+    for attr in attrs {
+        if attr.path().is_ident("stream") {
+            let n: LitInt = attr.parse_args()?;
+            stream = n.base10_parse()?;
+        }
+    }
+
+    Ok(stream)
+}
+
 fn is_repr_c(attr: &Attribute) -> bool {
     let parse_meta = |meta: ParseNestedMeta| {
         if meta.path.is_ident("C") {
@@ -190,4 +227,48 @@ mod tests {
 
         assert_eq!(actual.to_string(), expected.to_string());
     }
+
+    #[test]
+    fn derive_stream_vertex() {
+        let input = quote::quote! {
+            #[repr(C)]
+            struct Vert {
+                pos: [f32; 2],
+                #[stream(1)]
+                col: [f32; 3],
+            }
+        };
+
+        let input = syn::parse2(input).expect("parse input");
+        let actual = derive(input);
+        let expected = quote::quote! {
+            unsafe impl ::dunge::Vertex for Vert {
+                type Projection = VertProjection;
+                const DEF: ::dunge::sl::Define<::dunge::types::VectorType> = ::dunge::sl::Define::new(&[
+                    <[f32; 2] as ::dunge::vertex::InputProjection>::TYPE,
+                    <[f32; 3] as ::dunge::vertex::InputProjection>::TYPE,
+                ]);
+                const STREAMS: ::dunge::sl::Define<::core::primitive::u32> = ::dunge::sl::Define::new(&[
+                    0u32,
+                    1u32,
+                ]);
+            }
+
+            pub struct VertProjection {
+                pos: <[f32; 2] as ::dunge::vertex::InputProjection>::Field,
+                col: <[f32; 3] as ::dunge::vertex::InputProjection>::Field,
+            }
+
+            impl ::dunge::vertex::Projection for VertProjection {
+                fn projection(id: ::core::primitive::u32) -> Self {
+                    Self {
+                        pos: <[f32; 2] as ::dunge::vertex::InputProjection>::input_projection(id, 0u32),
+                        col: <[f32; 3] as ::dunge::vertex::InputProjection>::input_projection(id, 1u32),
+                    }
+                }
+            }
+        };
+
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
 }
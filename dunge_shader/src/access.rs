@@ -2,7 +2,9 @@ use {
     crate::{
         eval::{Eval, Expr, GetEntry},
         op::Ret,
+        types,
     },
+    naga::{SwizzleComponent, VectorSize},
     std::marker::PhantomData,
 };
 
@@ -69,6 +71,171 @@ where
     }
 }
 
+impl<A, O> Ret<A, O>
+where
+    O: Access + types::Vector,
+{
+    pub fn xy<E>(self) -> Ret<Swizzle<Self, E>, types::Vec2<O::Scalar>>
+    where
+        O::Dimension: Has<1>,
+    {
+        Ret::new(Swizzle::new(
+            VectorSize::Bi,
+            &[SwizzleComponent::X, SwizzleComponent::Y],
+            self,
+        ))
+    }
+
+    pub fn xz<E>(self) -> Ret<Swizzle<Self, E>, types::Vec2<O::Scalar>>
+    where
+        O::Dimension: Has<2>,
+    {
+        Ret::new(Swizzle::new(
+            VectorSize::Bi,
+            &[SwizzleComponent::X, SwizzleComponent::Z],
+            self,
+        ))
+    }
+
+    pub fn xw<E>(self) -> Ret<Swizzle<Self, E>, types::Vec2<O::Scalar>>
+    where
+        O::Dimension: Has<3>,
+    {
+        Ret::new(Swizzle::new(
+            VectorSize::Bi,
+            &[SwizzleComponent::X, SwizzleComponent::W],
+            self,
+        ))
+    }
+
+    pub fn yz<E>(self) -> Ret<Swizzle<Self, E>, types::Vec2<O::Scalar>>
+    where
+        O::Dimension: Has<2>,
+    {
+        Ret::new(Swizzle::new(
+            VectorSize::Bi,
+            &[SwizzleComponent::Y, SwizzleComponent::Z],
+            self,
+        ))
+    }
+
+    pub fn yw<E>(self) -> Ret<Swizzle<Self, E>, types::Vec2<O::Scalar>>
+    where
+        O::Dimension: Has<3>,
+    {
+        Ret::new(Swizzle::new(
+            VectorSize::Bi,
+            &[SwizzleComponent::Y, SwizzleComponent::W],
+            self,
+        ))
+    }
+
+    pub fn zw<E>(self) -> Ret<Swizzle<Self, E>, types::Vec2<O::Scalar>>
+    where
+        O::Dimension: Has<3>,
+    {
+        Ret::new(Swizzle::new(
+            VectorSize::Bi,
+            &[SwizzleComponent::Z, SwizzleComponent::W],
+            self,
+        ))
+    }
+
+    pub fn xyz<E>(self) -> Ret<Swizzle<Self, E>, types::Vec3<O::Scalar>>
+    where
+        O::Dimension: Has<2>,
+    {
+        Ret::new(Swizzle::new(
+            VectorSize::Tri,
+            &[
+                SwizzleComponent::X,
+                SwizzleComponent::Y,
+                SwizzleComponent::Z,
+            ],
+            self,
+        ))
+    }
+
+    pub fn xyw<E>(self) -> Ret<Swizzle<Self, E>, types::Vec3<O::Scalar>>
+    where
+        O::Dimension: Has<3>,
+    {
+        Ret::new(Swizzle::new(
+            VectorSize::Tri,
+            &[
+                SwizzleComponent::X,
+                SwizzleComponent::Y,
+                SwizzleComponent::W,
+            ],
+            self,
+        ))
+    }
+
+    pub fn xzw<E>(self) -> Ret<Swizzle<Self, E>, types::Vec3<O::Scalar>>
+    where
+        O::Dimension: Has<3>,
+    {
+        Ret::new(Swizzle::new(
+            VectorSize::Tri,
+            &[
+                SwizzleComponent::X,
+                SwizzleComponent::Z,
+                SwizzleComponent::W,
+            ],
+            self,
+        ))
+    }
+
+    pub fn yzw<E>(self) -> Ret<Swizzle<Self, E>, types::Vec3<O::Scalar>>
+    where
+        O::Dimension: Has<3>,
+    {
+        Ret::new(Swizzle::new(
+            VectorSize::Tri,
+            &[
+                SwizzleComponent::Y,
+                SwizzleComponent::Z,
+                SwizzleComponent::W,
+            ],
+            self,
+        ))
+    }
+}
+
+pub struct Swizzle<A, E> {
+    size: VectorSize,
+    pattern: [SwizzleComponent; 4],
+    a: A,
+    e: PhantomData<E>,
+}
+
+impl<A, E> Swizzle<A, E> {
+    fn new(size: VectorSize, components: &[SwizzleComponent], a: A) -> Self {
+        let mut pattern = [SwizzleComponent::X; 4];
+        pattern[..components.len()].copy_from_slice(components);
+        Self {
+            size,
+            pattern,
+            a,
+            e: PhantomData,
+        }
+    }
+}
+
+impl<A, O, E> Eval<E> for Ret<Swizzle<A, E>, O>
+where
+    A: Eval<E>,
+    E: GetEntry,
+{
+    type Out = O;
+
+    fn eval(self, en: &mut E) -> Expr {
+        let me = self.get();
+        let v = me.a.eval(en);
+        en.get_entry().swizzle(me.size, v, me.pattern)
+    }
+}
+
 pub trait Has<const D: usize> {}
 
 pub struct Dimension<const D: usize>;
@@ -45,6 +45,7 @@ pub enum InputInfo {
 #[derive(Clone, Copy)]
 pub struct VertInfo {
     pub def: Define<VectorType>,
+    pub streams: Define<u32>,
     pub size: usize,
 }
 
@@ -94,7 +95,9 @@ impl Context {
             limits: Limits {
                 index: 1,
                 verts: 1,
-                insts: 2,
+                // Each instance member takes a vertex buffer, four of them leave
+                // a half of the eight buffers supported by all devices to vertex streams
+                insts: 4,
                 group: 4,
             },
         }
@@ -107,10 +110,10 @@ impl Context {
         id
     }
 
-    fn add_vertex(&mut self, def: Define<VectorType>, size: usize) -> u32 {
+    fn add_vertex(&mut self, def: Define<VectorType>, streams: Define<u32>, size: usize) -> u32 {
         countdown(&mut self.limits.verts, "too many vertices in the shader");
         let id = self.inputs.len() as u32;
-        let info = VertInfo { def, size };
+        let info = VertInfo { def, streams, size };
         self.inputs.push(InputInfo::Vert(info));
         id
     }
@@ -201,7 +204,7 @@ where
     type Instance = ();
 
     fn from_context_input(cx: &mut Context) -> Self {
-        let id = cx.add_vertex(V::DEF, size_of::<V>());
+        let id = cx.add_vertex(V::DEF, V::STREAMS, size_of::<V>());
         Self(vertex::Projection::projection(id))
     }
 }
@@ -5,13 +5,14 @@ use {
         math::Func,
         module::{Module, Out, Output},
         op::{Bi, Ret, Un},
-        texture::Sampled,
+        texture::{Loaded, Sampled},
         types::{self, MemberType, ScalarType, ValueType, VectorType},
     },
     naga::{
-        AddressSpace, Arena, Binding, BuiltIn, EntryPoint, Expression, Function, FunctionArgument,
-        FunctionResult, GlobalVariable, Handle, Literal, LocalVariable, Range, ResourceBinding,
-        ShaderStage, Span, Statement, StructMember, Type, TypeInner, UniqueArena,
+        AddressSpace, Arena, Binding, BuiltIn, DerivativeAxis, DerivativeControl, EntryPoint,
+        Expression, Function, FunctionArgument, FunctionResult, GlobalVariable, Handle,
+        Interpolation, Literal, LocalVariable, Range, ResourceBinding, ShaderStage, Span,
+        Statement, StructMember, SwizzleComponent, Type, TypeInner, UniqueArena, VectorSize,
     },
     std::{
         cell::{Cell, RefCell},
@@ -105,6 +106,14 @@ impl Expr {
     }
 }
 
+impl<O, E> Eval<E> for Ret<Expr, O> {
+    type Out = O;
+
+    fn eval(self, _: &mut E) -> Expr {
+        self.get()
+    }
+}
+
 pub(crate) struct Exprs(pub Vec<Handle<Expression>>);
 
 impl FromIterator<Expr> for Exprs {
@@ -314,7 +323,38 @@ where
 
     fn eval(self, en: &mut Fs) -> Expr {
         let vecty = <A::Out as types::Vector>::TYPE;
-        let index = en.push_evalf(vecty, |en| self.get().0.eval(en));
+        let index = en.push_evalf(vecty, false, |en| self.get().0.eval(en));
+        let en = &mut en.inner;
+        let arg = en.argument(0);
+        en.access_index(arg, index)
+    }
+}
+
+/// Passes a value from the vertex to the fragment stage without interpolation.
+///
+/// Unlike the [`fragment`] function, the value is not interpolated across
+/// the primitive, instead every fragment gets the value of its first vertex.
+/// Integer values are always passed this way, so the function is useful
+/// for floating point values which must stay the same for the whole primitive,
+/// like a face normal in flat shading.
+pub const fn flat<A>(a: A) -> Ret<Flat<A>, A::Out>
+where
+    A: Eval<Vs, Out: types::Vector>,
+{
+    Ret::new(Flat(a))
+}
+
+pub struct Flat<A>(A);
+
+impl<A> Eval<Fs> for Ret<Flat<A>, A::Out>
+where
+    A: Eval<Vs, Out: types::Vector> + 'static,
+{
+    type Out = A::Out;
+
+    fn eval(self, en: &mut Fs) -> Expr {
+        let vecty = <A::Out as types::Vector>::TYPE;
+        let index = en.push_evalf(vecty, true, |en| self.get().0.eval(en));
         let en = &mut en.inner;
         let arg = en.argument(0);
         en.access_index(arg, index)
@@ -524,11 +564,16 @@ impl GetEntry for Vs {
 struct Member {
     vecty: VectorType,
     built: Option<BuiltIn>,
+    flat: bool,
 }
 
 impl Member {
     fn from_vecty(vecty: VectorType) -> Self {
-        Self { vecty, built: None }
+        Self {
+            vecty,
+            built: None,
+            flat: false,
+        }
     }
 }
 
@@ -539,6 +584,7 @@ enum EvalFunction {
 
 struct Required {
     vecty: VectorType,
+    flat: bool,
     evalf: EvalFunction,
 }
 
@@ -553,17 +599,19 @@ impl Fs {
             inner: Entry::new(compl),
             required: vec![Required {
                 vecty: VectorType::Vec4f,
+                flat: false,
                 evalf: EvalFunction::Position,
             }],
         }
     }
 
-    fn push_evalf<F>(&mut self, vecty: VectorType, f: F) -> u32
+    fn push_evalf<F>(&mut self, vecty: VectorType, flat: bool, f: F) -> u32
     where
         F: FnOnce(&mut Vs) -> Expr + 'static,
     {
         let req = Required {
             vecty,
+            flat,
             evalf: EvalFunction::Fn(Box::new(f)),
         };
 
@@ -577,8 +625,12 @@ impl Fs {
             EvalFunction::Position => Member {
                 vecty: req.vecty,
                 built: Some(BuiltIn::Position { invariant: false }),
+                flat: false,
+            },
+            EvalFunction::Fn(_) => Member {
+                flat: req.flat,
+                ..Member::from_vecty(req.vecty)
             },
-            EvalFunction::Fn(_) => Member::from_vecty(req.vecty),
         };
 
         let mut members = self.required.iter().map(member);
@@ -635,6 +687,7 @@ pub struct Entry {
     cached_glob: HashMap<Handle<GlobalVariable>, Expr>,
     cached_locl: HashMap<Handle<LocalVariable>, Expr>,
     cached_args: HashMap<u32, Expr>,
+    cached_lits: HashMap<LiteralKey, Expr>,
 }
 
 impl Entry {
@@ -647,6 +700,7 @@ impl Entry {
             cached_glob: HashMap::default(),
             cached_locl: HashMap::default(),
             cached_args: HashMap::default(),
+            cached_lits: HashMap::default(),
         }
     }
 
@@ -675,8 +729,13 @@ impl Entry {
     }
 
     fn literal(&mut self, literal: Literal) -> Expr {
-        let ex = Expression::Literal(literal);
-        Expr(self.exprs.append(ex, Span::UNDEFINED))
+        *self
+            .cached_lits
+            .entry(LiteralKey::new(literal))
+            .or_insert_with(|| {
+                let ex = Expression::Literal(literal);
+                Expr(self.exprs.append(ex, Span::UNDEFINED))
+            })
     }
 
     pub(crate) fn zero_value(&mut self, ty: Handle<Type>) -> Expr {
@@ -705,7 +764,7 @@ impl Entry {
         })
     }
 
-    fn load(&mut self, ptr: Expr) -> Expr {
+    pub(crate) fn load(&mut self, ptr: Expr) -> Expr {
         let ex = Expression::Load { pointer: ptr.0 };
         let handle = self.exprs.append(ex, Span::UNDEFINED);
         let st = Statement::Emit(Range::new_from_bounds(handle, handle));
@@ -713,6 +772,31 @@ impl Entry {
         Expr(handle)
     }
 
+    /// Stores the value in a new local variable and returns the pointer to it.
+    pub(crate) fn store_local(&mut self, ty: Handle<Type>, value: Expr) -> Expr {
+        let v = self.add_local(ty);
+        let ptr = self.local(v);
+        let st = Statement::Store {
+            pointer: ptr.0,
+            value: value.0,
+        };
+
+        self.stack.insert(st, &self.exprs);
+        ptr
+    }
+
+    pub(crate) fn access(&mut self, base: Expr, index: Expr) -> Expr {
+        let ex = Expression::Access {
+            base: base.0,
+            index: index.0,
+        };
+
+        let handle = self.exprs.append(ex, Span::UNDEFINED);
+        let st = Statement::Emit(Range::new_from_bounds(handle, handle));
+        self.stack.insert(st, &self.exprs);
+        Expr(handle)
+    }
+
     pub(crate) fn access_index(&mut self, base: Expr, index: u32) -> Expr {
         let ex = Expression::AccessIndex {
             base: base.0,
@@ -725,6 +809,24 @@ impl Entry {
         Expr(handle)
     }
 
+    pub(crate) fn swizzle(
+        &mut self,
+        size: VectorSize,
+        vector: Expr,
+        pattern: [SwizzleComponent; 4],
+    ) -> Expr {
+        let ex = Expression::Swizzle {
+            size,
+            vector: vector.0,
+            pattern,
+        };
+
+        let handle = self.exprs.append(ex, Span::UNDEFINED);
+        let st = Statement::Emit(Range::new_from_bounds(handle, handle));
+        self.stack.insert(st, &self.exprs);
+        Expr(handle)
+    }
+
     pub(crate) fn convert(&mut self, expr: Expr, ty: ScalarType) -> Expr {
         let (kind, width) = ty.inner();
         let ex = Expression::As {
@@ -764,6 +866,19 @@ impl Entry {
         Expr(handle)
     }
 
+    pub(crate) fn derivative(&mut self, axis: DerivativeAxis, a: Expr) -> Expr {
+        let ex = Expression::Derivative {
+            axis,
+            ctrl: DerivativeControl::None,
+            expr: a.0,
+        };
+
+        let handle = self.exprs.append(ex, Span::UNDEFINED);
+        let st = Statement::Emit(Range::new_from_bounds(handle, handle));
+        self.stack.insert(st, &self.exprs);
+        Expr(handle)
+    }
+
     pub(crate) fn math(&mut self, f: Func, exprs: Evaluated) -> Expr {
         let ex = f.expr(exprs);
         let handle = self.exprs.append(ex, Span::UNDEFINED);
@@ -791,6 +906,13 @@ impl Entry {
         Expr(handle)
     }
 
+    pub(crate) fn load_texel(&mut self, ex: Loaded) -> Expr {
+        let handle = self.exprs.append(ex.expr(), Span::UNDEFINED);
+        let st = Statement::Emit(Range::new_from_bounds(handle, handle));
+        self.stack.insert(st, &self.exprs);
+        Expr(handle)
+    }
+
     pub(crate) fn kill(&mut self) {
         let st = Statement::Kill;
         self.stack.insert(st, &self.exprs);
@@ -980,10 +1102,11 @@ impl Compiler {
     fn define_input(&mut self, new: &mut Members, binds: &mut Bindings) -> Handle<Type> {
         let len = new.len();
         let mut members = Vec::with_capacity(len);
-        for (idx, Member { vecty, built }) in iter::zip(0.., new) {
+        for (idx, Member { vecty, built, flat }) in iter::zip(0.., new) {
             let ty = vecty.ty();
             let binding = match built {
                 Some(bi @ BuiltIn::Position { .. }) => Binding::BuiltIn(bi),
+                None if flat => binds.next_flat(&ty),
                 None => binds.next(&ty),
                 _ => unimplemented!(),
             };
@@ -1073,6 +1196,39 @@ impl Globals {
     }
 }
 
+/// The hashable representation of a literal.
+///
+/// Floats are compared by their bits, so equal values with
+/// different representations like `0.0` and `-0.0` remain distinct.
+#[derive(PartialEq, Eq, Hash)]
+enum LiteralKey {
+    F64(u64),
+    F32(u32),
+    U32(u32),
+    I32(i32),
+    U64(u64),
+    I64(i64),
+    Bool(bool),
+    AbstractInt(i64),
+    AbstractFloat(u64),
+}
+
+impl LiteralKey {
+    fn new(literal: Literal) -> Self {
+        match literal {
+            Literal::F64(v) => Self::F64(v.to_bits()),
+            Literal::F32(v) => Self::F32(v.to_bits()),
+            Literal::U32(v) => Self::U32(v),
+            Literal::I32(v) => Self::I32(v),
+            Literal::U64(v) => Self::U64(v),
+            Literal::I64(v) => Self::I64(v),
+            Literal::Bool(v) => Self::Bool(v),
+            Literal::AbstractInt(v) => Self::AbstractInt(v),
+            Literal::AbstractFloat(v) => Self::AbstractFloat(v.to_bits()),
+        }
+    }
+}
+
 #[derive(Default)]
 struct Bindings(u32);
 
@@ -1089,4 +1245,19 @@ impl Bindings {
         binding.apply_default_interpolation(&ty.inner);
         binding
     }
+
+    fn next_flat(&mut self, ty: &Type) -> Binding {
+        let mut binding = self.next(ty);
+        if let Binding::Location {
+            interpolation,
+            sampling,
+            ..
+        } = &mut binding
+        {
+            *interpolation = Some(Interpolation::Flat);
+            *sampling = None;
+        }
+
+        binding
+    }
 }
@@ -10,7 +10,10 @@ pub mod instance;
 mod math;
 mod matrix;
 mod module;
+mod noise;
 mod op;
+mod screen;
+mod sdf;
 mod texture;
 pub mod types;
 mod vector;
@@ -22,6 +25,6 @@ pub mod sl {
 
     pub use crate::{
         branch::*, context::*, convert::*, define::*, discard::*, eval::*, math::*, matrix::*,
-        module::*, op::*, texture::*, vector::*, zero::*,
+        module::*, noise::*, op::*, screen::*, sdf::*, texture::*, vector::*, zero::*,
     };
 }
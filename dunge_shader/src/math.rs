@@ -1,10 +1,10 @@
 use {
     crate::{
-        eval::{Eval, EvalTuple, Evaluated, Expr, GetEntry},
+        eval::{Eval, EvalTuple, Evaluated, Expr, Fs, GetEntry},
         op::Ret,
         types,
     },
-    naga::{Expression, MathFunction},
+    naga::{DerivativeAxis, Expression, MathFunction},
     std::marker::PhantomData,
 };
 
@@ -72,7 +72,8 @@ where
     Ret::new(Math::new((x,), MathFunction::Ceil))
 }
 
-pub const fn clamp<X, L, H, E>(x: X, lo: L, hi: H) -> Ret<Math<(X, L, H), E>, f32>
+#[allow(clippy::type_complexity)]
+pub const fn clamp<X, L, H, E>(x: X, lo: L, hi: H) -> Ret<Math<(X, L, H), E>, X::Out>
 where
     X: Eval<E, Out: types::Number>,
     L: Eval<E, Out = X::Out>,
@@ -112,6 +113,24 @@ where
     Ret::new(Math::new((x, y), MathFunction::Dot))
 }
 
+/// Performs the [`exp`](https://www.w3.org/TR/WGSL/#exp-builtin) function.
+pub const fn exp<X, E>(x: X) -> Ret<Math<(X,), E>, X::Out>
+where
+    X: Eval<E, Out: types::Float>,
+{
+    Ret::new(Math::new((x,), MathFunction::Exp))
+}
+
+/// Performs the [`fract`](https://www.w3.org/TR/WGSL/#fract-builtin) function.
+///
+/// Returns the fractional part of `x`, computed as `x - floor(x)`.
+pub const fn fract<X, E>(x: X) -> Ret<Math<(X,), E>, X::Out>
+where
+    X: Eval<E, Out: types::Float>,
+{
+    Ret::new(Math::new((x,), MathFunction::Fract))
+}
+
 pub const fn floor<X, E>(x: X) -> Ret<Math<(X,), E>, f32>
 where
     X: Eval<E, Out = f32>,
@@ -119,6 +138,63 @@ where
     Ret::new(Math::new((x,), MathFunction::Floor))
 }
 
+/// Performs the [`length`](https://www.w3.org/TR/WGSL/#length-builtin) function.
+pub const fn length<X, E>(x: X) -> Ret<Math<(X,), E>, f32>
+where
+    X: Eval<E, Out: types::Vector<Scalar = f32>>,
+{
+    Ret::new(Math::new((x,), MathFunction::Length))
+}
+
+/// Performs the [`log`](https://www.w3.org/TR/WGSL/#log-builtin) function.
+///
+/// Returns the natural logarithm of `x`.
+pub const fn log<X, E>(x: X) -> Ret<Math<(X,), E>, X::Out>
+where
+    X: Eval<E, Out: types::Float>,
+{
+    Ret::new(Math::new((x,), MathFunction::Log))
+}
+
+/// Performs the [`max`](https://www.w3.org/TR/WGSL/#max-float-builtin) function.
+pub const fn max<X, Y, E>(x: X, y: Y) -> Ret<Math<(X, Y), E>, X::Out>
+where
+    X: Eval<E, Out: types::Float>,
+    Y: Eval<E, Out = X::Out>,
+{
+    Ret::new(Math::new((x, y), MathFunction::Max))
+}
+
+/// Performs the [`min`](https://www.w3.org/TR/WGSL/#min-float-builtin) function.
+pub const fn min<X, Y, E>(x: X, y: Y) -> Ret<Math<(X, Y), E>, X::Out>
+where
+    X: Eval<E, Out: types::Float>,
+    Y: Eval<E, Out = X::Out>,
+{
+    Ret::new(Math::new((x, y), MathFunction::Min))
+}
+
+/// Performs the [`mix`](https://www.w3.org/TR/WGSL/#mix-builtin) function.
+///
+/// Returns the linear blend of `x` and `y`, computed as `x * (1 - t) + y * t`.
+#[allow(clippy::type_complexity)]
+pub const fn mix<X, Y, T, E>(x: X, y: Y, t: T) -> Ret<Math<(X, Y, T), E>, X::Out>
+where
+    X: Eval<E, Out: types::Float>,
+    Y: Eval<E, Out = X::Out>,
+    T: Eval<E, Out = X::Out>,
+{
+    Ret::new(Math::new((x, y, t), MathFunction::Mix))
+}
+
+/// Performs the [`normalize`](https://www.w3.org/TR/WGSL/#normalize-builtin) function.
+pub const fn normalize<X, E>(x: X) -> Ret<Math<(X,), E>, X::Out>
+where
+    X: Eval<E, Out: types::Vector<Scalar = f32>>,
+{
+    Ret::new(Math::new((x,), MathFunction::Normalize))
+}
+
 pub const fn pow<B, X, E>(base: B, exp: X) -> Ret<Math<(B, X), E>, f32>
 where
     B: Eval<E, Out = f32>,
@@ -127,6 +203,40 @@ where
     Ret::new(Math::new((base, exp), MathFunction::Pow))
 }
 
+/// Performs the [`reflect`](https://www.w3.org/TR/WGSL/#reflect-builtin) function.
+///
+/// Returns the reflection direction of the incident vector `i`
+/// off the surface with the normal `n`.
+pub const fn reflect<I, N, E>(i: I, n: N) -> Ret<Math<(I, N), E>, I::Out>
+where
+    I: Eval<E, Out: types::Vector<Scalar = f32>>,
+    N: Eval<E, Out = I::Out>,
+{
+    Ret::new(Math::new((i, n), MathFunction::Reflect))
+}
+
+/// Performs the [`refract`](https://www.w3.org/TR/WGSL/#refract-builtin) function.
+///
+/// Returns the refraction direction of the incident vector `i`
+/// through the surface with the normal `n` and the ratio of indices of refraction `eta`.
+#[allow(clippy::type_complexity)]
+pub const fn refract<I, N, R, E>(i: I, n: N, eta: R) -> Ret<Math<(I, N, R), E>, I::Out>
+where
+    I: Eval<E, Out: types::Vector<Scalar = f32>>,
+    N: Eval<E, Out = I::Out>,
+    R: Eval<E, Out = f32>,
+{
+    Ret::new(Math::new((i, n, eta), MathFunction::Refract))
+}
+
+/// Performs the [`sign`](https://www.w3.org/TR/WGSL/#sign-builtin) function.
+pub const fn sign<X, E>(x: X) -> Ret<Math<(X,), E>, X::Out>
+where
+    X: Eval<E, Out: types::Float>,
+{
+    Ret::new(Math::new((x,), MathFunction::Sign))
+}
+
 pub const fn sin<X, E>(x: X) -> Ret<Math<(X,), E>, f32>
 where
     X: Eval<E, Out = f32>,
@@ -141,6 +251,19 @@ where
     Ret::new(Math::new((x,), MathFunction::Sinh))
 }
 
+/// Performs the [`smoothstep`](https://www.w3.org/TR/WGSL/#smoothstep-builtin) function.
+///
+/// Returns a smooth Hermite interpolation between `0` and `1`
+/// when `x` is in the range between `lo` and `hi`.
+pub const fn smoothstep<L, H, X, E>(lo: L, hi: H, x: X) -> Ret<Math<(L, H, X), E>, f32>
+where
+    L: Eval<E, Out = f32>,
+    H: Eval<E, Out = f32>,
+    X: Eval<E, Out = f32>,
+{
+    Ret::new(Math::new((lo, hi, x), MathFunction::SmoothStep))
+}
+
 pub const fn sqrt<X, E>(x: X) -> Ret<Math<(X,), E>, f32>
 where
     X: Eval<E, Out = f32>,
@@ -148,6 +271,17 @@ where
     Ret::new(Math::new((x,), MathFunction::Sqrt))
 }
 
+/// Performs the [`step`](https://www.w3.org/TR/WGSL/#step-builtin) function.
+///
+/// Returns `1` if `edge` is less than or equal to `x` and `0` otherwise.
+pub const fn step<L, X, E>(edge: L, x: X) -> Ret<Math<(L, X), E>, X::Out>
+where
+    L: Eval<E, Out = X::Out>,
+    X: Eval<E, Out: types::Float>,
+{
+    Ret::new(Math::new((edge, x), MathFunction::Step))
+}
+
 pub const fn tan<X, E>(x: X) -> Ret<Math<(X,), E>, f32>
 where
     X: Eval<E, Out = f32>,
@@ -162,6 +296,72 @@ where
     Ret::new(Math::new((x,), MathFunction::Tanh))
 }
 
+/// Performs the [`transpose`](https://www.w3.org/TR/WGSL/#transpose-builtin) function.
+pub const fn transpose<M, E>(m: M) -> Ret<Math<(M,), E>, M::Out>
+where
+    M: Eval<E, Out: types::Matrix>,
+{
+    Ret::new(Math::new((m,), MathFunction::Transpose))
+}
+
+/// Performs the [`dpdx`](https://www.w3.org/TR/WGSL/#dpdx-builtin) function.
+///
+/// Returns the partial derivative of `x` with respect to the window x coordinate.
+/// Derivatives are only available in the fragment stage.
+pub const fn dpdx<X>(x: X) -> Ret<Derivative<X>, X::Out>
+where
+    X: Eval<Fs, Out: types::Float>,
+{
+    Ret::new(Derivative::new(x, DerivativeAxis::X))
+}
+
+/// Performs the [`dpdy`](https://www.w3.org/TR/WGSL/#dpdy-builtin) function.
+///
+/// Returns the partial derivative of `x` with respect to the window y coordinate.
+/// Derivatives are only available in the fragment stage.
+pub const fn dpdy<X>(x: X) -> Ret<Derivative<X>, X::Out>
+where
+    X: Eval<Fs, Out: types::Float>,
+{
+    Ret::new(Derivative::new(x, DerivativeAxis::Y))
+}
+
+/// Performs the [`fwidth`](https://www.w3.org/TR/WGSL/#fwidth-builtin) function.
+///
+/// Returns the sum of absolute derivatives `abs(dpdx(x)) + abs(dpdy(x))`,
+/// which shows how fast the value changes between neighbouring pixels.
+/// Derivatives are only available in the fragment stage.
+pub const fn fwidth<X>(x: X) -> Ret<Derivative<X>, X::Out>
+where
+    X: Eval<Fs, Out: types::Float>,
+{
+    Ret::new(Derivative::new(x, DerivativeAxis::Width))
+}
+
+pub struct Derivative<X> {
+    x: X,
+    axis: DerivativeAxis,
+}
+
+impl<X> Derivative<X> {
+    const fn new(x: X, axis: DerivativeAxis) -> Self {
+        Self { x, axis }
+    }
+}
+
+impl<X> Eval<Fs> for Ret<Derivative<X>, X::Out>
+where
+    X: Eval<Fs>,
+{
+    type Out = X::Out;
+
+    fn eval(self, en: &mut Fs) -> Expr {
+        let Derivative { x, axis } = self.get();
+        let x = x.eval(en);
+        en.get_entry().derivative(axis, x)
+    }
+}
+
 pub struct Math<A, E> {
     args: A,
     func: Func,
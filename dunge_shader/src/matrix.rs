@@ -1,11 +1,13 @@
 use {
     crate::{
         access::{Access, Dimension},
-        eval::{Eval, EvalTuple, Evaluated, Expr, Exprs, GetEntry},
-        op::Ret,
+        eval::{self, Entry, Eval, EvalTuple, Evaluated, Expr, Exprs, GetEntry, Thunk, Vs},
+        math,
+        op::{Bi, Ret, Un},
         types::{self, Matrix},
+        vector,
     },
-    std::marker::PhantomData,
+    std::{collections::HashMap, iter, marker::PhantomData},
 };
 
 macro_rules! impl_eval_mat {
@@ -83,6 +85,151 @@ where
     Ret::new(NewMat::new((x, y, z, w)))
 }
 
+/// Returns the matrix to transform normals by the `model` matrix.
+///
+/// This is the inverse transpose of the upper left 3x3 part of the `model`.
+/// Unlike the model matrix itself, it keeps normals perpendicular to the surface
+/// under a non-uniform scale. The result is not normalized,
+/// so normalize transformed normals before use in lighting:
+/// ```rust,ignore
+/// let normal = sl::normalize(sl::normal_matrix(model) * vert.nrm);
+/// ```
+pub fn normal_matrix<M>(model: M) -> Ret<Thunk<impl Eval<Vs, Out = types::Mat3>, Vs>, types::Mat3>
+where
+    M: Eval<Vs, Out = types::Mat4>,
+{
+    let m = eval::thunk(model);
+    let xyz = |v: Ret<_, types::Vec4<f32>>| {
+        let v = eval::thunk(v);
+        eval::thunk(vector::vec3(v.clone().x(), v.clone().y(), v.z()))
+    };
+
+    let a = xyz(m.clone().x());
+    let b = xyz(m.clone().y());
+    let c = xyz(m.z());
+
+    // The columns of the inverse transpose are the cross products
+    // of the matrix columns divided by the determinant
+    let bc = eval::thunk(math::cross(b.clone(), c.clone()));
+    let ca = math::cross(c, a.clone());
+    let ab = math::cross(a.clone(), b);
+    let det = math::dot(a, bc.clone());
+    eval::thunk((1. / det) * mat3(bc, ca, ab))
+}
+
+/// Returns the inverse of the matrix.
+///
+/// WGSL has no built-in function for it, so the inverse is computed
+/// from the matrix cofactors. The result is undefined when the matrix
+/// is singular, i.e. its determinant is zero.
+pub const fn inverse<M, E>(m: M) -> Ret<Inverse<M, E>, M::Out>
+where
+    M: Eval<E, Out: Matrix>,
+{
+    Ret::new(Inverse { m, e: PhantomData })
+}
+
+pub struct Inverse<M, E> {
+    m: M,
+    e: PhantomData<E>,
+}
+
+impl<M, E> Eval<E> for Ret<Inverse<M, E>, M::Out>
+where
+    M: Eval<E, Out: Matrix>,
+    E: GetEntry,
+{
+    type Out = M::Out;
+
+    fn eval(self, en: &mut E) -> Expr {
+        let m = self.get().m.eval(en);
+        let one = 1.0_f32.eval(en);
+        let en = en.get_entry();
+        let matty = <M::Out as Matrix>::TYPE;
+        let dims = matty.dims();
+        let elems: Vec<Vec<_>> = (0..dims)
+            .map(|col| {
+                let v = en.access_index(m, col);
+                (0..dims).map(|row| en.access_index(v, row)).collect()
+            })
+            .collect();
+
+        let mut minors = Minors {
+            elems,
+            cache: HashMap::new(),
+        };
+
+        // The inverse is the adjugate matrix divided by the determinant
+        let all = (1 << dims) - 1;
+        let det = minors.det(en, all, all);
+        let inv_det = en.binary(Bi::Div, one, det);
+        let vecty = en.new_type(matty.vector_type().ty());
+        let columns: Exprs = (0..dims)
+            .map(|col| {
+                let column: Exprs = (0..dims)
+                    .map(|row| {
+                        let minor = minors.det(en, all & !(1 << col), all & !(1 << row));
+                        if (row + col) % 2 == 0 {
+                            minor
+                        } else {
+                            en.unary(Un::Neg, minor)
+                        }
+                    })
+                    .collect();
+
+                en.compose(vecty, column)
+            })
+            .collect();
+
+        let ty = en.new_type(matty.ty());
+        let adj = en.compose(ty, columns);
+        en.binary(Bi::Mul, adj, inv_det)
+    }
+}
+
+/// Determinants of the matrix minors.
+///
+/// Minors are identified by bit masks of rows and columns they consist of.
+/// The cache allows to share the same minors between cofactors.
+struct Minors {
+    elems: Vec<Vec<Expr>>,
+    cache: HashMap<(u32, u32), Expr>,
+}
+
+impl Minors {
+    fn det(&mut self, en: &mut Entry, rows: u32, cols: u32) -> Expr {
+        if rows.count_ones() == 1 {
+            let row = rows.trailing_zeros();
+            let col = cols.trailing_zeros();
+            return self.elems[col as usize][row as usize];
+        }
+
+        if let Some(&ex) = self.cache.get(&(rows, cols)) {
+            return ex;
+        }
+
+        // Expand along the first row
+        let first = rows.trailing_zeros();
+        let rest = rows & !(1 << first);
+        let mut det = None;
+        let cols_iter = (0..u32::BITS).filter(|col| cols & (1 << col) != 0);
+        for (n, col) in iter::zip(0.., cols_iter) {
+            let minor = self.det(en, rest, cols & !(1 << col));
+            let elem = self.elems[col as usize][first as usize];
+            let term = en.binary(Bi::Mul, elem, minor);
+            det = Some(match det {
+                None => term,
+                Some(det) if n % 2 == 0 => en.binary(Bi::Add, det, term),
+                Some(det) => en.binary(Bi::Sub, det, term),
+            });
+        }
+
+        let det = det.expect("the minor is not empty");
+        self.cache.insert((rows, cols), det);
+        det
+    }
+}
+
 pub struct NewMat<A, E> {
     a: A,
     e: PhantomData<E>,
@@ -127,7 +274,9 @@ impl IntoMatrix for glam::Mat2 {
     where
         F: FnMut(Self::Vector),
     {
-        self.to_cols_array_2d().map(|v| f(Self::Vector::from(v)));
+        self.to_cols_array_2d()
+            .into_iter()
+            .for_each(|v| f(Self::Vector::from(v)));
     }
 }
 
@@ -138,7 +287,9 @@ impl IntoMatrix for glam::Mat3 {
     where
         F: FnMut(Self::Vector),
     {
-        self.to_cols_array_2d().map(|v| f(Self::Vector::from(v)));
+        self.to_cols_array_2d()
+            .into_iter()
+            .for_each(|v| f(Self::Vector::from(v)));
     }
 }
 
@@ -149,6 +300,8 @@ impl IntoMatrix for glam::Mat4 {
     where
         F: FnMut(Self::Vector),
     {
-        self.to_cols_array_2d().map(|v| f(Self::Vector::from(v)));
+        self.to_cols_array_2d()
+            .into_iter()
+            .for_each(|v| f(Self::Vector::from(v)));
     }
 }
@@ -132,3 +132,47 @@ impl Module {
         Self { cx, nm, wgsl }
     }
 }
+
+/// Writes the shader module as SPIR-V binary.
+///
+/// # Errors
+/// Returns an error if the module is invalid or the SPIR-V writer fails.
+#[cfg(feature = "spirv")]
+pub fn write_spirv(nm: &naga::Module) -> Result<Vec<u32>, SpirvError> {
+    use naga::{
+        back::spv::{self, Options},
+        valid::{Capabilities, ValidationFlags, Validator},
+    };
+
+    let mut validator = Validator::new(ValidationFlags::all(), Capabilities::empty());
+    let info = validator.validate(nm).map_err(SpirvError::Validation)?;
+    spv::write_vec(nm, &info, &Options::default(), None).map_err(SpirvError::Write)
+}
+
+/// An error returned from the [`write_spirv`] function.
+#[cfg(feature = "spirv")]
+#[derive(Debug)]
+pub enum SpirvError {
+    Validation(naga::WithSpan<naga::valid::ValidationError>),
+    Write(naga::back::spv::Error),
+}
+
+#[cfg(feature = "spirv")]
+impl std::fmt::Display for SpirvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Validation(err) => write!(f, "invalid shader: {err}"),
+            Self::Write(err) => write!(f, "spirv writer error: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "spirv")]
+impl std::error::Error for SpirvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Validation(err) => Some(err),
+            Self::Write(err) => Some(err),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use {
+    crate::{
+        convert,
+        eval::{Eval, Expr, GetEntry},
+        math,
+        op::Ret,
+        types,
+    },
+    naga::{ArraySize, Type, TypeInner},
+    std::{
+        f32::consts::{FRAC_1_SQRT_2, SQRT_2},
+        marker::PhantomData,
+        num::NonZeroU32,
+    },
+};
+
+/// Returns a pseudo-random value in the `[0, 1)` range for the given point.
+///
+/// The hash is deterministic, the same point always gives the same value.
+/// It uses only arithmetic without trigonometry, so the result doesn't depend
+/// on the precision of `sin` which differs between backends. The hash of integer
+/// coordinates is a good source of per-cell random values:
+/// ```rust,ignore
+/// let cell = sl::vec2(sl::floor(uv.clone().x()), sl::floor(uv.y()));
+/// let rand = sl::hash(cell);
+/// ```
+pub const fn hash<X, E>(x: X) -> Ret<Noise<X, E>, f32>
+where
+    X: Eval<E, Out = types::Vec2<f32>>,
+{
+    Ret::new(Noise::new(x, Kind::Hash))
+}
+
+/// Returns a value noise in the `[0, 1]` range for the given point.
+///
+/// Random values in the corners of the unit grid cells are smoothly
+/// interpolated across the cells. Scale the point to change the noise frequency.
+pub const fn value_noise<X, E>(x: X) -> Ret<Noise<X, E>, f32>
+where
+    X: Eval<E, Out = types::Vec2<f32>>,
+{
+    Ret::new(Noise::new(x, Kind::Value))
+}
+
+/// Returns a Perlin (gradient) noise in about the `[-1, 1]` range for the given point.
+///
+/// Unlike the [value noise](value_noise) it has no visible grid artifacts,
+/// so it suits natural patterns like clouds or terrain. The noise is zero in the
+/// corners of the unit grid cells. Several octaves can be summed up to get more details:
+/// ```rust,ignore
+/// let uv = sl::thunk(uv);
+/// let n = sl::perlin(uv.clone()) + sl::perlin(uv * 2.) * 0.5;
+/// ```
+pub const fn perlin<X, E>(x: X) -> Ret<Noise<X, E>, f32>
+where
+    X: Eval<E, Out = types::Vec2<f32>>,
+{
+    Ret::new(Noise::new(x, Kind::Perlin))
+}
+
+enum Kind {
+    Hash,
+    Value,
+    Perlin,
+}
+
+pub struct Noise<X, E> {
+    x: X,
+    kind: Kind,
+    e: PhantomData<E>,
+}
+
+impl<X, E> Noise<X, E> {
+    const fn new(x: X, kind: Kind) -> Self {
+        Self {
+            x,
+            kind,
+            e: PhantomData,
+        }
+    }
+}
+
+impl<X, E> Eval<E> for Ret<Noise<X, E>, f32>
+where
+    X: Eval<E, Out = types::Vec2<f32>>,
+    E: GetEntry,
+{
+    type Out = f32;
+
+    fn eval(self, en: &mut E) -> Expr {
+        let Noise { x, kind, .. } = self.get();
+        let p = x.eval(en);
+        let px = value(en.get_entry().access_index(p, 0));
+        let py = value(en.get_entry().access_index(p, 1));
+        match kind {
+            Kind::Hash => hash12(en, px, py).get(),
+            Kind::Value => value_noise2(en, px, py).get(),
+            Kind::Perlin => perlin2(en, px, py).get(),
+        }
+    }
+}
+
+/// An evaluated scalar which can be used many times.
+type Value = Ret<Expr, f32>;
+
+const fn value(ex: Expr) -> Value {
+    Ret::new(ex)
+}
+
+fn eval<A, E>(en: &mut E, a: A) -> Value
+where
+    A: Eval<E, Out = f32>,
+{
+    value(a.eval(en))
+}
+
+/// The "hash without sine" function by Dave Hoskins
+/// written for scalars, since the third component of the point equals the first one.
+fn hash12<E>(en: &mut E, x: Value, y: Value) -> Value
+where
+    E: GetEntry,
+{
+    let a = eval(en, math::fract(x * 0.1031));
+    let b = eval(en, math::fract(y * 0.1031));
+    let d = eval(en, a * (b + 33.33) + b * (a + 33.33) + a * (a + 33.33));
+    let a = eval(en, a + d);
+    let b = eval(en, b + d);
+    eval(en, math::fract((a + b) * a))
+}
+
+struct Cell {
+    ix: Value,
+    iy: Value,
+    fx: Value,
+    fy: Value,
+}
+
+impl Cell {
+    fn new<E>(en: &mut E, x: Value, y: Value) -> Self
+    where
+        E: GetEntry,
+    {
+        let ix = eval(en, math::floor(x));
+        let iy = eval(en, math::floor(y));
+        Self {
+            ix,
+            iy,
+            fx: eval(en, x - ix),
+            fy: eval(en, y - iy),
+        }
+    }
+
+    /// Calls the function for each corner of the cell with the corner
+    /// coordinates and the offset of the point from the corner.
+    fn corners<E, F>(&self, en: &mut E, mut f: F) -> [Value; 4]
+    where
+        E: GetEntry,
+        F: FnMut(&mut E, [Value; 2], [Value; 2]) -> Value,
+    {
+        let side = |en: &mut E, i: Value, f: Value, far: bool| {
+            if far {
+                [eval(en, i + 1.), eval(en, f - 1.)]
+            } else {
+                [i, f]
+            }
+        };
+
+        [(false, false), (true, false), (false, true), (true, true)].map(|(fx, fy)| {
+            let [cx, dx] = side(en, self.ix, self.fx, fx);
+            let [cy, dy] = side(en, self.iy, self.fy, fy);
+            f(en, [cx, cy], [dx, dy])
+        })
+    }
+}
+
+fn interpolate<E>(en: &mut E, [a, b, c, d]: [Value; 4], ux: Value, uy: Value) -> Value
+where
+    E: GetEntry,
+{
+    let ab = eval(en, math::mix(a, b, ux));
+    let cd = eval(en, math::mix(c, d, ux));
+    eval(en, math::mix(ab, cd, uy))
+}
+
+fn value_noise2<E>(en: &mut E, x: Value, y: Value) -> Value
+where
+    E: GetEntry,
+{
+    let cell = Cell::new(en, x, y);
+    let corners = cell.corners(en, |en, [cx, cy], _| hash12(en, cx, cy));
+
+    // Cubic Hermite curve
+    let ux = eval(en, cell.fx * cell.fx * (3. - cell.fx * 2.));
+    let uy = eval(en, cell.fy * cell.fy * (3. - cell.fy * 2.));
+    interpolate(en, corners, ux, uy)
+}
+
+/// Unit gradients of the Perlin noise, the hash picks one of them.
+const GRADIENTS: [glam::Vec2; 8] = [
+    glam::Vec2::X,
+    glam::Vec2::NEG_X,
+    glam::Vec2::Y,
+    glam::Vec2::NEG_Y,
+    glam::Vec2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    glam::Vec2::new(-FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    glam::Vec2::new(FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+    glam::Vec2::new(-FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+];
+
+/// Stores the gradient table in a local variable, so it can be indexed dynamically.
+fn gradients<E>(en: &mut E) -> Expr
+where
+    E: GetEntry,
+{
+    let vectors = GRADIENTS.map(|v| v.eval(en));
+    let en = en.get_entry();
+    let base = en.new_type(types::VectorType::Vec2f.ty());
+    let ty = en.new_type(Type {
+        name: None,
+        inner: TypeInner::Array {
+            base,
+            size: ArraySize::Constant(NonZeroU32::new(GRADIENTS.len() as u32).expect("non zero")),
+            stride: size_of::<glam::Vec2>() as u32,
+        },
+    });
+
+    let table = en.compose(ty, vectors.into_iter().collect());
+    en.store_local(ty, table)
+}
+
+fn perlin2<E>(en: &mut E, x: Value, y: Value) -> Value
+where
+    E: GetEntry,
+{
+    let cell = Cell::new(en, x, y);
+    let table = gradients(en);
+    let corners = cell.corners(en, |en, [cx, cy], [dx, dy]| {
+        // A gradient picked by the low bits of the hash dotted with the offset from the corner
+        let rand = hash12(en, cx, cy);
+        let index = (convert::u32(rand * 256.) % GRADIENTS.len() as u32).eval(en);
+        let [gx, gy] = {
+            let en = en.get_entry();
+            let ptr = en.access(table, index);
+            let grad = en.load(ptr);
+            [0, 1].map(|i| value(en.access_index(grad, i)))
+        };
+
+        eval(en, gx * dx + gy * dy)
+    });
+
+    // Quintic curve with continuous second derivative
+    let fade = |en: &mut E, f: Value| eval(en, f * f * f * (f * (f * 6. - 15.) + 10.));
+    let ux = fade(en, cell.fx);
+    let uy = fade(en, cell.fy);
+    let n = interpolate(en, corners, ux, uy);
+    eval(en, n * SQRT_2)
+}
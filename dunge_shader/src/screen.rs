@@ -0,0 +1,27 @@
+use crate::{
+    context::Index,
+    convert,
+    eval::{Eval, Vs},
+    types, vector,
+};
+
+/// Returns the clip space position of a full-screen triangle vertex.
+///
+/// The triangle is generated from the vertex index, so it doesn't need a mesh.
+/// It covers the whole screen and should be drawn with exactly 3 points.
+pub fn fullscreen_place(Index(idx): Index) -> impl Eval<Vs, Out = types::Vec4<f32>> {
+    let x = convert::f32(idx % 2);
+    let y = convert::f32(idx / 2);
+    vector::vec4(x * 4. - 1., y * 4. - 1., 0., 1.)
+}
+
+/// Returns the texture coordinates of a full-screen triangle vertex.
+///
+/// The coordinates match the [position](fullscreen_place) of the vertex,
+/// so the visible part of the triangle maps to the `[0, 1]` range
+/// with the top left corner at the origin.
+pub fn fullscreen_uv(Index(idx): Index) -> impl Eval<Vs, Out = types::Vec2<f32>> {
+    let x = convert::f32(idx % 2);
+    let y = convert::f32(idx / 2);
+    vector::vec2(x * 2., 1. - y * 2.)
+}
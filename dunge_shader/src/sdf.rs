@@ -0,0 +1,28 @@
+use crate::{
+    eval::{Eval, Fs},
+    math,
+};
+
+/// Returns the coverage of a signed distance field sample.
+///
+/// An SDF atlas stores the distance to the nearest glyph edge in a color channel
+/// of a normalized texture, for example, in the alpha channel of an `RgbAlpha`
+/// texture. It should be sampled with a linear filter. The value `0.5` lies
+/// exactly on the edge, greater values are inside the glyph and lesser ones are outside.
+/// The `smoothing` sets the half width of the transition around the edge in the same units.
+/// Small values give sharp edges, large values blur them. When text is scaled,
+/// a good smoothing is about `0.25 / spread / scale`, where the `spread` is the
+/// distance range in texels encoded in the atlas and the `scale` is how much
+/// a texel of the atlas is magnified on the screen.
+///
+/// The result can be used as the alpha value of the fragment:
+/// ```rust,ignore
+/// let dist = sl::texture_sample(atlas.tex, atlas.sam, sl::fragment(vert.uv)).w();
+/// let alpha = sl::sdf_alpha(dist, 0.05);
+/// ```
+pub fn sdf_alpha<D>(dist: D, smoothing: f32) -> impl Eval<Fs, Out = f32>
+where
+    D: Eval<Fs, Out = f32>,
+{
+    math::smoothstep(0.5 - smoothing, 0.5 + smoothing, dist)
+}
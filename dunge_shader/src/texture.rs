@@ -2,9 +2,10 @@ use {
     crate::{
         eval::{Eval, Expr, Fs, GetEntry},
         op::Ret,
-        types,
+        types::{self, ScalarType},
     },
     naga::{Expression, SampleLevel},
+    std::marker::PhantomData,
 };
 
 type Tex<T, S, C, O> = Ret<Samp<T, S, C>, types::Vec4<O>>;
@@ -19,6 +20,76 @@ where
     Ret::new(Samp { tex, sam, crd })
 }
 
+/// Performs the [`textureSampleLevel`](https://www.w3.org/TR/WGSL/#texturesamplelevel) function.
+///
+/// Samples the texture at the explicit mip level. Unlike the
+/// [`texture_sample`] function, it can be used in the vertex stage.
+pub const fn texture_sample_level<T, S, C, L, E>(
+    tex: T,
+    sam: S,
+    crd: C,
+    lvl: L,
+) -> Ret<SampLevel<T, S, C, L, E>, types::Vec4<f32>>
+where
+    T: Eval<E, Out = types::Texture2d<f32>>,
+    S: Eval<E, Out = types::Sampler>,
+    C: Eval<E, Out = types::Vec2<f32>>,
+    L: Eval<E, Out = f32>,
+{
+    Ret::new(SampLevel {
+        tex,
+        sam,
+        crd,
+        lvl,
+        e: PhantomData,
+    })
+}
+
+/// Performs the [`textureSampleBias`](https://www.w3.org/TR/WGSL/#texturesamplebias) function.
+///
+/// Samples the texture with the `bias` added to the mip level
+/// before the sampling. The bias is clamped to the range from `-16` to `15.99`.
+pub const fn texture_sample_bias<T, S, C, B>(
+    tex: T,
+    sam: S,
+    crd: C,
+    bias: B,
+) -> Ret<SampBias<T, S, C, B>, types::Vec4<f32>>
+where
+    T: Eval<Fs, Out = types::Texture2d<f32>>,
+    S: Eval<Fs, Out = types::Sampler>,
+    C: Eval<Fs, Out = types::Vec2<f32>>,
+    B: Eval<Fs, Out = f32>,
+{
+    Ret::new(SampBias {
+        tex,
+        sam,
+        crd,
+        bias,
+    })
+}
+
+/// Performs the [`textureLoad`](https://www.w3.org/TR/WGSL/#textureload) function.
+///
+/// Reads a single texel by integer coordinates from the mip level without a sampler.
+pub const fn texture_load<T, C, L, F, E>(
+    tex: T,
+    crd: C,
+    lvl: L,
+) -> Ret<Load<T, C, L, E>, types::Vec4<F>>
+where
+    T: Eval<E, Out = types::Texture2d<F>>,
+    C: Eval<E, Out = types::Vec2<u32>>,
+    L: Eval<E, Out = u32>,
+{
+    Ret::new(Load {
+        tex,
+        crd,
+        lvl,
+        e: PhantomData,
+    })
+}
+
 pub struct Samp<T, S, C> {
     tex: T,
     sam: S,
@@ -39,16 +110,93 @@ where
             tex: tex.eval(en),
             sam: sam.eval(en),
             crd: crd.eval(en),
+            lvl: Level::Auto,
+        };
+
+        en.get_entry().sample(ex)
+    }
+}
+
+pub struct SampLevel<T, S, C, L, E> {
+    tex: T,
+    sam: S,
+    crd: C,
+    lvl: L,
+    e: PhantomData<E>,
+}
+
+impl<T, S, C, L, E> Eval<E> for Ret<SampLevel<T, S, C, L, E>, types::Vec4<f32>>
+where
+    T: Eval<E, Out = types::Texture2d<f32>>,
+    S: Eval<E, Out = types::Sampler>,
+    C: Eval<E, Out = types::Vec2<f32>>,
+    L: Eval<E, Out = f32>,
+    E: GetEntry,
+{
+    type Out = types::Vec4<f32>;
+
+    fn eval(self, en: &mut E) -> Expr {
+        let SampLevel {
+            tex, sam, crd, lvl, ..
+        } = self.get();
+
+        let ex = Sampled {
+            tex: tex.eval(en),
+            sam: sam.eval(en),
+            crd: crd.eval(en),
+            lvl: Level::Exact(lvl.eval(en)),
+        };
+
+        en.get_entry().sample(ex)
+    }
+}
+
+pub struct SampBias<T, S, C, B> {
+    tex: T,
+    sam: S,
+    crd: C,
+    bias: B,
+}
+
+impl<T, S, C, B> Eval<Fs> for Ret<SampBias<T, S, C, B>, types::Vec4<f32>>
+where
+    T: Eval<Fs, Out = types::Texture2d<f32>>,
+    S: Eval<Fs, Out = types::Sampler>,
+    C: Eval<Fs, Out = types::Vec2<f32>>,
+    B: Eval<Fs, Out = f32>,
+{
+    type Out = types::Vec4<f32>;
+
+    fn eval(self, en: &mut Fs) -> Expr {
+        let SampBias {
+            tex,
+            sam,
+            crd,
+            bias,
+        } = self.get();
+
+        let ex = Sampled {
+            tex: tex.eval(en),
+            sam: sam.eval(en),
+            crd: crd.eval(en),
+            lvl: Level::Bias(bias.eval(en)),
         };
 
         en.get_entry().sample(ex)
     }
 }
 
+enum Level {
+    Auto,
+    Exact(Expr),
+    Bias(Expr),
+}
+
 pub(crate) struct Sampled {
     tex: Expr,
     sam: Expr,
     crd: Expr,
+    lvl: Level,
 }
 
 impl Sampled {
@@ -60,8 +208,60 @@ impl Sampled {
             coordinate: self.crd.get(),
             array_index: None,
             offset: None,
-            level: SampleLevel::Auto,
+            level: match self.lvl {
+                Level::Auto => SampleLevel::Auto,
+                Level::Exact(lvl) => SampleLevel::Exact(lvl.get()),
+                Level::Bias(bias) => SampleLevel::Bias(bias.get()),
+            },
             depth_ref: None,
         }
     }
 }
+
+pub struct Load<T, C, L, E> {
+    tex: T,
+    crd: C,
+    lvl: L,
+    e: PhantomData<E>,
+}
+
+impl<T, C, L, F, E> Eval<E> for Ret<Load<T, C, L, E>, types::Vec4<F>>
+where
+    T: Eval<E, Out = types::Texture2d<F>>,
+    C: Eval<E, Out = types::Vec2<u32>>,
+    L: Eval<E, Out = u32>,
+    E: GetEntry,
+{
+    type Out = types::Vec4<F>;
+
+    fn eval(self, en: &mut E) -> Expr {
+        let Load { tex, crd, lvl, .. } = self.get();
+        let tex = tex.eval(en);
+        let crd = crd.eval(en);
+
+        // The level index must be signed in naga
+        let lvl = lvl.eval(en);
+        let lvl = en.get_entry().convert(lvl, ScalarType::Sint);
+        let ex = Loaded { tex, crd, lvl };
+
+        en.get_entry().load_texel(ex)
+    }
+}
+
+pub(crate) struct Loaded {
+    tex: Expr,
+    crd: Expr,
+    lvl: Expr,
+}
+
+impl Loaded {
+    pub fn expr(self) -> Expression {
+        Expression::ImageLoad {
+            image: self.tex.get(),
+            coordinate: self.crd.get(),
+            array_index: None,
+            sample: None,
+            level: Some(self.lvl.get()),
+        }
+    }
+}
@@ -13,6 +13,18 @@ pub enum ValueType {
 }
 
 impl ValueType {
+    /// Returns the size of the type in the uniform address space in bytes.
+    ///
+    /// Matrix columns follow the WGSL layout rules,
+    /// so each column of a `mat3x3` takes 16 bytes.
+    pub const fn uniform_size(self) -> usize {
+        match self {
+            Self::Scalar(_) => 4,
+            Self::Vector(v) => v.size(),
+            Self::Matrix(v) => v.dims() as usize * v.column_stride(),
+        }
+    }
+
     pub(crate) const fn ty(self) -> Type {
         match self {
             Self::Scalar(v) => v.ty(),
@@ -81,6 +93,14 @@ impl Number for f32 {}
 impl Number for i32 {}
 impl Number for u32 {}
 
+/// The trait for floating point scalar and vector types.
+pub trait Float: Value {}
+
+impl Float for f32 {}
+impl Float for Vec2<f32> {}
+impl Float for Vec3<f32> {}
+impl Float for Vec4<f32> {}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum ScalarType {
     Float,
@@ -136,6 +156,11 @@ impl VectorType {
         }
     }
 
+    /// Returns the size of the vector in bytes.
+    pub const fn size(self) -> usize {
+        self.dims() * 4
+    }
+
     pub(crate) const fn ty(self) -> Type {
         match self {
             Self::Vec2f => VEC2F,
@@ -279,6 +304,14 @@ impl MatrixType {
         }
     }
 
+    /// Returns the distance between matrix columns in bytes.
+    pub const fn column_stride(self) -> usize {
+        match self {
+            Self::Mat2 => 8,
+            Self::Mat3 | Self::Mat4 => 16,
+        }
+    }
+
     pub const fn vector_type(self) -> VectorType {
         match self {
             Self::Mat2 => VectorType::Vec2f,
@@ -362,6 +395,7 @@ pub enum MemberType {
     Scalar(ScalarType),
     Vector(VectorType),
     Matrix(MatrixType),
+    Dynamic(ValueType),
     Tx2df,
     Sampl,
 }
@@ -376,7 +410,10 @@ impl MemberType {
     }
 
     pub const fn is_value(self) -> bool {
-        matches!(self, Self::Scalar(_) | Self::Vector(_) | Self::Matrix(_))
+        matches!(
+            self,
+            Self::Scalar(_) | Self::Vector(_) | Self::Matrix(_) | Self::Dynamic(_),
+        )
     }
 
     pub(crate) const fn ty(self) -> Type {
@@ -384,6 +421,7 @@ impl MemberType {
             Self::Scalar(v) => v.ty(),
             Self::Vector(v) => v.ty(),
             Self::Matrix(v) => v.ty(),
+            Self::Dynamic(v) => v.ty(),
             Self::Tx2df => TEXTURE2DF,
             Self::Sampl => SAMPLER,
         }
@@ -391,7 +429,9 @@ impl MemberType {
 
     pub(crate) const fn address_space(self) -> AddressSpace {
         match self {
-            Self::Scalar(_) | Self::Vector(_) | Self::Matrix(_) => AddressSpace::Uniform,
+            Self::Scalar(_) | Self::Vector(_) | Self::Matrix(_) | Self::Dynamic(_) => {
+                AddressSpace::Uniform
+            }
             Self::Tx2df | Self::Sampl => AddressSpace::Handle,
         }
     }
@@ -177,6 +177,81 @@ where
     }
 }
 
+/// Constructs a 3D vector from scalars and smaller vectors.
+///
+/// The parts are passed as a tuple, for example `(x, v.xy())`,
+/// and their components must sum up to three. It's checked at compile time
+/// with the [`Layout`] trait.
+pub const fn vec3_parts<P, S, E>(parts: P) -> Ret<NewVec<P, E>, types::Vec3<S>>
+where
+    P: Parts<E, Types: Layout<Vector = types::Vec3<S>>>,
+{
+    Ret::new(NewVec::new(parts))
+}
+
+/// Constructs a 4D vector from scalars and smaller vectors.
+///
+/// The parts are passed as a tuple, for example `(x, v.xy(), 1.)`,
+/// and their components must sum up to four. It's checked at compile time
+/// with the [`Layout`] trait.
+pub const fn vec4_parts<P, S, E>(parts: P) -> Ret<NewVec<P, E>, types::Vec4<S>>
+where
+    P: Parts<E, Types: Layout<Vector = types::Vec4<S>>>,
+{
+    Ret::new(NewVec::new(parts))
+}
+
+/// A tuple of vector parts.
+pub trait Parts<E> {
+    /// The tuple of part types.
+    type Types;
+}
+
+macro_rules! impl_parts {
+    ($($t:ident),*) => {
+        impl<$($t),*, E> Parts<E> for ($($t),*,)
+        where
+            $(
+                $t: Eval<E>,
+            )*
+        {
+            type Types = ($($t::Out),*,);
+        }
+    };
+}
+
+impl_parts!(X, Y);
+impl_parts!(X, Y, Z);
+impl_parts!(X, Y, Z, W);
+
+/// A combination of part types which forms a vector.
+pub trait Layout {
+    /// The vector type.
+    type Vector;
+}
+
+macro_rules! impl_layout {
+    ($v:ident => $($t:ty),*) => {
+        impl<S> Layout for ($($t),*,)
+        where
+            S: Scalar,
+        {
+            type Vector = types::$v<S>;
+        }
+    };
+}
+
+impl_layout!(Vec3 => S, types::Vec2<S>);
+impl_layout!(Vec3 => types::Vec2<S>, S);
+impl_layout!(Vec3 => S, S, S);
+impl_layout!(Vec4 => S, types::Vec3<S>);
+impl_layout!(Vec4 => types::Vec3<S>, S);
+impl_layout!(Vec4 => types::Vec2<S>, types::Vec2<S>);
+impl_layout!(Vec4 => S, S, types::Vec2<S>);
+impl_layout!(Vec4 => S, types::Vec2<S>, S);
+impl_layout!(Vec4 => types::Vec2<S>, S, S);
+impl_layout!(Vec4 => S, S, S, S);
+
 pub const fn vec4_concat<A, B, S, E>(a: A, b: B) -> Ret<Compose<A, B>, types::Vec4<S>>
 where
     A: Eval<E, Out = types::Vec2<S>>,
@@ -241,7 +316,7 @@ impl IntoVector for glam::Vec2 {
     where
         F: FnMut(Self::Scalar),
     {
-        self.to_array().map(f);
+        self.to_array().into_iter().for_each(f);
     }
 }
 
@@ -252,7 +327,7 @@ impl IntoVector for glam::Vec3 {
     where
         F: FnMut(Self::Scalar),
     {
-        self.to_array().map(f);
+        self.to_array().into_iter().for_each(f);
     }
 }
 
@@ -263,7 +338,7 @@ impl IntoVector for glam::Vec3A {
     where
         F: FnMut(Self::Scalar),
     {
-        self.to_array().map(f);
+        self.to_array().into_iter().for_each(f);
     }
 }
 
@@ -274,7 +349,7 @@ impl IntoVector for glam::Vec4 {
     where
         F: FnMut(Self::Scalar),
     {
-        self.to_array().map(f);
+        self.to_array().into_iter().for_each(f);
     }
 }
 
@@ -285,7 +360,7 @@ impl IntoVector for glam::IVec2 {
     where
         F: FnMut(Self::Scalar),
     {
-        self.to_array().map(f);
+        self.to_array().into_iter().for_each(f);
     }
 }
 
@@ -296,7 +371,7 @@ impl IntoVector for glam::IVec3 {
     where
         F: FnMut(Self::Scalar),
     {
-        self.to_array().map(f);
+        self.to_array().into_iter().for_each(f);
     }
 }
 
@@ -307,7 +382,7 @@ impl IntoVector for glam::IVec4 {
     where
         F: FnMut(Self::Scalar),
     {
-        self.to_array().map(f);
+        self.to_array().into_iter().for_each(f);
     }
 }
 
@@ -318,7 +393,7 @@ impl IntoVector for glam::UVec2 {
     where
         F: FnMut(Self::Scalar),
     {
-        self.to_array().map(f);
+        self.to_array().into_iter().for_each(f);
     }
 }
 
@@ -329,7 +404,7 @@ impl IntoVector for glam::UVec3 {
     where
         F: FnMut(Self::Scalar),
     {
-        self.to_array().map(f);
+        self.to_array().into_iter().for_each(f);
     }
 }
 
@@ -340,6 +415,6 @@ impl IntoVector for glam::UVec4 {
     where
         F: FnMut(Self::Scalar),
     {
-        self.to_array().map(f);
+        self.to_array().into_iter().for_each(f);
     }
 }
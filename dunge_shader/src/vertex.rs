@@ -30,9 +30,43 @@ use {
 /// }
 /// ```
 ///
+/// # Field types
+/// Fields are plain `[f32; N]` arrays or the `glam` vectors `Vec2`, `Vec3`
+/// and `Vec4`, both kinds can be mixed in one struct. Fields are packed
+/// without padding, so keep in mind that `Vec4` may be 16 byte aligned:
+/// the shader creation panics if the vertex type has padding.
+///
+/// # Streams
+/// By default, all fields of a vertex are stored interleaved in one buffer.
+/// To store some fields in a separate buffer, mark them with the `#[stream(n)]`
+/// attribute. Streams are numbered sequentially from zero and fields without
+/// the attribute belong to the stream zero:
+/// ```rust,ignore
+/// #[repr(C)]
+/// #[derive(Vertex)]
+/// struct Vert {
+///     pos: [f32; 3],
+///     #[stream(1)]
+///     col: [f32; 3],
+/// }
+/// ```
+///
 pub unsafe trait Vertex {
     type Projection: Projection + 'static;
     const DEF: Define<VectorType>;
+
+    /// The buffer stream of each field.
+    ///
+    /// If empty, all fields belong to the stream zero.
+    const STREAMS: Define<u32> = Define::new(&[]);
+}
+
+/// Returns the number of buffer streams of the vertex type.
+pub fn stream_count<V>() -> usize
+where
+    V: Vertex,
+{
+    V::STREAMS.into_iter().max().map_or(1, |n| n as usize + 1)
 }
 
 /// Maps the slice of vertices to the slice of bytes.
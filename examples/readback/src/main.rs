@@ -0,0 +1,100 @@
+type Error = Box<dyn std::error::Error>;
+
+fn main() {
+    env_logger::init();
+    if let Err(err) = helpers::block_on(run()) {
+        eprintln!("error: {err}");
+    }
+}
+
+async fn run() -> Result<(), Error> {
+    use {
+        dunge::{color::Rgba, glam::Vec2, prelude::*, sl},
+        std::{
+            sync::{
+                atomic::{AtomicBool, Ordering},
+                Arc,
+            },
+            thread,
+        },
+    };
+
+    // Create a shader of a full-screen gradient
+    let gradient = |idx: sl::Index| sl::Out {
+        place: sl::fullscreen_place(idx),
+        color: sl::vec4_concat(sl::fragment(sl::fullscreen_uv(idx)), Vec2::new(0., 1.)),
+    };
+
+    let cx = dunge::context().await?;
+    let shader = cx.make_shader(gradient);
+
+    // Create an offscreen texture to read it back
+    let size = (64, 64);
+    let texture = {
+        let data = TextureData::empty(size, Format::RgbAlpha)?
+            .with_draw()
+            .with_copy();
+
+        cx.make_texture(data)
+    };
+
+    let buffer = Arc::new(cx.make_copy_buffer(size));
+    let reading = Arc::new(AtomicBool::new(false));
+    let make_handler = move |cx: &Context, view: &View| {
+        let offscreen = cx.make_layer(&shader, Format::RgbAlpha);
+        let layer = cx.make_layer(&shader, view.format());
+        let cx = cx.clone();
+        let upd = move |ctrl: &Control| {
+            for key in ctrl.pressed_keys() {
+                match key.code {
+                    KeyCode::Escape => return Then::Close,
+                    // Skip the key while the previous readback is in progress
+                    KeyCode::Space if !reading.swap(true, Ordering::Relaxed) => {
+                        let draw = dunge::draw(|mut frame| {
+                            frame
+                                .layer(&offscreen, Options::default())
+                                .bind_empty()
+                                .draw_points(3);
+                            frame.copy_texture(&buffer, &texture);
+                        });
+
+                        cx.draw_to(&texture, draw);
+
+                        // Wait for the data on another thread, so the event loop keeps
+                        // running and polls the device until the mapping is ready.
+                        // Mapping inside this handler would block the loop instead
+                        let cx = cx.clone();
+                        let buffer = Arc::clone(&buffer);
+                        let reading = Arc::clone(&reading);
+                        thread::spawn(move || {
+                            let mapped = helpers::block_on({
+                                let (tx, rx) = helpers::oneshot();
+                                cx.map_view(buffer.view(), tx, rx)
+                            });
+
+                            let (width, _) = buffer.size();
+                            let center = (size.1 / 2 * width + size.0 / 2) as usize;
+                            println!("the center pixel is {:?}", mapped.data()[center]);
+                            drop(mapped);
+                            reading.store(false, Ordering::Relaxed);
+                        });
+                    }
+                    _ => {}
+                }
+            }
+
+            Then::Run
+        };
+
+        let draw = move |mut frame: Frame| {
+            let bg = Rgba::from_bytes([0, 0, 0, !0]);
+            frame.layer(&layer, bg).bind_empty().draw_points(3);
+        };
+
+        dunge::update(upd, draw)
+    };
+
+    println!("press space to read back the frame");
+    dunge::window().run_local(cx, dunge::make(make_handler))?;
+    Ok(())
+}
@@ -4,10 +4,10 @@ pub async fn run(ws: dunge::window::WindowState) -> Result<(), Error> {
     use dunge::{
         bind::UniqueBinding,
         color::Rgba,
-        glam::{Vec2, Vec4},
+        glam::Vec4,
         group::BoundTexture,
         prelude::*,
-        sl::{Groups, InVertex, Index, Out},
+        sl::{Groups, Index, Out},
         texture::{DrawTexture, Filter, Sampler},
         uniform::Uniform,
         Format,
@@ -31,10 +31,6 @@ pub async fn run(ws: dunge::window::WindowState) -> Result<(), Error> {
         }
     };
 
-    #[repr(C)]
-    #[derive(Vertex)]
-    struct Screen([f32; 2], [f32; 2]);
-
     #[derive(Group)]
     struct Map<'a> {
         tex: BoundTexture<'a>,
@@ -42,10 +38,10 @@ pub async fn run(ws: dunge::window::WindowState) -> Result<(), Error> {
         stp: &'a Uniform<[f32; 2]>,
     }
 
-    let screen = |vert: InVertex<Screen>, Groups(map): Groups<Map>| Out {
-        place: sl::vec4_concat(vert.0, Vec2::new(0., 1.)),
+    let screen = |idx: Index, Groups(map): Groups<Map>| Out {
+        place: sl::fullscreen_place(idx),
         color: {
-            let s = sl::thunk(sl::fragment(vert.1));
+            let s = sl::thunk(sl::fragment(sl::fullscreen_uv(idx)));
             let tex = || map.tex.clone();
             let sam = || map.sam.clone();
             let stp = || map.stp.clone();
@@ -109,20 +105,6 @@ pub async fn run(ws: dunge::window::WindowState) -> Result<(), Error> {
         (binder.into_binding(), handler)
     };
 
-    let screen_mesh = {
-        let verts = const {
-            [[
-                Screen([-1., -1.], [0., 1.]),
-                Screen([1., -1.], [1., 1.]),
-                Screen([1., 1.], [1., 0.]),
-                Screen([-1., 1.], [0., 0.]),
-            ]]
-        };
-
-        let data = MeshData::from_quads(&verts)?;
-        cx.make_mesh(&data)
-    };
-
     struct State<R> {
         cx: Context,
         render_buf: R,
@@ -137,7 +119,7 @@ pub async fn run(ws: dunge::window::WindowState) -> Result<(), Error> {
 
     let make_handler = move |cx: &Context, view: &View| {
         let triangle_layer = cx.make_layer(&triangle_shader, Format::SrgbAlpha);
-        let screen_layer = cx.make_layer(&screen_shader, view.format());
+        let screen_layer = cx.make_fullscreen_layer(&screen_shader, view.format());
 
         let upd = move |state: &mut State<_>, ctrl: &Control| {
             for key in ctrl.pressed_keys() {
@@ -146,21 +128,23 @@ pub async fn run(ws: dunge::window::WindowState) -> Result<(), Error> {
                 }
             }
 
+            let mut updates = state.cx.begin_updates();
             if let Some(size) = ctrl.resized() {
                 state.render_buf = make_render_buf(&state.cx, size);
                 let buf_size = state.render_buf.draw_texture().size();
-                stp.update(&state.cx, make_stp(buf_size));
+                updates.update(&stp, make_stp(buf_size));
                 let map = Map {
                     tex: BoundTexture::new(&state.render_buf),
                     sam: &sam,
                     stp: &stp,
                 };
 
-                dunge::then!(state.cx.update_group(&mut state.bind_map, &handler, &map));
+                dunge::then!(state.bind_map.rebind(&state.cx, &handler, &map));
             }
 
             r += ctrl.delta_time().as_secs_f32() * 0.5;
-            uniform.update(&state.cx, r);
+            updates.update(&uniform, r);
+            updates.submit();
             Then::Run
         };
 
@@ -178,7 +162,7 @@ pub async fn run(ws: dunge::window::WindowState) -> Result<(), Error> {
             frame
                 .layer(&screen_layer, Options::default())
                 .bind(&state.bind_map)
-                .draw(&screen_mesh);
+                .draw_points(3);
         };
 
         dunge::update_with_state(state, upd, draw)
@@ -3,7 +3,7 @@ type Error = Box<dyn std::error::Error>;
 pub async fn run(ws: dunge::window::WindowState) -> Result<(), Error> {
     use dunge::{
         color::Rgba,
-        glam::Vec4,
+        glam::{Vec2, Vec4},
         prelude::*,
         sl::{Groups, Index, Out},
         uniform::Uniform,
@@ -18,10 +18,15 @@ pub async fn run(ws: dunge::window::WindowState) -> Result<(), Error> {
         let color = const { Vec4::new(1., 0.4, 0.8, 1.) };
         let third = const { consts::TAU / 3. };
 
-        let i = sl::thunk(sl::f32(idx) * third + offset.0);
+        let i = sl::thunk(sl::f32(idx) * third + offset.0.clone());
+        let pos = sl::thunk(sl::vec2(sl::cos(i.clone()), sl::sin(i)));
+
+        // Shade the triangle with an animated noise field
+        let uv = sl::fragment(pos.clone() * 3. + sl::splat_vec2(offset.0));
+        let shade = sl::perlin(uv) * 0.25 + 0.75;
         Out {
-            place: sl::vec4(sl::cos(i.clone()), sl::sin(i), 0., 1.),
-            color,
+            place: sl::vec4_concat(pos, Vec2::new(0., 1.)),
+            color: color * shade,
         }
     };
 